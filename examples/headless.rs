@@ -0,0 +1,52 @@
+//! Drive the emulator from a custom render loop, without the terminal UI
+//!
+//! Runs a ROM (or a built-in demo) for a second of emulated frames and prints
+//! the framebuffer with plain text.
+//!
+//! ```sh
+//! cargo run --example headless [-- path/to/rom.ch8]
+//! ```
+
+use joe::Emulator;
+use std::time::Duration;
+
+/// CPU cycles executed per 60Hz frame (~600 instructions per second)
+const CYCLES_PER_FRAME: usize = 10;
+
+/// Draws "C8" with the built-in font
+const DEMO_ROM: [u8; 18] = [
+    0x60, 0x18, // LD V0, 24  (x)
+    0x61, 0x0D, // LD V1, 13  (y)
+    0x62, 0x0C, // LD V2, 0xC
+    0xF2, 0x29, // LD F, V2
+    0xD0, 0x15, // DRW V0, V1, 5
+    0x70, 0x08, // ADD V0, 8
+    0x62, 0x08, // LD V2, 0x8
+    0xF2, 0x29, // LD F, V2
+    0xD0, 0x15, // DRW V0, V1, 5
+];
+
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    let mut emulator = Emulator::with_defaults();
+    match std::env::args().nth(1) {
+        Some(path) => emulator.load_rom(&std::fs::read(path)?)?,
+        None => {
+            // Append JP to self so the demo spins once drawn
+            let mut rom = DEMO_ROM.to_vec();
+            let spin = 0x200 + rom.len() as u16;
+            rom.extend_from_slice(&(0x1000 | spin).to_be_bytes());
+            emulator.load_rom(&rom)?;
+        }
+    }
+
+    for _ in 0..60 {
+        emulator.run_cycles(CYCLES_PER_FRAME)?;
+        std::thread::sleep(Duration::from_secs(1) / 60);
+    }
+
+    for row in emulator.frame_buffer() {
+        let line: String = row.iter().map(|&lit| if lit { '█' } else { ' ' }).collect();
+        println!("{}", line.trim_end());
+    }
+    Ok(())
+}
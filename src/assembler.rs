@@ -0,0 +1,381 @@
+//! Simple CHIP-8 assembler
+//!
+//! Turns the mnemonic syntax produced by [`Instruction::mnemonic`] (and so by
+//! the disassembler) back into big-endian opcodes, one instruction per line.
+//! Numeric operands are hexadecimal, exactly as the disassembler prints them
+//! (`LD V3, 42` loads 0x42, `JP 234` jumps to 0x234); an optional `0x` prefix
+//! is accepted. Everything after a `;` is a comment and blank lines are skipped.
+//! `DW NNNN` emits a raw data word, matching the disassembler's data entries.
+//!
+//! A line may start with a `NAME:` label, which names the address of the
+//! following instruction (code is placed from 0x200). Address operands take
+//! a label in place of a number, e.g. `JP LOOP`.
+
+use crate::constants::{NUM_RPL_FLAGS, PROGRAM_START_ADDR};
+use crate::instruction::Instruction;
+use std::collections::HashMap;
+use thiserror::Error;
+
+/// Assembly errors, with 1-based source line numbers
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AssembleError {
+    #[error("Line {line}: unknown mnemonic {mnemonic}")]
+    UnknownMnemonic { line: usize, mnemonic: String },
+
+    #[error("Line {line}: invalid operands for {mnemonic}: {operands}")]
+    InvalidOperands {
+        line: usize,
+        mnemonic: String,
+        operands: String,
+    },
+
+    #[error("Line {line}: value {value} out of range (max: {max:#X})")]
+    ValueOutOfRange {
+        line: usize,
+        value: String,
+        max: u16,
+    },
+
+    #[error("Line {line}: undefined label {label}")]
+    UndefinedLabel { line: usize, label: String },
+
+    #[error("Line {line}: label {label} is already defined")]
+    DuplicateLabel { line: usize, label: String },
+}
+
+/// Label names, uppercased, mapped to their addresses
+type Labels = HashMap<String, u16>;
+
+/// Assemble source text into ROM bytes
+pub fn assemble(source: &str) -> Result<Vec<u8>, AssembleError> {
+    // First pass: every instruction is one word, so labels resolve up front
+    let mut labels = Labels::new();
+    let mut address = PROGRAM_START_ADDR;
+    for (index, line) in source.lines().enumerate() {
+        let (label, code) = split_label(line);
+        if let Some(label) = label
+            && labels.insert(label.to_ascii_uppercase(), address).is_some()
+        {
+            return Err(AssembleError::DuplicateLabel {
+                line: index + 1,
+                label: label.to_string(),
+            });
+        }
+        if !code.is_empty() {
+            address = address.wrapping_add(2);
+        }
+    }
+
+    let mut rom = Vec::new();
+    for (index, line) in source.lines().enumerate() {
+        let (_, code) = split_label(line);
+        if code.is_empty() {
+            continue;
+        }
+        let opcode = assemble_line(index + 1, code, &labels)?;
+        rom.extend_from_slice(&opcode.to_be_bytes());
+    }
+    Ok(rom)
+}
+
+/// Strip the comment from a line and split off its leading `NAME:` label
+fn split_label(line: &str) -> (Option<&str>, &str) {
+    let code = line.split(';').next().unwrap_or_default().trim();
+    match code.split_once(':') {
+        Some((label, rest)) if is_label(label.trim()) => (Some(label.trim()), rest.trim()),
+        _ => (None, code),
+    }
+}
+
+/// Whether `text` is a label name: a letter or `_`, then letters, digits or `_`
+fn is_label(text: &str) -> bool {
+    let mut chars = text.chars();
+    chars
+        .next()
+        .is_some_and(|first| first.is_ascii_alphabetic() || first == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+/// Parse a single instruction line (comment and label already stripped) into its opcode
+fn assemble_line(line: usize, code: &str, labels: &Labels) -> Result<u16, AssembleError> {
+    let (mnemonic, rest) = code.split_once(char::is_whitespace).unwrap_or((code, ""));
+    let mnemonic = mnemonic.to_ascii_uppercase();
+    let operands: Vec<String> = if rest.trim().is_empty() {
+        Vec::new()
+    } else {
+        rest.split(',')
+            .map(|operand| operand.trim().to_ascii_uppercase())
+            .collect()
+    };
+    let ops: Vec<&str> = operands.iter().map(String::as_str).collect();
+
+    let invalid = || AssembleError::InvalidOperands {
+        line,
+        mnemonic: mnemonic.clone(),
+        operands: rest.trim().to_string(),
+    };
+    let reg = |text: &str| register(text).ok_or_else(invalid);
+    let flag_reg = |text: &str| {
+        register(text)
+            .filter(|&vx| vx < NUM_RPL_FLAGS)
+            .ok_or_else(invalid)
+    };
+    let addr = |text: &str| {
+        if let Some(&address) = labels.get(text) {
+            return Ok(address);
+        }
+        match number(line, text, 0xFFF) {
+            Some(result) => result,
+            None if is_label(text) => Err(AssembleError::UndefinedLabel {
+                line,
+                label: text.to_string(),
+            }),
+            None => Err(invalid()),
+        }
+    };
+    let byte = |text: &str| Ok(number(line, text, 0xFF).ok_or_else(invalid)?? as u8);
+    let nibble = |text: &str| Ok(number(line, text, 0xF).ok_or_else(invalid)?? as u8);
+
+    let instruction = match (mnemonic.as_str(), ops.as_slice()) {
+        ("DW", [word]) => return number(line, word, 0xFFFF).ok_or_else(invalid)?,
+        ("CLS", []) => Instruction::Cls,
+        ("RET", []) => Instruction::Ret,
+        ("SCR", []) => Instruction::ScrollRight,
+        ("SCL", []) => Instruction::ScrollLeft,
+        ("SCD", [n]) => Instruction::ScrollDown { n: nibble(n)? },
+        ("SYS", [a]) => Instruction::Sys { addr: addr(a)? },
+        ("JP", ["V0", a]) => Instruction::JumpV0 { addr: addr(a)? },
+        ("JP", [a]) => Instruction::Jump { addr: addr(a)? },
+        ("CALL", [a]) => Instruction::Call { addr: addr(a)? },
+        ("SE", [x, y]) => match register(y) {
+            Some(vy) => Instruction::SkipEqReg { vx: reg(x)?, vy },
+            None => Instruction::SkipEqImm {
+                vx: reg(x)?,
+                value: byte(y)?,
+            },
+        },
+        ("SNE", [x, y]) => match register(y) {
+            Some(vy) => Instruction::SkipNeReg { vx: reg(x)?, vy },
+            None => Instruction::SkipNeImm {
+                vx: reg(x)?,
+                value: byte(y)?,
+            },
+        },
+        ("LD", ["I", a]) => Instruction::SetIndex { addr: addr(a)? },
+        ("LD", ["DT", x]) => Instruction::SetDelayTimer { vx: reg(x)? },
+        ("LD", ["ST", x]) => Instruction::SetSoundTimer { vx: reg(x)? },
+        ("LD", ["F", x]) => Instruction::LoadFont { vx: reg(x)? },
+        ("LD", ["B", x]) => Instruction::StoreBcd { vx: reg(x)? },
+        ("LD", ["[I]", x]) => Instruction::StoreRegisters { vx: reg(x)? },
+        ("LD", ["R", x]) => Instruction::StoreFlags { vx: flag_reg(x)? },
+        ("LD", [x, "DT"]) => Instruction::LoadDelayTimer { vx: reg(x)? },
+        ("LD", [x, "K"]) => Instruction::WaitKey { vx: reg(x)? },
+        ("LD", [x, "[I]"]) => Instruction::LoadRegisters { vx: reg(x)? },
+        ("LD", [x, "R"]) => Instruction::LoadFlags { vx: flag_reg(x)? },
+        ("LD", [x, y]) => match register(y) {
+            Some(vy) => Instruction::LoadReg { vx: reg(x)?, vy },
+            None => Instruction::LoadImm {
+                vx: reg(x)?,
+                value: byte(y)?,
+            },
+        },
+        ("ADD", ["I", x]) => Instruction::AddIndex { vx: reg(x)? },
+        ("ADD", [x, y]) => match register(y) {
+            Some(vy) => Instruction::AddReg { vx: reg(x)?, vy },
+            None => Instruction::AddImm {
+                vx: reg(x)?,
+                value: byte(y)?,
+            },
+        },
+        ("SUB", [x, y]) => Instruction::SubReg {
+            vx: reg(x)?,
+            vy: reg(y)?,
+        },
+        ("SUBN", [x, y]) => Instruction::SubnReg {
+            vx: reg(x)?,
+            vy: reg(y)?,
+        },
+        ("OR", [x, y]) => Instruction::OrReg {
+            vx: reg(x)?,
+            vy: reg(y)?,
+        },
+        ("AND", [x, y]) => Instruction::AndReg {
+            vx: reg(x)?,
+            vy: reg(y)?,
+        },
+        ("XOR", [x, y]) => Instruction::XorReg {
+            vx: reg(x)?,
+            vy: reg(y)?,
+        },
+        ("SHR", [x]) => Instruction::ShrReg { vx: reg(x)? },
+        ("SHL", [x]) => Instruction::ShlReg { vx: reg(x)? },
+        ("DRW", [x, y, n]) => Instruction::Draw {
+            vx: reg(x)?,
+            vy: reg(y)?,
+            n: nibble(n)?,
+        },
+        ("SKP", [x]) => Instruction::SkipKeyPressed { vx: reg(x)? },
+        ("SKNP", [x]) => Instruction::SkipKeyNotPressed { vx: reg(x)? },
+        ("RND", [x, k]) => Instruction::Random {
+            vx: reg(x)?,
+            mask: byte(k)?,
+        },
+        (
+            "DW" | "CLS" | "RET" | "SCR" | "SCL" | "SCD" | "SYS" | "JP" | "CALL" | "SE" | "SNE"
+            | "LD" | "ADD" | "SUB" | "SUBN" | "OR" | "AND" | "XOR" | "SHR" | "SHL" | "DRW" | "SKP"
+            | "SKNP" | "RND",
+            _,
+        ) => return Err(invalid()),
+        _ => {
+            return Err(AssembleError::UnknownMnemonic { line, mnemonic });
+        }
+    };
+    Ok(instruction.opcode())
+}
+
+/// Parse a `Vx` register operand
+fn register(text: &str) -> Option<usize> {
+    let digit = text.strip_prefix('V')?;
+    if digit.len() != 1 {
+        return None;
+    }
+    usize::from_str_radix(digit, 16).ok()
+}
+
+/// Parse a hexadecimal operand no larger than `max`
+///
+/// Returns None if the text isn't a number, and an out-of-range error if it
+/// is one but doesn't fit.
+fn number(line: usize, text: &str, max: u16) -> Option<Result<u16, AssembleError>> {
+    let digits = text.strip_prefix("0X").unwrap_or(text);
+    let value = u32::from_str_radix(digits, 16).ok()?;
+    Some(if value <= max as u32 {
+        Ok(value as u16)
+    } else {
+        Err(AssembleError::ValueOutOfRange {
+            line,
+            value: text.to_string(),
+            max,
+        })
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::disassembler::disassemble_rom;
+    use crate::instruction::decode_all;
+    use crate::memory::Memory;
+    use crate::rom_loader::BUILTIN_LOGO_ROM;
+
+    #[test]
+    fn test_ibm_logo_round_trip() {
+        let mut memory = Memory::new(true);
+        memory.load_rom(BUILTIN_LOGO_ROM).unwrap();
+        let instructions = disassemble_rom(&memory).unwrap();
+
+        // Code and sprite data (as DW words) both come back byte for byte
+        let source: String = instructions
+            .iter()
+            .map(|instruction| format!("{}\n", instruction.mnemonic()))
+            .collect();
+        assert_eq!(assemble(&source).unwrap(), BUILTIN_LOGO_ROM);
+    }
+
+    #[test]
+    fn test_every_mnemonic_reassembles() {
+        for (opcode, result) in decode_all() {
+            let Ok(instruction) = result else { continue };
+            let bytes = assemble(&instruction.mnemonic()).unwrap();
+            assert_eq!(
+                u16::from_be_bytes([bytes[0], bytes[1]]),
+                instruction.opcode(),
+                "{:#06x} ({})",
+                opcode,
+                instruction.mnemonic()
+            );
+        }
+    }
+
+    #[test]
+    fn test_comments_blank_lines_and_case() {
+        let source = "
+            ; draw the digit in V0
+            ld v0, 0x0A   ; digit
+            LD F, V0
+            drw V1, V2, 5
+            DW F00D
+
+        ";
+        assert_eq!(
+            assemble(source).unwrap(),
+            vec![0x60, 0x0A, 0xF0, 0x29, 0xD1, 0x25, 0xF0, 0x0D]
+        );
+    }
+
+    #[test]
+    fn test_labels() {
+        let source = "
+            start:
+                LD V0, 0
+            loop: ADD V0, 1
+                SE V0, 10
+                JP Loop       ; labels are case-insensitive
+                CALL done
+            DONE:
+                JP START
+        ";
+        assert_eq!(
+            assemble(source).unwrap(),
+            vec![
+                0x60, 0x00, 0x70, 0x01, 0x30, 0x10, 0x12, 0x02, 0x22, 0x0A, 0x12, 0x00
+            ]
+        );
+
+        assert_eq!(
+            assemble("JP NOWHERE"),
+            Err(AssembleError::UndefinedLabel {
+                line: 1,
+                label: "NOWHERE".to_string()
+            })
+        );
+        assert_eq!(
+            assemble("A1:\nCLS\na1: RET"),
+            Err(AssembleError::DuplicateLabel {
+                line: 3,
+                label: "a1".to_string()
+            })
+        );
+    }
+
+    #[test]
+    fn test_assemble_errors() {
+        assert_eq!(
+            assemble("CLS\nFOO V1"),
+            Err(AssembleError::UnknownMnemonic {
+                line: 2,
+                mnemonic: "FOO".to_string()
+            })
+        );
+        assert_eq!(
+            assemble("SHR V1, V2, V3"),
+            Err(AssembleError::InvalidOperands {
+                line: 1,
+                mnemonic: "SHR".to_string(),
+                operands: "V1, V2, V3".to_string()
+            })
+        );
+        assert!(matches!(
+            assemble("SUB V1, 42"),
+            Err(AssembleError::InvalidOperands { .. })
+        ));
+        assert_eq!(
+            assemble("LD V1, 100"),
+            Err(AssembleError::ValueOutOfRange {
+                line: 1,
+                value: "100".to_string(),
+                max: 0xFF
+            })
+        );
+    }
+}
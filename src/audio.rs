@@ -0,0 +1,141 @@
+//! Audio - Sound timer beeping
+//!
+//! CHIP-8 beeps while the sound timer is non-zero; the timer counts down at
+//! 60Hz. The emulator drives an [`AudioOutput`]: [`NullAudio`] by default, or
+//! the speaker-backed [`Audio`] when built with the `audio` cargo feature.
+//! The emulator holds each beep for its [`effective_beep_duration`], at least
+//! `EmulatorConfig::min_beep_ms`, so very short beeps are not dropped.
+
+use std::time::Duration;
+use thiserror::Error;
+
+/// Default beep tone in Hz
+pub const DEFAULT_BEEP_FREQUENCY: f32 = 440.0;
+
+/// Duration of one sound timer tick at 60Hz
+pub const TIMER_TICK: Duration = Duration::from_nanos(1_000_000_000 / 60);
+
+/// How long a beep should last for a sound timer value
+///
+/// The natural length is one `TIMER_TICK` per timer unit, raised to
+/// `min_duration` so `LD ST, Vx` with small values is still audible.
+/// A timer of 0 never beeps.
+pub fn effective_beep_duration(sound_timer: u8, min_duration: Duration) -> Duration {
+    if sound_timer == 0 {
+        return Duration::ZERO;
+    }
+    (TIMER_TICK * sound_timer as u32).max(min_duration)
+}
+
+/// Audio errors
+#[derive(Debug, Error)]
+pub enum AudioError {
+    #[error("Failed to open audio device: {message}")]
+    Device { message: String },
+}
+
+/// Sink for the beep driven by the sound timer
+pub trait AudioOutput {
+    /// Start the beep tone (no-op if already beeping)
+    fn start_beep(&mut self);
+
+    /// Stop the beep tone (no-op if silent)
+    fn stop_beep(&mut self);
+
+    /// Set the beep tone frequency in Hz
+    fn set_frequency(&mut self, hz: f32);
+}
+
+/// Audio output that discards every beep (headless runs and tests)
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NullAudio;
+
+impl AudioOutput for NullAudio {
+    fn start_beep(&mut self) {}
+
+    fn stop_beep(&mut self) {}
+
+    fn set_frequency(&mut self, _hz: f32) {}
+}
+
+/// Sine-wave beep played on the default output device
+#[cfg(feature = "audio")]
+pub struct Audio {
+    /// Keeps the output device open; playback stops when dropped
+    _sink: rodio::MixerDeviceSink,
+    player: rodio::Player,
+    frequency: f32,
+    beeping: bool,
+}
+
+#[cfg(feature = "audio")]
+impl Audio {
+    /// Open the default output device
+    pub fn new() -> Result<Self, AudioError> {
+        let mut sink =
+            rodio::DeviceSinkBuilder::open_default_sink().map_err(|e| AudioError::Device {
+                message: e.to_string(),
+            })?;
+        sink.log_on_drop(false);
+        let player = rodio::Player::connect_new(sink.mixer());
+
+        Ok(Self {
+            _sink: sink,
+            player,
+            frequency: DEFAULT_BEEP_FREQUENCY,
+            beeping: false,
+        })
+    }
+}
+
+#[cfg(feature = "audio")]
+impl AudioOutput for Audio {
+    fn start_beep(&mut self) {
+        use rodio::Source;
+
+        if self.beeping {
+            return;
+        }
+        self.player
+            .append(rodio::source::SineWave::new(self.frequency).amplify(0.2));
+        self.player.play();
+        self.beeping = true;
+    }
+
+    fn stop_beep(&mut self) {
+        self.player.clear();
+        self.beeping = false;
+    }
+
+    fn set_frequency(&mut self, hz: f32) {
+        self.frequency = hz;
+        if self.beeping {
+            // Restart the tone at the new pitch
+            self.stop_beep();
+            self.start_beep();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_effective_beep_duration() {
+        let min = Duration::from_millis(50);
+
+        // Silent timer stays silent regardless of the minimum
+        assert_eq!(effective_beep_duration(0, min), Duration::ZERO);
+
+        // Short beeps are stretched to the minimum
+        assert_eq!(effective_beep_duration(1, min), min);
+        assert_eq!(effective_beep_duration(3, min), min);
+
+        // Longer beeps keep their natural length
+        assert_eq!(effective_beep_duration(60, min), TIMER_TICK * 60);
+
+        // Without a minimum, one tick lasts about 16ms
+        assert_eq!(effective_beep_duration(1, Duration::ZERO).as_millis(), 16);
+    }
+}
@@ -1,7 +1,9 @@
 use clap::Parser;
 use joe::{
-    Memory, Result, RomSource, analyze_instruction_usage, disassemble_rom, load_rom_data,
-    print_disassembly,
+    DisassembledInstruction, InstructionFamily, Memory, Result, RomLoaderConfig, RomSource,
+    analyze_instruction_usage, branch_targets, disassemble_rom, disassembly_to_json, emit_asm,
+    find_data_regions, load_rom_data, load_rom_data_with_progress, print_disassembly,
+    trace_disassemble,
 };
 
 #[derive(Parser)]
@@ -17,53 +19,118 @@ pub struct AnalyzeCommand {
     #[arg(short, long)]
     pub disassemble: bool,
 
-    /// Show instruction usage statistics
+    /// Follow jumps and calls from the entry point instead of decoding linearly,
+    /// so unreachable bytes are listed as data
     #[arg(short, long)]
-    pub stats: bool,
-}
+    pub trace: bool,
 
-impl AnalyzeCommand {
-    pub fn execute(self, disable_write_protection: bool) -> Result<()> {
-        // Detect source type and load ROM data
-        let source = RomSource::from_string(&self.rom_source);
+    /// Print the disassembly as JSON instead of the text report
+    #[arg(long)]
+    pub json: bool,
 
-        println!(
-            "Loading ROM from {}: {}",
-            if source.is_url() { "URL" } else { "file" },
-            source.description()
-        );
+    /// Print assembler source (with labels) that `joe::assemble` turns back into the ROM
+    #[arg(long, conflicts_with_all = ["json", "trace"])]
+    pub emit_asm: bool,
 
-        if source.is_url() {
-            println!("Downloading ROM from remote server...");
-        }
+    /// Show instruction usage statistics
+    #[arg(short, long)]
+    pub stats: bool,
 
-        // Load ROM data (from file or URL)
-        let rom_data = load_rom_data(&self.rom_source)?;
+    /// Only summarize these instruction families (comma-separated, e.g. draw,input,arithmetic)
+    #[arg(long, value_delimiter = ',', value_name = "FAMILIES")]
+    pub only: Vec<InstructionFamily>,
 
-        println!("Analyzing ROM: {}", source.description());
-        println!("ROM size: {} bytes", rom_data.len());
+    /// Leave these instruction families out of the summary (comma-separated)
+    #[arg(long, value_delimiter = ',', value_name = "FAMILIES")]
+    pub exclude: Vec<InstructionFamily>,
+}
 
-        // Create memory and load ROM
-        let write_protection = !disable_write_protection;
-        let mut memory = Memory::new(write_protection);
-        memory.load_rom(&rom_data)?;
+/// Families to summarize: `only` (or all when empty), minus `exclude`
+fn select_families(
+    only: &[InstructionFamily],
+    exclude: &[InstructionFamily],
+) -> Vec<InstructionFamily> {
+    InstructionFamily::ALL
+        .into_iter()
+        .filter(|family| only.is_empty() || only.contains(family))
+        .filter(|family| !exclude.contains(family))
+        .collect()
+}
 
-        // Disassemble the ROM
-        let instructions =
-            disassemble_rom(&memory).map_err(|e| anyhow::anyhow!("Disassembly failed: {}", e))?;
+impl AnalyzeCommand {
+    /// Load the ROM into memory and disassemble it, linearly or by tracing
+    fn disassemble(
+        &self,
+        rom_data: &[u8],
+        disable_write_protection: bool,
+    ) -> Result<(Memory, Vec<DisassembledInstruction>)> {
+        let mut memory = Memory::new(!disable_write_protection);
+        memory.load_rom(rom_data)?;
+
+        let instructions = if self.trace {
+            trace_disassemble(&memory, joe::constants::PROGRAM_START_ADDR)
+        } else {
+            disassemble_rom(&memory)
+        }
+        .map_err(|e| anyhow::anyhow!("Disassembly failed: {}", e))?;
+        Ok((memory, instructions))
+    }
+
+    pub fn execute(self, disable_write_protection: bool) -> Result<()> {
+        // JSON and assembler source go to stdout on their own, without the
+        // progress chatter
+        let machine_output = self.json || self.emit_asm;
+        let rom_data = if machine_output {
+            load_rom_data(&self.rom_source)?
+        } else {
+            let rom_data = super::load_rom_with_report(&self.rom_source, |on_progress| {
+                load_rom_data_with_progress(
+                    &self.rom_source,
+                    &RomLoaderConfig::default(),
+                    on_progress,
+                )
+            })?;
+            let source = RomSource::from_string(&self.rom_source);
+            println!("Analyzing ROM: {}", source.description());
+            println!("ROM size: {} bytes", rom_data.len());
+            rom_data
+        };
+
+        // Load and disassemble the ROM; `--emit-asm` can't be combined with
+        // `--trace`, since source must cover every byte in order
+        let (memory, instructions) = self.disassemble(&rom_data, disable_write_protection)?;
+        if self.json {
+            println!("{}", disassembly_to_json(&instructions));
+            return Ok(());
+        }
+        if self.emit_asm {
+            print!(
+                "{}",
+                emit_asm(&instructions, &branch_targets(&instructions))
+            );
+            return Ok(());
+        }
 
         if instructions.is_empty() {
             println!("No instructions found in ROM (empty or invalid)");
             return Ok(());
         }
 
-        println!("Found {} instructions", instructions.len());
+        let data_words = instructions
+            .iter()
+            .filter(|instruction| instruction.instruction.is_none())
+            .count();
+        println!(
+            "Found {} instructions ({} data words)",
+            instructions.len() - data_words,
+            data_words
+        );
 
         // Show disassembly if requested
         if self.disassemble {
             println!("\nDisassembly:");
             println!("============");
-            print_disassembly(&instructions);
+            print_disassembly(&instructions, &find_data_regions(&memory, &instructions));
         }
 
         // Show instruction analysis
@@ -71,9 +138,76 @@ impl AnalyzeCommand {
 
         // Always show summary unless user only wants disassembly
         if !self.disassemble || self.stats {
-            analysis.print_summary();
+            if self.only.is_empty() && self.exclude.is_empty() {
+                analysis.print_summary();
+            } else {
+                analysis.print_family_summary(&select_families(&self.only, &self.exclude));
+            }
         }
 
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use joe::InstructionAnalysis;
+
+    #[test]
+    fn test_family_names_parse() {
+        assert_eq!(
+            "draw".parse::<InstructionFamily>().unwrap(),
+            InstructionFamily::Draw
+        );
+        assert_eq!(
+            " Arithmetic".parse::<InstructionFamily>().unwrap(),
+            InstructionFamily::Arithmetic
+        );
+        assert!("graphics".parse::<InstructionFamily>().is_err());
+
+        // Every family round-trips through its name
+        for family in InstructionFamily::ALL {
+            assert_eq!(family.name().parse::<InstructionFamily>().unwrap(), family);
+        }
+    }
+
+    #[test]
+    fn test_only_and_exclude_arguments() {
+        let cmd = AnalyzeCommand::try_parse_from([
+            "analyze",
+            "rom.ch8",
+            "--only",
+            "draw,input,arithmetic",
+            "--exclude",
+            "input",
+        ])
+        .unwrap();
+
+        let analysis = InstructionAnalysis {
+            draw: 3,
+            input: 2,
+            arithmetic: 5,
+            jump: 7,
+            ..Default::default()
+        };
+
+        let selected = select_families(&cmd.only, &cmd.exclude);
+        assert_eq!(
+            selected,
+            vec![InstructionFamily::Arithmetic, InstructionFamily::Draw]
+        );
+        let counts: Vec<usize> = selected
+            .iter()
+            .map(|&family| analysis.family_count(family))
+            .collect();
+        assert_eq!(counts, vec![5, 3]);
+
+        // Exclude alone keeps every other family
+        let selected = select_families(&[], &[InstructionFamily::Jump]);
+        assert_eq!(selected.len(), InstructionFamily::ALL.len() - 1);
+        assert!(!selected.contains(&InstructionFamily::Jump));
+
+        assert!(AnalyzeCommand::try_parse_from(["analyze", "rom.ch8", "--only", "bogus"]).is_err());
+    }
+}
@@ -0,0 +1,379 @@
+//! Batch Command
+//!
+//! Runs every ROM in a directory headless for a bounded number of frames and
+//! reports pass/fail/crash per ROM, for regression suites.
+
+use anyhow::Context;
+use clap::Parser;
+use joe::constants::TIMER_FREQUENCY;
+use joe::{Emulator, EmulatorConfig, ErrorPolicy, HaltReason, Result};
+use serde::Serialize;
+use std::panic::{AssertUnwindSafe, catch_unwind};
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+#[derive(Parser)]
+pub struct BatchCommand {
+    /// Directory containing the `.ch8` ROMs to run
+    #[arg(value_name = "DIR")]
+    pub dir: PathBuf,
+
+    /// Number of 60Hz frames to run each ROM for
+    #[arg(short = 'f', long, default_value_t = 600)]
+    pub frames: usize,
+
+    /// CPU cycles executed per frame
+    #[arg(long, default_value_t = 10)]
+    pub cycles_per_frame: usize,
+
+    /// What to do when a ROM faults: abort (default) and stop fail it, warm-reset restarts it
+    #[arg(long, value_name = "POLICY")]
+    pub on_error: Option<ErrorPolicy>,
+
+    /// Print results as JSON instead of a table
+    #[arg(long)]
+    pub json: bool,
+}
+
+/// How a single ROM run ended
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+enum Outcome {
+    /// Ran every frame without an unrecovered fault
+    Pass,
+    /// Failed to load, or faulted and the error policy stopped it
+    Fail { error: String },
+    /// The emulator panicked
+    Crash { message: String },
+}
+
+/// Result of running one ROM
+#[derive(Debug, Clone, PartialEq, Serialize)]
+struct RomResult {
+    rom: String,
+    #[serde(flatten)]
+    outcome: Outcome,
+    cycles: usize,
+    /// Faults recovered from by warm-resetting
+    warm_resets: usize,
+    #[serde(rename = "duration_ms", serialize_with = "serialize_millis")]
+    duration: Duration,
+}
+
+/// Totals across a batch
+#[derive(Debug, Default, PartialEq, Serialize)]
+struct BatchSummary {
+    total: usize,
+    passed: usize,
+    failed: usize,
+    crashed: usize,
+    #[serde(rename = "duration_ms", serialize_with = "serialize_millis")]
+    duration: Duration,
+}
+
+/// JSON output: every ROM result followed by the totals
+#[derive(Serialize)]
+struct BatchReport<'a> {
+    results: &'a [RomResult],
+    summary: BatchSummary,
+}
+
+fn serialize_millis<S: serde::Serializer>(
+    duration: &Duration,
+    serializer: S,
+) -> std::result::Result<S::Ok, S::Error> {
+    serializer.serialize_f64(duration.as_secs_f64() * 1000.0)
+}
+
+/// Find the `.ch8` files in a directory, sorted by name
+fn find_roms(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut roms = Vec::new();
+    for entry in std::fs::read_dir(dir)
+        .with_context(|| format!("Failed to read ROM directory: {}", dir.display()))?
+    {
+        let path = entry?.path();
+        if path.is_file()
+            && path
+                .extension()
+                .is_some_and(|ext| ext.eq_ignore_ascii_case("ch8"))
+        {
+            roms.push(path);
+        }
+    }
+    roms.sort();
+    Ok(roms)
+}
+
+/// Run one ROM for `frames` frames, returning the outcome, cycles run and warm resets
+///
+/// Frames are [`Emulator::run_frames`] ticks, so the error policy is the
+/// emulator's own and the cycles run don't depend on how fast the host is.
+fn run_rom(
+    rom_data: &[u8],
+    frames: usize,
+    cycles_per_frame: usize,
+    policy: ErrorPolicy,
+) -> (Outcome, usize, usize) {
+    let mut emulator = Emulator::new(EmulatorConfig {
+        // run_tick runs cpu_hz / timer_hz cycles per frame
+        cpu_hz: (cycles_per_frame as u32).saturating_mul(TIMER_FREQUENCY),
+        timer_hz: TIMER_FREQUENCY,
+        on_cpu_error: policy,
+        ..Default::default()
+    });
+    if let Err(e) = emulator.load_rom(rom_data) {
+        return (
+            Outcome::Fail {
+                error: e.to_string(),
+            },
+            0,
+            0,
+        );
+    }
+
+    let result = emulator.run_frames(frames);
+    let report = emulator.run_report();
+    let outcome = match (result, report.halt_reason) {
+        (Err(e), _) => Outcome::Fail {
+            error: e.to_string(),
+        },
+        (Ok(_), HaltReason::Error { message }) => Outcome::Fail { error: message },
+        (Ok(_), _) => Outcome::Pass,
+    };
+    (outcome, report.cycles_executed, report.warm_resets)
+}
+
+/// Add up the outcomes of a batch
+fn summarize(results: &[RomResult]) -> BatchSummary {
+    let mut summary = BatchSummary {
+        total: results.len(),
+        ..Default::default()
+    };
+    for result in results {
+        match result.outcome {
+            Outcome::Pass => summary.passed += 1,
+            Outcome::Fail { .. } => summary.failed += 1,
+            Outcome::Crash { .. } => summary.crashed += 1,
+        }
+        summary.duration += result.duration;
+    }
+    summary
+}
+
+/// Build the printable results table and summary line
+fn table_lines(results: &[RomResult], summary: &BatchSummary) -> Vec<String> {
+    let width = results
+        .iter()
+        .map(|result| result.rom.len())
+        .max()
+        .unwrap_or(0)
+        .max("ROM".len());
+    let mut lines = vec![
+        format!(
+            "{:<width$}  {:<6}  {:>9}  {:>9}  Details",
+            "ROM", "Status", "Cycles", "Time (ms)"
+        ),
+        "-".repeat(width + 40),
+    ];
+    for result in results {
+        let (status, details) = match &result.outcome {
+            Outcome::Pass if result.warm_resets > 0 => {
+                ("pass", format!("{} warm resets", result.warm_resets))
+            }
+            Outcome::Pass => ("pass", String::new()),
+            Outcome::Fail { error } => ("FAIL", error.clone()),
+            Outcome::Crash { message } => ("CRASH", message.clone()),
+        };
+        lines.push(
+            format!(
+                "{:<width$}  {:<6}  {:>9}  {:>9.1}  {}",
+                result.rom,
+                status,
+                result.cycles,
+                result.duration.as_secs_f64() * 1000.0,
+                details
+            )
+            .trim_end()
+            .to_string(),
+        );
+    }
+    lines.push(format!(
+        "{} ROMs: {} passed, {} failed, {} crashed in {:.1}ms",
+        summary.total,
+        summary.passed,
+        summary.failed,
+        summary.crashed,
+        summary.duration.as_secs_f64() * 1000.0
+    ));
+    lines
+}
+
+/// Text of a panic payload
+fn panic_message(payload: &(dyn std::any::Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "unknown panic".to_string())
+}
+
+impl BatchCommand {
+    pub fn execute(self) -> Result<()> {
+        let policy = self.on_error.unwrap_or_default();
+        let mut results = Vec::new();
+
+        for path in find_roms(&self.dir)? {
+            let rom = path.file_name().map_or_else(
+                || path.display().to_string(),
+                |name| name.to_string_lossy().into_owned(),
+            );
+            let started = Instant::now();
+            let (outcome, cycles, warm_resets) = match std::fs::read(&path) {
+                Ok(rom_data) => catch_unwind(AssertUnwindSafe(|| {
+                    run_rom(&rom_data, self.frames, self.cycles_per_frame, policy)
+                }))
+                .unwrap_or_else(|payload| {
+                    let message = panic_message(payload.as_ref());
+                    (Outcome::Crash { message }, 0, 0)
+                }),
+                Err(e) => (
+                    Outcome::Fail {
+                        error: e.to_string(),
+                    },
+                    0,
+                    0,
+                ),
+            };
+            results.push(RomResult {
+                rom,
+                outcome,
+                cycles,
+                warm_resets,
+                duration: started.elapsed(),
+            });
+        }
+
+        let summary = summarize(&results);
+        let unsuccessful = summary.failed + summary.crashed;
+        let total = summary.total;
+        if self.json {
+            let report = BatchReport {
+                results: &results,
+                summary,
+            };
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        } else {
+            for line in table_lines(&results, &summary) {
+                println!("{}", line);
+            }
+        }
+
+        if unsuccessful > 0 {
+            anyhow::bail!("{} of {} ROMs did not pass", unsuccessful, total);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn result(rom: &str, outcome: Outcome, millis: u64) -> RomResult {
+        RomResult {
+            rom: rom.to_string(),
+            outcome,
+            cycles: 100,
+            warm_resets: 0,
+            duration: Duration::from_millis(millis),
+        }
+    }
+
+    fn synthetic_results() -> Vec<RomResult> {
+        vec![
+            result("a.ch8", Outcome::Pass, 10),
+            result(
+                "bad.ch8",
+                Outcome::Fail {
+                    error: "CPU error".to_string(),
+                },
+                5,
+            ),
+            result("b.ch8", Outcome::Pass, 20),
+            result(
+                "boom.ch8",
+                Outcome::Crash {
+                    message: "overflow".to_string(),
+                },
+                1,
+            ),
+        ]
+    }
+
+    #[test]
+    fn test_summarize_counts_outcomes() {
+        assert_eq!(summarize(&[]), BatchSummary::default());
+        assert_eq!(
+            summarize(&synthetic_results()),
+            BatchSummary {
+                total: 4,
+                passed: 2,
+                failed: 1,
+                crashed: 1,
+                duration: Duration::from_millis(36),
+            }
+        );
+    }
+
+    #[test]
+    fn test_table_lines() {
+        let results = synthetic_results();
+        let lines = table_lines(&results, &summarize(&results));
+
+        assert_eq!(lines.len(), 7);
+        assert_eq!(lines[2], "a.ch8     pass          100       10.0");
+        assert_eq!(
+            lines[3],
+            "bad.ch8   FAIL          100        5.0  CPU error"
+        );
+        assert_eq!(lines[5], "boom.ch8  CRASH         100        1.0  overflow");
+        assert_eq!(lines[6], "4 ROMs: 2 passed, 1 failed, 1 crashed in 36.0ms");
+    }
+
+    #[test]
+    fn test_json_report() {
+        let results = synthetic_results();
+        let report = BatchReport {
+            results: &results[1..2],
+            summary: summarize(&results[1..2]),
+        };
+        let json: serde_json::Value = serde_json::to_value(&report).unwrap();
+
+        assert_eq!(json["results"][0]["status"], "fail");
+        assert_eq!(json["results"][0]["error"], "CPU error");
+        assert_eq!(json["results"][0]["duration_ms"], 5.0);
+        assert_eq!(json["summary"]["failed"], 1);
+    }
+
+    #[test]
+    fn test_run_rom_outcomes() {
+        // JP 200 spins forever
+        let (outcome, cycles, _) = run_rom(&[0x12, 0x00], 3, 10, ErrorPolicy::Abort);
+        assert_eq!(outcome, Outcome::Pass);
+        assert_eq!(cycles, 30);
+
+        // RET with an empty stack faults on the first cycle
+        let (outcome, cycles, _) = run_rom(&[0x00, 0xEE], 3, 10, ErrorPolicy::Stop);
+        assert!(matches!(outcome, Outcome::Fail { .. }));
+        assert_eq!(cycles, 0);
+
+        // Warm resets keep the ROM running through every cycle of every frame
+        let (outcome, _, warm_resets) = run_rom(&[0x00, 0xEE], 3, 10, ErrorPolicy::WarmReset);
+        assert_eq!(outcome, Outcome::Pass);
+        assert_eq!(warm_resets, 30);
+
+        // Abort fails the ROM just like Stop
+        let (outcome, _, _) = run_rom(&[0x00, 0xEE], 3, 10, ErrorPolicy::Abort);
+        assert!(matches!(outcome, Outcome::Fail { .. }));
+    }
+}
@@ -0,0 +1,118 @@
+//! Bench Command
+//!
+//! Runs a ROM headless with no cycle delay, rendering, audio or status output
+//! for a fixed number of cycles and reports the interpreter's raw throughput.
+//! RND is seeded with a fixed value, so every run executes the same
+//! instruction stream and results are comparable across runs.
+
+use clap::Parser;
+use joe::{Emulator, EmulatorConfig, Result, RomLoaderConfig, load_rom_data_with_config};
+use std::time::Duration;
+
+/// RND seed for benchmark runs
+const BENCH_SEED: u64 = 0;
+
+#[derive(Parser)]
+pub struct BenchCommand {
+    /// Path to the ROM file, or HTTP(S) URL to download it from
+    #[arg(value_name = "ROM_SOURCE")]
+    pub rom_source: String,
+
+    /// Number of CPU cycles to run (at least 1)
+    #[arg(
+        short = 'c',
+        long,
+        default_value_t = 1_000_000,
+        value_parser = clap::builder::RangedU64ValueParser::<usize>::new().range(1..)
+    )]
+    pub cycles: usize,
+}
+
+/// Run `rom_data` headless for `cycles` cycles, returning the cycles run and the time taken
+fn bench(rom_data: &[u8], cycles: usize) -> Result<(usize, Duration)> {
+    let mut emulator = Emulator::new(EmulatorConfig {
+        max_cycles: cycles,
+        cycle_delay_ms: 0,
+        seed: Some(BENCH_SEED),
+        quiet: true,
+        ..Default::default()
+    });
+    emulator.load_rom(rom_data)?;
+    emulator.run_headless()?;
+    let stats = emulator.get_stats();
+    Ok((stats.cycles_executed, stats.run_duration))
+}
+
+/// Build the printable summary table
+fn summary_lines(cycles: usize, elapsed: Duration) -> Vec<String> {
+    let seconds = elapsed.as_secs_f64();
+    let (per_second, ns_per_instruction) = if cycles == 0 || seconds == 0.0 {
+        (0.0, 0.0)
+    } else {
+        (cycles as f64 / seconds, seconds * 1e9 / cycles as f64)
+    };
+    vec![
+        format!("{:<16}  {:>14}", "Cycles", cycles),
+        format!("{:<16}  {:>14.3}", "Time (s)", seconds),
+        format!("{:<16}  {:>14.0}", "Cycles/second", per_second),
+        format!("{:<16}  {:>14.1}", "ns/instruction", ns_per_instruction),
+    ]
+}
+
+impl BenchCommand {
+    pub fn execute(self) -> Result<()> {
+        let rom_data = load_rom_data_with_config(&self.rom_source, &RomLoaderConfig::default())?;
+        println!(
+            "Benchmarking {} for {} cycles...",
+            self.rom_source, self.cycles
+        );
+
+        let (cycles, elapsed) = bench(&rom_data, self.cycles)?;
+        for line in summary_lines(cycles, elapsed) {
+            println!("{}", line);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_summary_lines() {
+        assert_eq!(
+            summary_lines(2_000_000, Duration::from_millis(500)),
+            vec![
+                "Cycles                   2000000",
+                "Time (s)                   0.500",
+                "Cycles/second            4000000",
+                "ns/instruction             250.0",
+            ]
+        );
+        assert!(summary_lines(0, Duration::ZERO)[2].ends_with(" 0"));
+    }
+
+    #[test]
+    fn test_bench_command_runs_bounded_cycles() {
+        let path = std::env::temp_dir().join(format!("joe-bench-{}.ch8", std::process::id()));
+        std::fs::write(&path, [0x70, 0x01, 0x12, 0x00]).unwrap(); // ADD V0, 1; JP 200
+        let cmd =
+            BenchCommand::try_parse_from(["bench", path.to_str().unwrap(), "--cycles", "500"])
+                .unwrap();
+        assert_eq!(cmd.cycles, 500);
+
+        let rom_data = std::fs::read(&cmd.rom_source).unwrap();
+        let (cycles, _) = bench(&rom_data, cmd.cycles).unwrap();
+        assert_eq!(cycles, 500);
+        cmd.execute().unwrap();
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_bench_rejects_zero_cycles() {
+        // max_cycles 0 would mean unlimited, so the run would never return
+        assert!(BenchCommand::try_parse_from(["bench", "rom.ch8", "--cycles", "0"]).is_err());
+        assert!(BenchCommand::try_parse_from(["bench", "rom.ch8", "--cycles", "1"]).is_ok());
+    }
+}
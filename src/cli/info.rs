@@ -0,0 +1,109 @@
+use clap::Parser;
+use joe::{
+    METADATA_MARKER, Memory, Result, RomLoaderConfig, RomMetadata, RomSource,
+    constants::PROGRAM_START_ADDR, load_rom_data_with_progress, split_metadata,
+};
+
+#[derive(Parser)]
+pub struct InfoCommand {
+    /// Path to the ROM file, or HTTP(S) URL to download it from
+    #[arg(value_name = "ROM_SOURCE")]
+    pub rom_source: String,
+
+    /// Also print the first BYTES bytes of the loaded program as a hex dump
+    #[arg(long, value_name = "BYTES")]
+    pub hexdump: Option<usize>,
+}
+
+/// Hex dump of the first `len` bytes of `program`, at its load address
+fn program_hexdump(program: &[u8], len: usize) -> Result<String> {
+    let mut memory = Memory::new(true);
+    memory.load_rom(program)?;
+    Ok(memory.hexdump(PROGRAM_START_ADDR, len.min(program.len())))
+}
+
+/// Describe a ROM's size and any trailing metadata block
+fn info_lines(program_len: usize, metadata: Option<&RomMetadata>) -> Vec<String> {
+    let mut lines = vec![format!("Program size: {} bytes", program_len)];
+    match metadata {
+        Some(metadata) => {
+            lines.push("Metadata:".to_string());
+            for (key, value) in &metadata.fields {
+                lines.push(format!("  {}: {}", key, value));
+            }
+        }
+        None => lines.push(format!(
+            "Metadata: none (no trailing {:?} block)",
+            String::from_utf8_lossy(METADATA_MARKER).trim_end()
+        )),
+    }
+    lines
+}
+
+impl InfoCommand {
+    pub fn execute(self) -> Result<()> {
+        let source = RomSource::from_string(&self.rom_source);
+        println!("ROM: {}", source.description());
+
+        // Keep the metadata block so it can be shown
+        let config = RomLoaderConfig {
+            strip_metadata: false,
+            ..Default::default()
+        };
+        let rom_data = load_rom_data_with_progress(
+            &self.rom_source,
+            &config,
+            &mut super::print_download_progress,
+        )?;
+        if source.is_url() {
+            println!();
+        }
+
+        let (program, metadata) = split_metadata(&rom_data);
+        for line in info_lines(program.len(), metadata.as_ref()) {
+            println!("{}", line);
+        }
+        if let Some(len) = self.hexdump {
+            println!();
+            print!("{}", program_hexdump(program, len)?);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_info_lines() {
+        let mut rom = vec![0x00, 0xE0, 0x12, 0x02];
+        rom.extend_from_slice(METADATA_MARKER);
+        rom.extend_from_slice(b"title: Pong\nauthor: Someone\n");
+        let (program, metadata) = split_metadata(&rom);
+        assert_eq!(
+            info_lines(program.len(), metadata.as_ref()),
+            vec![
+                "Program size: 4 bytes",
+                "Metadata:",
+                "  author: Someone",
+                "  title: Pong",
+            ]
+        );
+
+        assert_eq!(
+            info_lines(4, None),
+            vec![
+                "Program size: 4 bytes",
+                "Metadata: none (no trailing \"#chip8-meta\" block)",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_program_hexdump() {
+        let dump = program_hexdump(&[0x00, 0xE0, 0x12, 0x00], 16).unwrap();
+        assert_eq!(dump.lines().count(), 1);
+        assert!(dump.starts_with("0200: 00 E0 12 00"));
+    }
+}
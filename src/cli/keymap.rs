@@ -0,0 +1,159 @@
+use clap::Parser;
+use joe::{
+    Config, ConfigManager, KEYPAD_LAYOUT, KeyMappings, Result, resolve_key_mappings_with_case,
+};
+use std::collections::HashMap;
+
+#[derive(Parser)]
+pub struct KeymapCommand {}
+
+/// Build the printable 4×4 keypad, each cell showing `CHIP-8 key → keyboard key`
+///
+/// Letters are shown in uppercase unless the mappings are case-sensitive.
+fn keypad_lines(mappings: &KeyMappings, case_sensitive: bool) -> Vec<String> {
+    let mut lines = vec!["┌───────┬───────┬───────┬───────┐".to_string()];
+    for (row, keys) in KEYPAD_LAYOUT.iter().enumerate() {
+        let cells: Vec<String> = keys
+            .iter()
+            .map(|&key| {
+                let bound = mappings.get_keyboard_key(key).map_or('-', |c| {
+                    if case_sensitive {
+                        c
+                    } else {
+                        c.to_ascii_uppercase()
+                    }
+                });
+                format!(" {:X} → {} ", key, bound)
+            })
+            .collect();
+        lines.push(format!("│{}│", cells.join("│")));
+        if row < KEYPAD_LAYOUT.len() - 1 {
+            lines.push("├───────┼───────┼───────┼───────┤".to_string());
+        }
+    }
+    lines.push("└───────┴───────┴───────┴───────┘".to_string());
+    lines
+}
+
+/// Warn about CHIP-8 keys with no binding and keyboard keys bound more than once
+fn mapping_warnings(
+    config_mappings: &HashMap<String, String>,
+    case_sensitive: bool,
+) -> Vec<String> {
+    let mut warnings = Vec::new();
+
+    let mut bound_keys: Vec<u8> = config_mappings
+        .keys()
+        .filter_map(|chip8_key| u8::from_str_radix(chip8_key, 16).ok())
+        .collect();
+    bound_keys.sort_unstable();
+    for key in 0..16u8 {
+        if !bound_keys.contains(&key) {
+            warnings.push(format!("CHIP-8 key {:X} is not mapped", key));
+        }
+    }
+
+    let mut by_keyboard_key: HashMap<char, Vec<u8>> = HashMap::new();
+    for (chip8_key, keyboard_key) in config_mappings {
+        if let (Ok(chip8_key), Some(keyboard_key)) = (
+            u8::from_str_radix(chip8_key, 16),
+            keyboard_key.chars().next(),
+        ) {
+            let keyboard_key = if case_sensitive {
+                keyboard_key
+            } else {
+                keyboard_key.to_ascii_uppercase()
+            };
+            by_keyboard_key
+                .entry(keyboard_key)
+                .or_default()
+                .push(chip8_key);
+        }
+    }
+    let mut duplicates: Vec<(char, Vec<u8>)> = by_keyboard_key
+        .into_iter()
+        .filter(|(_, chip8_keys)| chip8_keys.len() > 1)
+        .collect();
+    duplicates.sort_unstable();
+    for (keyboard_key, mut chip8_keys) in duplicates {
+        chip8_keys.sort_unstable();
+        let chip8_keys: Vec<String> = chip8_keys.iter().map(|k| format!("{:X}", k)).collect();
+        warnings.push(format!(
+            "Keyboard key {} is bound to several CHIP-8 keys ({}); only one will work",
+            keyboard_key,
+            chip8_keys.join(", ")
+        ));
+    }
+
+    warnings
+}
+
+impl KeymapCommand {
+    pub fn execute(self) -> Result<()> {
+        let config = ConfigManager::new()
+            .and_then(|manager| manager.load())
+            .unwrap_or_else(|e| {
+                eprintln!("Warning: Failed to load config: {}. Using defaults.", e);
+                Config::default()
+            });
+
+        let case_sensitive = config.input.case_sensitive;
+        let mappings =
+            resolve_key_mappings_with_case(Some(&config.input.key_mappings), case_sensitive)?;
+
+        println!("CHIP-8 key → keyboard key");
+        for line in keypad_lines(&mappings, case_sensitive) {
+            println!("{}", line);
+        }
+
+        for warning in mapping_warnings(&config.input.key_mappings, case_sensitive) {
+            println!("⚠️  {}", warning);
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn custom_mappings() -> HashMap<String, String> {
+        // Default layout, but 0 moved to "M" and F unbound; "1" also bound to C
+        let mut mappings = Config::default().input.key_mappings;
+        mappings.insert("0".to_string(), "M".to_string());
+        mappings.insert("C".to_string(), "1".to_string());
+        mappings.remove("F");
+        mappings
+    }
+
+    #[test]
+    fn test_keypad_lines_show_custom_bindings() {
+        let mappings = resolve_key_mappings_with_case(Some(&custom_mappings()), false).unwrap();
+        let lines = keypad_lines(&mappings, false);
+
+        assert_eq!(lines.len(), 9);
+        assert_eq!(lines[1], "│ 1 → 1 │ 2 → 2 │ 3 → 3 │ C → 1 │");
+        assert_eq!(lines[3], "│ 4 → Q │ 5 → W │ 6 → E │ D → R │");
+        assert_eq!(lines[7], "│ A → Z │ 0 → M │ B → C │ F → - │");
+    }
+
+    #[test]
+    fn test_mapping_warnings() {
+        assert!(mapping_warnings(&Config::default().input.key_mappings, false).is_empty());
+
+        assert_eq!(
+            mapping_warnings(&custom_mappings(), false),
+            vec![
+                "CHIP-8 key F is not mapped".to_string(),
+                "Keyboard key 1 is bound to several CHIP-8 keys (1, C); only one will work"
+                    .to_string(),
+            ]
+        );
+
+        // "q" and "Q" only clash when letters match either case
+        let mut mappings = Config::default().input.key_mappings;
+        mappings.insert("F".to_string(), "q".to_string());
+        assert_eq!(mapping_warnings(&mappings, true), Vec::<String>::new());
+        assert_eq!(mapping_warnings(&mappings, false).len(), 1);
+    }
+}
@@ -1,13 +1,21 @@
 mod analyze;
+mod batch;
+mod bench;
 mod config;
+mod info;
+mod keymap;
 mod run;
 mod version;
 
 use clap::{Parser, Subcommand};
-use joe::Result;
+use joe::{Result, RomSource};
 
 pub use analyze::AnalyzeCommand;
+pub use batch::BatchCommand;
+pub use bench::BenchCommand;
 pub use config::ConfigCommand;
+pub use info::InfoCommand;
+pub use keymap::KeymapCommand;
 pub use run::RunCommand;
 pub use version::VersionCommand;
 
@@ -33,13 +41,19 @@ pub enum Commands {
     Analyze(AnalyzeCommand),
     /// Run a CHIP-8 ROM file
     Run(RunCommand),
+    /// Run every ROM in a directory headless and report pass/fail/crash
+    Batch(BatchCommand),
+    /// Measure raw interpreter speed: run a ROM headless for a fixed cycle count
+    Bench(BenchCommand),
     /// Manage configuration files
     Config(ConfigCommand),
+    /// Show the effective keyboard to CHIP-8 key mapping
+    Keymap(KeymapCommand),
+    /// Show information about a ROM file, including embedded metadata
+    Info(InfoCommand),
     // Future commands:
     // /// Run a ROM with debugging features
     // Debug(DebugCommand),
-    // /// Show information about a ROM file
-    // Info(InfoCommand),
     // /// Run built-in tests
     // Test(TestCommand),
 }
@@ -53,13 +67,53 @@ pub struct GlobalOptions {
     pub disable_write_protection: bool,
 }
 
+/// Load a ROM for a command that reports what it is doing
+///
+/// Announces where `rom_source` is loaded from, hands `load` a progress
+/// callback that prints URL downloads on one line, and ends that line after.
+fn load_rom_with_report<T>(
+    rom_source: &str,
+    load: impl FnOnce(&mut dyn FnMut(usize, Option<u64>)) -> Result<T>,
+) -> Result<T> {
+    let source = RomSource::from_string(rom_source);
+    println!(
+        "Loading ROM from {}: {}",
+        if source.is_url() { "URL" } else { "file" },
+        source.description()
+    );
+    if source.is_url() {
+        println!("Downloading ROM from remote server...");
+    }
+
+    let loaded = load(&mut print_download_progress)?;
+    if source.is_url() {
+        println!();
+    }
+    Ok(loaded)
+}
+
+/// Print URL download progress on a single, continuously updated line
+fn print_download_progress(received: usize, total: Option<u64>) {
+    use std::io::Write;
+
+    match total {
+        Some(total) => print!("\rDownloaded {} / {} bytes", received, total),
+        None => print!("\rDownloaded {} bytes", received),
+    }
+    let _ = std::io::stdout().flush();
+}
+
 impl Cli {
     pub fn run(self) -> Result<()> {
         match self.command {
             Commands::Version(cmd) => cmd.execute(),
             Commands::Analyze(cmd) => cmd.execute(self.disable_write_protection),
             Commands::Run(cmd) => cmd.execute(self.disable_write_protection),
+            Commands::Batch(cmd) => cmd.execute(),
+            Commands::Bench(cmd) => cmd.execute(),
             Commands::Config(cmd) => cmd.execute(),
+            Commands::Keymap(cmd) => cmd.execute(),
+            Commands::Info(cmd) => cmd.execute(),
         }
     }
 }
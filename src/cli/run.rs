@@ -1,5 +1,15 @@
+use anyhow::Context;
 use clap::Parser;
-use joe::{Config, ConfigManager, Emulator, EmulatorConfig, RomSource, load_rom_data};
+use joe::{
+    BUILTIN_LOGO_ROM, Config, ConfigManager, DEFAULT_TRACE_DEPTH, Emulator, EmulatorConfig,
+    EmulatorSettings, ErrorPolicy, FileSequence, QuirkProfile, QuirkSettings, RomLoaderConfig,
+    SequenceEnd,
+};
+#[cfg(feature = "gif")]
+use joe::{GifRecorder, RatatuiConfig};
+use std::path::PathBuf;
+#[cfg(feature = "gif")]
+use std::{cell::RefCell, rc::Rc, time::Duration};
 
 #[derive(Parser)]
 pub struct RunCommand {
@@ -7,8 +17,10 @@ pub struct RunCommand {
     /// Examples:
     ///   - Local file: game.ch8
     ///   - Remote URL: https://example.com/rom.ch8
+    ///
+    /// If omitted, uses `default_rom` from the config file or the built-in IBM logo
     #[arg(value_name = "ROM_SOURCE")]
-    pub rom_source: String,
+    pub rom_source: Option<String>,
 
     /// Maximum number of CPU cycles to execute (0 = unlimited)
     /// If not specified, uses value from config file
@@ -20,6 +32,25 @@ pub struct RunCommand {
     #[arg(short = 'd', long)]
     pub cycle_delay_ms: Option<u64>,
 
+    /// Run this many cycles per 60Hz frame, ticking timers and rendering once
+    /// per frame; overrides the cycle delay (0 = use the cycle delay)
+    /// If not specified, uses value from config file
+    #[arg(long, value_name = "N")]
+    pub cycles_per_frame: Option<usize>,
+
+    /// Run this many CPU cycles per second, spread over the timer ticks
+    /// (8, 8, 9, ... at 500Hz/60Hz); replaces the cycle delay, but
+    /// --cycles-per-frame takes precedence
+    /// If not specified, uses value from config file
+    #[arg(long, value_name = "HZ", value_parser = clap::value_parser!(u32).range(1..))]
+    pub cpu_hz: Option<u32>,
+
+    /// Delay/sound timer rate in Hz, also the frame rate with --cpu-hz or
+    /// --cycles-per-frame (default: 60)
+    /// If not specified, uses value from config file
+    #[arg(long, value_name = "HZ", value_parser = clap::value_parser!(u32).range(1..))]
+    pub timer_hz: Option<u32>,
+
     /// Show CPU state after each cycle
     #[arg(short = 'v', long)]
     pub verbose: bool,
@@ -27,34 +58,173 @@ pub struct RunCommand {
     /// Run without terminal UI (headless mode for testing/automation)
     #[arg(long)]
     pub headless: bool,
+
+    /// Start paused so the first frame can be inspected (Space resumes)
+    #[arg(long)]
+    pub step_mode: bool,
+
+    /// Write a JSON run report to this path when the run ends
+    #[arg(long, value_name = "PATH")]
+    pub report: Option<PathBuf>,
+
+    /// Seed for the RND instruction, to reproduce a previous run
+    /// If not specified, a random seed is used and printed
+    #[arg(long, value_name = "SEED")]
+    pub seed: Option<u64>,
+
+    /// Feed RND these bytes in order instead of the seeded generator
+    #[arg(long, value_name = "PATH")]
+    pub random_file: Option<PathBuf>,
+
+    /// What RND does after the last byte of --random-file: cycle (start
+    /// over) or error (fail the instruction)
+    #[arg(
+        long,
+        value_name = "END",
+        default_value = "cycle",
+        requires = "random_file"
+    )]
+    pub random_end: SequenceEnd,
+
+    /// Quirk preset: cosmac-vip, super-chip, xo-chip or modern (overrides the config file)
+    #[arg(long, value_name = "NAME")]
+    pub quirks: Option<QuirkProfile>,
+
+    /// What to do when the CPU faults: abort (default), warm-reset or stop
+    #[arg(long, value_name = "POLICY")]
+    pub on_error: Option<ErrorPolicy>,
+
+    /// Warn when a RET returns outside the ROM or the run ends with unreturned CALLs
+    #[arg(long)]
+    pub track_stack_balance: bool,
+
+    /// Continue from the state saved with Ctrl+Q (needs the `save-state` feature)
+    #[arg(long)]
+    pub resume: bool,
+
+    /// Record the first frames of the run as an animated GIF (needs the `gif` feature)
+    #[arg(long, value_name = "PATH")]
+    pub record_gif: Option<PathBuf>,
+
+    /// Number of frames to record with --record-gif
+    #[arg(long, value_name = "N", default_value_t = 300)]
+    pub gif_frames: usize,
+
+    /// Size in GIF pixels of one CHIP-8 pixel
+    #[arg(long, value_name = "SCALE", default_value_t = 4)]
+    pub gif_scale: u32,
+
+    /// How long each GIF frame is shown, in milliseconds (rounded down to 10ms steps)
+    #[arg(long, value_name = "MS", default_value_t = 20)]
+    pub gif_delay_ms: u64,
 }
 
-impl RunCommand {
-    pub fn execute(self, disable_write_protection: bool) -> joe::Result<()> {
-        println!("CHIP-8 Emulator - Running ROM");
-        println!("==============================");
+/// Resolve the quirks to run with: a preset named on the command line wins,
+/// then one named in the config file, then the config file's individual flags
+fn resolve_quirks(cli_profile: Option<QuirkProfile>, settings: &EmulatorSettings) -> QuirkSettings {
+    match cli_profile.or(settings.quirks) {
+        Some(profile) => profile.to_quirks(),
+        None => QuirkSettings {
+            reset_vf_on_logic: settings.reset_vf_on_logic,
+            index_increment: settings.index_increment,
+            wait_key_on_release: settings.wait_key_on_release,
+            display_wait: settings.display_wait,
+            add_index_sets_vf: settings.add_index_sets_vf,
+            shift_uses_vy: settings.shift_uses_vy,
+            jump_uses_vx: settings.jump_uses_vx,
+            clip_sprites: settings.clip_sprites,
+        },
+    }
+}
+
+/// Flags that only make sense with the terminal UI, paired with whether they are set
+fn interactive_only_flags(cmd: &RunCommand) -> [(&'static str, bool); 1] {
+    [("--step-mode", cmd.step_mode)]
+}
 
-        // Detect source type and load ROM data
-        let source = RomSource::from_string(&self.rom_source);
+/// Terminal UI hotkeys, paired with what they do; a headless run has no way to press them
+const INTERACTIVE_ONLY_HOTKEYS: [(&str, &str); 5] = [
+    ("Space", "pause"),
+    ("Ctrl+R", "reset"),
+    ("F5", "reload"),
+    ("Ctrl+S", "screenshot"),
+    ("Ctrl+Q", "save & quit"),
+];
 
-        println!(
-            "Loading ROM from {}: {}",
-            if source.is_url() { "URL" } else { "file" },
-            source.description()
+/// Warning a headless run prints about the hotkeys it can't offer
+fn headless_hotkey_warning() -> String {
+    let hotkeys: Vec<String> = INTERACTIVE_ONLY_HOTKEYS
+        .iter()
+        .map(|(key, action)| format!("{} ({})", key, action))
+        .collect();
+    format!(
+        "Note: hotkeys need the terminal UI and are unavailable headless: {}",
+        hotkeys.join(", ")
+    )
+}
+
+/// Reject flag combinations that can't work together, listing every conflict
+fn validate_flags(cmd: &RunCommand) -> joe::Result<()> {
+    if !cmd.headless {
+        return Ok(());
+    }
+    let conflicts: Vec<&str> = interactive_only_flags(cmd)
+        .into_iter()
+        .filter_map(|(flag, set)| set.then_some(flag))
+        .collect();
+    if !conflicts.is_empty() {
+        anyhow::bail!(
+            "--headless cannot be combined with interactive-only flags: {}",
+            conflicts.join(", ")
         );
+    }
+    Ok(())
+}
 
-        if source.is_url() {
-            println!("Downloading ROM from remote server...");
-        }
+/// ROM selected for a run
+#[derive(Debug, Clone, PartialEq)]
+enum StartupRom {
+    /// File path or URL to load
+    Source(String),
+    /// The embedded IBM logo ROM
+    BuiltIn,
+}
 
-        // Load ROM data (from file or URL)
-        let rom_data = load_rom_data(&self.rom_source)?;
+/// Pick the ROM to run: CLI argument, then configured default, then the built-in logo
+fn resolve_startup_rom(cli_source: Option<&str>, default_rom: Option<&str>) -> StartupRom {
+    match cli_source.or(default_rom) {
+        Some(source) => StartupRom::Source(source.to_string()),
+        None => StartupRom::BuiltIn,
+    }
+}
 
-        println!(
-            "Loaded ROM: {} ({} bytes)",
-            source.description(),
-            rom_data.len()
-        );
+/// Restore the state Ctrl+Q saved, on top of the freshly loaded ROM
+#[cfg(feature = "save-state")]
+fn resume_state(emulator: &mut Emulator, user_config: &Config) -> joe::Result<()> {
+    let path = ConfigManager::new()?.resume_state_path(&user_config.emulator);
+    let state = std::fs::read(&path)
+        .with_context(|| format!("No saved state to resume: {}", path.display()))?;
+    emulator
+        .load_state(&state)
+        .with_context(|| format!("Failed to resume from {}", path.display()))?;
+    println!("Resumed from {}", path.display());
+    Ok(())
+}
+
+impl RunCommand {
+    pub fn execute(self, disable_write_protection: bool) -> joe::Result<()> {
+        validate_flags(&self)?;
+        #[cfg(not(feature = "gif"))]
+        if self.record_gif.is_some() {
+            anyhow::bail!("--record-gif needs joe built with the `gif` feature");
+        }
+        #[cfg(not(feature = "save-state"))]
+        if self.resume {
+            anyhow::bail!("--resume needs joe built with the `save-state` feature");
+        }
+
+        println!("CHIP-8 Emulator - Running ROM");
+        println!("==============================");
 
         // Load user configuration
         let user_config = ConfigManager::new()
@@ -65,37 +235,166 @@ impl RunCommand {
             });
 
         // Configure the emulator (CLI args override config file)
-        let config = EmulatorConfig {
+        let cpu_hz = self.cpu_hz.or(user_config.emulator.cpu_hz);
+        let mut config = EmulatorConfig {
             max_cycles: self.max_cycles.unwrap_or(user_config.emulator.max_cycles),
             cycle_delay_ms: self
                 .cycle_delay_ms
                 .unwrap_or(user_config.emulator.cycle_delay_ms),
+            cycles_per_frame: self
+                .cycles_per_frame
+                .unwrap_or(user_config.emulator.cycles_per_frame),
+            cpu_hz: cpu_hz.unwrap_or(joe::constants::CPU_FREQUENCY),
+            pace_by_cpu_hz: cpu_hz.is_some(),
+            timer_hz: self.timer_hz.unwrap_or(user_config.emulator.timer_hz),
             verbose: self.verbose || user_config.emulator.verbose,
             write_protection: if disable_write_protection {
                 false
             } else {
                 user_config.emulator.write_protection
             },
+            seed: self.seed,
+            auto_input: Vec::new(),
+            font: user_config.emulator.font,
+            reset_vf_on_logic: false,
+            index_increment: Default::default(),
+            lenient_decode: user_config.emulator.lenient_decode,
+            wait_key_on_release: false,
+            keep_rpl_flags: true,
+            display_wait: false,
+            add_index_sets_vf: false,
+            shift_uses_vy: false,
+            jump_uses_vx: false,
+            clip_sprites: false,
+            min_beep_ms: user_config.audio.min_beep_ms,
+            quiet: false,
+            on_cpu_error: self.on_error.unwrap_or_default(),
+            track_stack_balance: self.track_stack_balance,
+            track_ram_writes: false,
+            rewind_depth: 0,
+            trace_depth: DEFAULT_TRACE_DEPTH,
         };
+        config.set_quirks(resolve_quirks(self.quirks, &user_config.emulator));
 
         // Create and initialize emulator
         let mut emulator = Emulator::new(config);
+        if let Some(path) = &self.random_file {
+            let sequence = FileSequence::from_file(path, self.random_end)
+                .with_context(|| format!("Failed to read random file: {}", path.display()))?;
+            emulator.set_random_source(Box::new(sequence));
+            println!("RND bytes: {}", path.display());
+        } else {
+            println!("RNG seed: {} (reproduce with --seed)", emulator.get_seed());
+        }
+
+        let startup_rom = resolve_startup_rom(
+            self.rom_source.as_deref(),
+            user_config.emulator.default_rom.as_deref(),
+        );
 
-        // Load ROM into emulator
-        emulator.load_rom(&rom_data)?;
-        println!("ROM loaded at address 0x{:04X}", 0x200);
+        match startup_rom {
+            StartupRom::Source(rom_source) => {
+                // Load ROM (from file or URL) into emulator, showing download progress
+                let source = super::load_rom_with_report(&rom_source, |on_progress| {
+                    Ok(emulator.load_rom_from_source_with_progress(
+                        &rom_source,
+                        &RomLoaderConfig::default(),
+                        on_progress,
+                    )?)
+                })?;
+                println!(
+                    "Loaded ROM: {} at address 0x{:04X}",
+                    source.description(),
+                    0x200
+                );
+            }
+            StartupRom::BuiltIn => {
+                emulator.load_rom(BUILTIN_LOGO_ROM)?;
+                println!("Loaded built-in IBM logo ROM at address 0x{:04X}", 0x200);
+            }
+        }
+
+        #[cfg(feature = "save-state")]
+        if self.resume {
+            resume_state(&mut emulator, &user_config)?;
+        }
+
+        // Beep through the speakers when built with audio support
+        #[cfg(feature = "audio")]
+        if !self.headless {
+            match joe::Audio::new() {
+                Ok(audio) => emulator.set_audio_output(Box::new(audio)),
+                Err(e) => eprintln!("Warning: {}. Running without sound.", e),
+            }
+        }
+
+        if self.step_mode {
+            emulator.toggle_pause();
+        }
+
+        #[cfg(feature = "gif")]
+        let gif = match self.record_gif {
+            Some(_) => Some(self.attach_gif_recorder(&mut emulator, &user_config)?),
+            None => None,
+        };
 
         // Run the emulator
-        if self.headless {
+        let result = if self.headless {
             // Run in headless mode - just execute cycles without UI
             println!("Running in headless mode...");
-            emulator.run_headless()?;
+            println!("{}", headless_hotkey_warning());
+            emulator.run_headless()
         } else {
             // Run with terminal UI
-            emulator.run()?;
+            emulator.run()
+        };
+
+        // Write the report even if the run failed, so CI can inspect the halt reason
+        if let Some(path) = &self.report {
+            let json = emulator.run_report().to_json()?;
+            std::fs::write(path, json)
+                .with_context(|| format!("Failed to write run report: {}", path.display()))?;
+            println!("Run report written to {}", path.display());
+        }
+
+        #[cfg(feature = "gif")]
+        if let (Some(path), Some(recorder)) = (&self.record_gif, gif) {
+            let recorder = recorder.borrow();
+            recorder
+                .save(path)
+                .with_context(|| format!("Failed to write GIF: {}", path.display()))?;
+            println!(
+                "Recorded {} frames to {}",
+                recorder.frames().len(),
+                path.display()
+            );
         }
+
+        result?;
         Ok(())
     }
+
+    /// Capture frames for --record-gif, drawn in the configured pixel color
+    #[cfg(feature = "gif")]
+    fn attach_gif_recorder(
+        &self,
+        emulator: &mut Emulator,
+        user_config: &Config,
+    ) -> joe::Result<Rc<RefCell<GifRecorder>>> {
+        let color = RatatuiConfig::from_display_settings(&user_config.display).pixel_rgb();
+        let recorder = GifRecorder::new(
+            self.gif_frames,
+            self.gif_scale,
+            Duration::from_millis(self.gif_delay_ms),
+            color,
+        )?;
+        let recorder = Rc::new(RefCell::new(recorder));
+        let capture = Rc::clone(&recorder);
+        emulator.on_frame(Box::new(move |display| {
+            capture.borrow_mut().capture(display)
+        }));
+        Ok(recorder)
+    }
 }
 
 #[cfg(test)]
@@ -106,11 +405,27 @@ mod tests {
     fn test_run_command_creation() {
         // Test that RunCommand can be created with optional values
         let cmd = RunCommand {
-            rom_source: "test.ch8".to_string(),
+            rom_source: Some("test.ch8".to_string()),
             max_cycles: Some(100),
             cycle_delay_ms: Some(16),
+            cycles_per_frame: None,
+            cpu_hz: None,
+            timer_hz: None,
             verbose: false,
             headless: false,
+            step_mode: false,
+            report: None,
+            seed: None,
+            random_file: None,
+            random_end: SequenceEnd::Cycle,
+            quirks: None,
+            on_error: None,
+            track_stack_balance: false,
+            resume: false,
+            record_gif: None,
+            gif_frames: 300,
+            gif_scale: 4,
+            gif_delay_ms: 20,
         };
 
         assert_eq!(cmd.max_cycles, Some(100));
@@ -122,23 +437,165 @@ mod tests {
     fn test_emulator_config_creation() {
         // Test that we can create EmulatorConfig from RunCommand parameters
         let cmd = RunCommand {
-            rom_source: "test.ch8".to_string(),
+            rom_source: Some("test.ch8".to_string()),
             max_cycles: Some(200),
             cycle_delay_ms: Some(8),
+            cycles_per_frame: Some(15),
+            cpu_hz: Some(700),
+            timer_hz: None,
             verbose: true,
             headless: false,
+            step_mode: false,
+            report: None,
+            seed: None,
+            random_file: None,
+            random_end: SequenceEnd::Cycle,
+            quirks: None,
+            on_error: None,
+            track_stack_balance: false,
+            resume: false,
+            record_gif: None,
+            gif_frames: 300,
+            gif_scale: 4,
+            gif_delay_ms: 20,
         };
 
         let config = EmulatorConfig {
             max_cycles: cmd.max_cycles.unwrap_or(0),
             cycle_delay_ms: cmd.cycle_delay_ms.unwrap_or(16),
+            cycles_per_frame: cmd.cycles_per_frame.unwrap_or(0),
+            cpu_hz: cmd.cpu_hz.unwrap_or(joe::constants::CPU_FREQUENCY),
+            pace_by_cpu_hz: cmd.cpu_hz.is_some(),
+            timer_hz: cmd.timer_hz.unwrap_or(joe::constants::TIMER_FREQUENCY),
             verbose: cmd.verbose,
             write_protection: true,
+            seed: cmd.seed,
+            auto_input: Vec::new(),
+            font: Default::default(),
+            reset_vf_on_logic: false,
+            index_increment: Default::default(),
+            on_cpu_error: cmd.on_error.unwrap_or_default(),
+            lenient_decode: false,
+            wait_key_on_release: false,
+            keep_rpl_flags: true,
+            display_wait: false,
+            add_index_sets_vf: false,
+            shift_uses_vy: false,
+            jump_uses_vx: false,
+            clip_sprites: false,
+            min_beep_ms: 50,
+            quiet: false,
+            track_stack_balance: cmd.track_stack_balance,
+            track_ram_writes: false,
+            rewind_depth: 0,
+            trace_depth: DEFAULT_TRACE_DEPTH,
         };
 
         assert_eq!(config.max_cycles, 200);
         assert_eq!(config.cycle_delay_ms, 8);
+        assert_eq!(config.cycles_per_frame, 15);
+        assert_eq!(config.cpu_hz, 700);
+        assert!(config.pace_by_cpu_hz);
+        assert_eq!(config.timer_hz, 60);
         assert!(config.verbose);
         assert!(config.write_protection);
     }
+
+    #[test]
+    fn test_resolve_quirks() {
+        let mut settings = Config::default().emulator;
+        settings.reset_vf_on_logic = true;
+        assert!(resolve_quirks(None, &settings).reset_vf_on_logic);
+
+        settings.quirks = Some(QuirkProfile::XoChip);
+        assert_eq!(
+            resolve_quirks(None, &settings),
+            QuirkProfile::XoChip.to_quirks()
+        );
+        assert_eq!(
+            resolve_quirks(Some(QuirkProfile::CosmacVip), &settings),
+            QuirkProfile::CosmacVip.to_quirks()
+        );
+    }
+
+    fn command(headless: bool, step_mode: bool) -> RunCommand {
+        RunCommand {
+            rom_source: None,
+            max_cycles: None,
+            cycle_delay_ms: None,
+            cycles_per_frame: None,
+            cpu_hz: None,
+            timer_hz: None,
+            verbose: false,
+            headless,
+            step_mode,
+            report: None,
+            seed: None,
+            random_file: None,
+            random_end: SequenceEnd::Cycle,
+            quirks: None,
+            on_error: None,
+            track_stack_balance: false,
+            resume: false,
+            record_gif: None,
+            gif_frames: 300,
+            gif_scale: 4,
+            gif_delay_ms: 20,
+        }
+    }
+
+    #[test]
+    fn test_validate_flags_rejects_interactive_only_in_headless() {
+        let err = validate_flags(&command(true, true)).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "--headless cannot be combined with interactive-only flags: --step-mode"
+        );
+
+        // Each flag is fine on its own
+        assert!(validate_flags(&command(true, false)).is_ok());
+        assert!(validate_flags(&command(false, true)).is_ok());
+        assert!(validate_flags(&command(false, false)).is_ok());
+    }
+
+    #[test]
+    fn test_headless_warning_lists_hotkeys() {
+        let warning = headless_hotkey_warning();
+        for hotkey in ["Ctrl+S (screenshot)", "F5 (reload)", "Ctrl+Q (save & quit)"] {
+            assert!(warning.contains(hotkey), "{}", warning);
+        }
+    }
+
+    #[test]
+    fn test_validate_flags_from_command_line() {
+        let parse = |args: &[&str]| {
+            let cmd = RunCommand::try_parse_from(args).unwrap();
+            validate_flags(&cmd)
+        };
+        assert!(parse(&["run", "game.ch8", "--headless", "--step-mode"]).is_err());
+        assert!(parse(&["run", "--step-mode", "--headless", "-c", "100"]).is_err());
+        assert!(parse(&["run", "game.ch8", "--headless", "-v"]).is_ok());
+    }
+
+    #[test]
+    fn test_resolve_startup_rom() {
+        // CLI argument wins over the configured default
+        assert_eq!(
+            resolve_startup_rom(Some("cli.ch8"), Some("config.ch8")),
+            StartupRom::Source("cli.ch8".to_string())
+        );
+        assert_eq!(
+            resolve_startup_rom(Some("cli.ch8"), None),
+            StartupRom::Source("cli.ch8".to_string())
+        );
+
+        // Configured default is used when no argument is given
+        assert_eq!(
+            resolve_startup_rom(None, Some("https://example.com/demo.ch8")),
+            StartupRom::Source("https://example.com/demo.ch8".to_string())
+        );
+
+        // Fall back to the built-in logo when neither is set
+        assert_eq!(resolve_startup_rom(None, None), StartupRom::BuiltIn);
+    }
 }
@@ -0,0 +1,185 @@
+//! Clock - Time source for the emulation loop
+//!
+//! The emulator reads wall-clock time through the [`Clock`] trait so timing
+//! behavior (60Hz timers, render pacing) can be driven deterministically by a
+//! [`MockClock`] in tests instead of the real [`SystemClock`].
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+
+/// Source of the current time
+pub trait Clock {
+    /// Get the current instant
+    fn now(&self) -> Instant;
+}
+
+/// Clock backed by the system's monotonic time
+#[derive(Debug, Default, Clone, Copy)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> Instant {
+        Instant::now()
+    }
+}
+
+/// Manually driven clock for tests
+///
+/// Clones share the same time, so a test can keep one handle and hand another
+/// to the emulator. With an auto-advance step, every `now()` call moves time
+/// forward by that step after reading it, so each loop iteration takes a fixed
+/// amount of simulated time.
+#[derive(Debug, Clone)]
+pub struct MockClock {
+    start: Instant,
+    elapsed_nanos: Arc<AtomicU64>,
+    auto_advance: Duration,
+}
+
+impl MockClock {
+    /// Create a clock that only moves when advanced
+    pub fn new() -> Self {
+        Self::with_auto_advance(Duration::ZERO)
+    }
+
+    /// Create a clock that advances by `step` after every `now()` call
+    pub fn with_auto_advance(step: Duration) -> Self {
+        Self {
+            start: Instant::now(),
+            elapsed_nanos: Arc::new(AtomicU64::new(0)),
+            auto_advance: step,
+        }
+    }
+
+    /// Move time forward
+    pub fn advance(&self, duration: Duration) {
+        self.elapsed_nanos
+            .fetch_add(duration.as_nanos() as u64, Ordering::SeqCst);
+    }
+
+    /// Get the simulated time elapsed since the clock was created
+    pub fn elapsed(&self) -> Duration {
+        Duration::from_nanos(self.elapsed_nanos.load(Ordering::SeqCst))
+    }
+}
+
+impl Default for MockClock {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Clock for MockClock {
+    fn now(&self) -> Instant {
+        let elapsed = self
+            .elapsed_nanos
+            .fetch_add(self.auto_advance.as_nanos() as u64, Ordering::SeqCst);
+        self.start + Duration::from_nanos(elapsed)
+    }
+}
+
+/// Splits a CPU rate into whole cycles per timer tick without drifting
+///
+/// 500Hz over 60Hz timers is 8⅓ cycles per tick; the fractional part is
+/// carried over, so ticks run 8, 8, 9, 8, 8, 9, ... and every 60 ticks add
+/// up to exactly 500 cycles.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickScheduler {
+    cpu_hz: u32,
+    timer_hz: u32,
+    /// Cycles owed, in units of 1/timer_hz cycle
+    remainder: u32,
+}
+
+impl TickScheduler {
+    /// Schedule `cpu_hz` cycles per second against `timer_hz` ticks (at least 1)
+    pub fn new(cpu_hz: u32, timer_hz: u32) -> Self {
+        Self {
+            cpu_hz,
+            timer_hz: timer_hz.max(1),
+            remainder: 0,
+        }
+    }
+
+    /// Number of CPU cycles to run before the next timer tick
+    pub fn next_tick_cycles(&mut self) -> u32 {
+        let owed = self.remainder as u64 + self.cpu_hz as u64;
+        self.remainder = (owed % self.timer_hz as u64) as u32;
+        (owed / self.timer_hz as u64) as u32
+    }
+
+    /// Duration of one timer tick
+    pub fn tick_duration(&self) -> Duration {
+        Duration::from_nanos(1_000_000_000 / self.timer_hz as u64)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Cycles scheduled for each of the first `ticks` ticks
+    fn schedule(cpu_hz: u32, timer_hz: u32, ticks: usize) -> Vec<u32> {
+        let mut scheduler = TickScheduler::new(cpu_hz, timer_hz);
+        (0..ticks).map(|_| scheduler.next_tick_cycles()).collect()
+    }
+
+    #[test]
+    fn test_tick_scheduler_carries_fractional_cycles() {
+        assert_eq!(schedule(500, 60, 6), vec![8, 8, 9, 8, 8, 9]);
+        assert_eq!(schedule(700, 60, 6), vec![11, 12, 12, 11, 12, 12]);
+        assert_eq!(schedule(600, 60, 3), vec![10, 10, 10]);
+        // Slower than the timers: some ticks run no cycles
+        assert_eq!(schedule(30, 60, 4), vec![0, 1, 0, 1]);
+    }
+
+    #[test]
+    fn test_tick_scheduler_has_no_long_term_drift() {
+        for (cpu_hz, timer_hz) in [(500, 60), (700, 60), (1000, 60), (540, 50)] {
+            let cycles = schedule(cpu_hz, timer_hz, 60 * 60 * 10);
+            // After every tick the total is exactly floor(ticks * cpu_hz / timer_hz)
+            let mut total = 0u64;
+            for (tick, &n) in cycles.iter().enumerate() {
+                total += n as u64;
+                let ideal = (tick as u64 + 1) * cpu_hz as u64 / timer_hz as u64;
+                assert_eq!(
+                    total,
+                    ideal,
+                    "{}/{} after {} ticks",
+                    cpu_hz,
+                    timer_hz,
+                    tick + 1
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_tick_duration() {
+        assert_eq!(
+            TickScheduler::new(500, 60).tick_duration(),
+            crate::audio::TIMER_TICK
+        );
+        assert_eq!(
+            TickScheduler::new(500, 50).tick_duration(),
+            Duration::from_millis(20)
+        );
+    }
+
+    #[test]
+    fn test_mock_clock_advances() {
+        let clock = MockClock::new();
+        let t0 = clock.now();
+        assert_eq!(clock.now(), t0);
+
+        // Clones share the same time
+        clock.clone().advance(Duration::from_millis(5));
+        assert_eq!(clock.now() - t0, Duration::from_millis(5));
+
+        let auto = MockClock::with_auto_advance(Duration::from_millis(2));
+        let t0 = auto.now();
+        assert_eq!(auto.now() - t0, Duration::from_millis(2));
+        assert_eq!(auto.elapsed(), Duration::from_millis(4));
+    }
+}
@@ -3,12 +3,18 @@
 //! Handles loading, saving, and managing user configuration files
 //! stored in OS-appropriate directories.
 
+use crate::cpu::IndexIncrementMode;
+use crate::emulator::QuirkProfile;
+use crate::memory::FontSet;
 use directories::ProjectDirs;
 use serde::{Deserialize, Serialize};
 use std::fs;
 use std::path::{Path, PathBuf};
 use thiserror::Error;
 
+/// File name of the quit-and-save state, kept next to the config file
+pub const RESUME_STATE_FILE: &str = "resume.state";
+
 /// Configuration errors
 #[derive(Debug, Error)]
 pub enum ConfigError {
@@ -36,6 +42,10 @@ pub struct Config {
 
     /// Input settings
     pub input: InputSettings,
+
+    /// Audio settings
+    #[serde(default)]
+    pub audio: AudioSettings,
 }
 
 /// Emulator-specific settings
@@ -47,11 +57,77 @@ pub struct EmulatorSettings {
     /// Delay between CPU cycles in milliseconds
     pub cycle_delay_ms: u64,
 
+    /// CPU cycles per timer tick frame; when non-zero it replaces `cycle_delay_ms`
+    #[serde(default)]
+    pub cycles_per_frame: usize,
+
+    /// CPU cycles per second spread over the timer ticks; when set it
+    /// replaces `cycle_delay_ms`, but `cycles_per_frame` takes precedence
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cpu_hz: Option<u32>,
+
+    /// Delay/sound timer rate in Hz, also the frame rate of the frame model
+    #[serde(default = "default_timer_hz")]
+    pub timer_hz: u32,
+
     /// Enable verbose output
     pub verbose: bool,
 
     /// Enable memory write protection
     pub write_protection: bool,
+
+    /// ROM to run when `joe run` is given no ROM source (None = built-in logo)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub default_rom: Option<String>,
+
+    /// Font set to load (standard, cosmac_vip or super_chip)
+    #[serde(default)]
+    pub font: FontSet,
+
+    /// Quirk preset; when set it overrides the individual quirk fields below
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub quirks: Option<QuirkProfile>,
+
+    /// COSMAC VIP quirk: OR, AND and XOR reset VF to 0
+    #[serde(default)]
+    pub reset_vf_on_logic: bool,
+
+    /// FX55/FX65 quirk: how far I advances (unchanged, x or x_plus_one)
+    #[serde(default)]
+    pub index_increment: IndexIncrementMode,
+
+    /// Run EXxx opcodes with a corrupted low byte as the nearest EX9E/EXA1
+    #[serde(default)]
+    pub lenient_decode: bool,
+
+    /// COSMAC VIP quirk: FX0A stores the key once it is released
+    #[serde(default)]
+    pub wait_key_on_release: bool,
+
+    /// COSMAC VIP quirk: DXYN waits for the next 60Hz tick
+    #[serde(default)]
+    pub display_wait: bool,
+
+    /// Amiga quirk: FX1E sets VF when I passes 0xFFF
+    #[serde(default)]
+    pub add_index_sets_vf: bool,
+
+    /// COSMAC VIP quirk: 8XY6/8XYE shift Vy into Vx
+    #[serde(default)]
+    pub shift_uses_vy: bool,
+
+    /// SUPER-CHIP quirk: BXNN jumps to XNN plus Vx
+    #[serde(default)]
+    pub jump_uses_vx: bool,
+
+    /// Clip sprites at the screen edges instead of wrapping them
+    #[serde(default)]
+    pub clip_sprites: bool,
+
+    /// Where Ctrl+Q saves the state and `joe run --resume` reads it
+    /// (None = `resume.state` in the config directory)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resume_state: Option<PathBuf>,
 }
 /// Display-specific settings for ratatui renderer
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -67,6 +143,50 @@ pub struct DisplaySettings {
 
     /// Theme name for the overall UI
     pub theme: String,
+
+    /// Tint recently lit pixels to visualize sprite motion
+    #[serde(default)]
+    pub motion_trail: bool,
+
+    /// Number of frames a motion trail lasts
+    #[serde(default = "default_motion_trail_frames")]
+    pub motion_trail_frames: u8,
+
+    /// Terminal cell height / width (e.g. 2.0 for tall cells, 1.0 for square);
+    /// picks 2 or 1 characters per pixel (None = use `pixel_char` as-is)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub cell_aspect: Option<f32>,
+
+    /// Border around the display: rounded, double or plain
+    #[serde(default = "default_border_style")]
+    pub border_style: String,
+
+    /// Blank space inside the display border as [rows, columns]
+    #[serde(default)]
+    pub border_padding: [u16; 2],
+}
+
+/// Default motion trail length in frames
+fn default_motion_trail_frames() -> u8 {
+    4
+}
+
+/// Default display border style
+fn default_border_style() -> String {
+    "rounded".to_string()
+}
+
+/// Audio-specific settings
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AudioSettings {
+    /// Minimum beep length in milliseconds, so tiny sound timer values stay audible
+    pub min_beep_ms: u64,
+}
+
+impl Default for AudioSettings {
+    fn default() -> Self {
+        Self { min_beep_ms: 50 }
+    }
 }
 
 /// Input-specific settings
@@ -74,6 +194,35 @@ pub struct DisplaySettings {
 pub struct InputSettings {
     /// Custom key mappings (CHIP-8 key -> keyboard key)
     pub key_mappings: std::collections::HashMap<String, String>,
+
+    /// Button mappings for external input sources (button name -> CHIP-8 key)
+    #[serde(default = "default_button_mappings")]
+    pub button_mappings: std::collections::HashMap<String, String>,
+
+    /// Map keyboard keys in their exact case, so e.g. `q` and `Q` can be
+    /// bound to different CHIP-8 keys (default: letters match either case)
+    #[serde(default)]
+    pub case_sensitive: bool,
+}
+
+/// Default timer rate, the CHIP-8's 60Hz
+fn default_timer_hz() -> u32 {
+    crate::constants::TIMER_FREQUENCY
+}
+
+/// Default button mappings matching `resolve_button_mappings(None)`
+fn default_button_mappings() -> std::collections::HashMap<String, String> {
+    [
+        ("Up", "2"),
+        ("Down", "8"),
+        ("Left", "4"),
+        ("Right", "6"),
+        ("A", "5"),
+        ("B", "0"),
+    ]
+    .into_iter()
+    .map(|(button, key)| (button.to_string(), key.to_string()))
+    .collect()
 }
 
 impl Default for Config {
@@ -102,16 +251,42 @@ impl Default for Config {
             emulator: EmulatorSettings {
                 max_cycles: 0,
                 cycle_delay_ms: 16,
+                cycles_per_frame: 0,
+                cpu_hz: None,
+                timer_hz: default_timer_hz(),
                 verbose: false,
                 write_protection: true,
+                default_rom: None,
+                font: FontSet::default(),
+                quirks: None,
+                reset_vf_on_logic: false,
+                index_increment: IndexIncrementMode::Unchanged,
+                lenient_decode: false,
+                wait_key_on_release: false,
+                display_wait: false,
+                add_index_sets_vf: false,
+                shift_uses_vy: false,
+                jump_uses_vx: false,
+                clip_sprites: false,
+                resume_state: None,
             },
             display: DisplaySettings {
                 pixel_char: "██".to_string(),
                 pixel_color: "Green".to_string(),
                 refresh_rate_ms: 16,
                 theme: "Default".to_string(),
+                motion_trail: false,
+                motion_trail_frames: default_motion_trail_frames(),
+                cell_aspect: None,
+                border_style: default_border_style(),
+                border_padding: [0, 0],
             },
-            input: InputSettings { key_mappings },
+            input: InputSettings {
+                key_mappings,
+                button_mappings: default_button_mappings(),
+                case_sensitive: false,
+            },
+            audio: AudioSettings::default(),
         }
     }
 }
@@ -173,6 +348,15 @@ impl ConfigManager {
     pub fn exists(&self) -> bool {
         self.config_path.exists()
     }
+
+    /// Get the quit-and-save state path: the configured `resume_state`, or
+    /// [`RESUME_STATE_FILE`] next to the config file
+    pub fn resume_state_path(&self, settings: &EmulatorSettings) -> PathBuf {
+        settings
+            .resume_state
+            .clone()
+            .unwrap_or_else(|| self.config_path.with_file_name(RESUME_STATE_FILE))
+    }
 }
 
 #[cfg(test)]
@@ -191,6 +375,49 @@ mod tests {
         assert_eq!(config.display.theme, deserialized.display.theme);
     }
 
+    #[test]
+    fn test_quirk_profile_setting() {
+        let mut config = Config::default();
+        config.emulator.quirks = Some(QuirkProfile::SuperChip);
+        let toml_str = toml::to_string(&config).unwrap();
+        assert!(toml_str.contains("quirks = \"super_chip\""));
+
+        let deserialized: Config = toml::from_str(&toml_str).unwrap();
+        assert_eq!(deserialized.emulator.quirks, Some(QuirkProfile::SuperChip));
+
+        let defaults = toml::to_string(&Config::default()).unwrap();
+        assert!(!defaults.contains("quirks ="));
+        // The command-line spellings work in the config file too
+        for (name, profile) in [
+            ("super-chip", QuirkProfile::SuperChip),
+            ("schip", QuirkProfile::SuperChip),
+            ("cosmac-vip", QuirkProfile::CosmacVip),
+            ("xo-chip", QuirkProfile::XoChip),
+        ] {
+            let toml_str = toml_str.replace("\"super_chip\"", &format!("\"{}\"", name));
+            let deserialized: Config = toml::from_str(&toml_str).unwrap();
+            assert_eq!(deserialized.emulator.quirks, Some(profile), "{}", name);
+        }
+    }
+
+    #[test]
+    fn test_resume_state_path() {
+        let manager = ConfigManager {
+            config_path: PathBuf::from("/home/user/.config/joe/config.toml"),
+        };
+        let mut settings = Config::default().emulator;
+        assert_eq!(
+            manager.resume_state_path(&settings),
+            PathBuf::from("/home/user/.config/joe/resume.state")
+        );
+
+        settings.resume_state = Some(PathBuf::from("/tmp/pong.state"));
+        assert_eq!(
+            manager.resume_state_path(&settings),
+            PathBuf::from("/tmp/pong.state")
+        );
+    }
+
     #[test]
     fn test_config_manager_creation() {
         // This test might fail in some CI environments without home directories
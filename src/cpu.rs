@@ -6,8 +6,13 @@
 use crate::constants::*;
 use crate::display::{DisplayBus, DisplayError};
 use crate::input::{InputBus, InputError};
-use crate::instruction::{DecodeError, Instruction, decode_opcode};
+use crate::instruction::{DecodeError, Instruction, decode_opcode, decode_opcode_lenient};
 use crate::memory::{MemoryBus, MemoryError};
+use crate::random::{RandomError, RandomSource};
+use rand::SeedableRng;
+use rand::rngs::SmallRng;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeSet;
 use thiserror::Error;
 
 /// CPU errors
@@ -25,6 +30,9 @@ pub enum CpuError {
     #[error("Input error: {0}")]
     Input(#[from] InputError),
 
+    #[error("Random source error: {0}")]
+    Random(#[from] RandomError),
+
     #[error("Stack overflow - cannot push more than {max_depth} levels")]
     StackOverflow { max_depth: usize },
 
@@ -41,20 +49,90 @@ pub enum CpuError {
         source: Box<CpuError>,
     },
 
-    #[error("Program counter out of bounds: {pc:#06x}")]
+    #[error("Program counter out of bounds or odd: {pc:#06x}")]
     InvalidProgramCounter { pc: u16 },
+
+    #[error("Index register value out of bounds: {value:#06x} (max: {max:#06x})")]
+    InvalidIndex { value: u16, max: u16 },
 }
 
 /// CPU execution state
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum CpuState {
     /// Normal execution - fetch, decode, execute instructions
     Running,
     /// Waiting for a key press - stores which register (Vx) to store the key in
     WaitingForKey { vx: usize },
+    /// Release-edge quirk: `key` was pressed and goes into Vx once released
+    WaitingForRelease { vx: usize, key: u8 },
+    /// Display-wait quirk: a sprite was drawn this frame; resumes on the next timer tick
+    WaitingForVblank,
+}
+
+/// The instruction the last cycle executed, see [`Cpu::last_executed`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct LastInstruction {
+    /// Address the instruction was fetched from
+    pub addr: u16,
+    /// Raw 16-bit opcode
+    pub opcode: u16,
+    /// What the opcode decoded to
+    pub instruction: Instruction,
+    /// PC at the start of the cycle (equal to `addr`)
+    pub pc_before: u16,
+    /// PC once the instruction finished
+    pub pc_after: u16,
+}
+
+impl LastInstruction {
+    /// How far the instruction moved PC: 2 normally, 4 for a taken skip,
+    /// anything else for jumps, calls and returns
+    pub fn pc_delta(&self) -> i32 {
+        self.pc_after as i32 - self.pc_before as i32
+    }
+}
+
+/// Where FX55/FX65 leave I after transferring V0..=Vx
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum IndexIncrementMode {
+    /// I is left unchanged (SUPER-CHIP 1.1 and most modern interpreters)
+    #[default]
+    Unchanged,
+    /// I advances by x, stopping on the last register (SUPER-CHIP 1.0)
+    X,
+    /// I advances by x + 1, past the last register (COSMAC VIP)
+    XPlusOne,
+}
+
+/// Why [`Cpu::step_until_break`] stopped
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BreakReason {
+    /// PC reached a breakpoint; the instruction there has not run yet
+    Breakpoint { addr: u16 },
+    /// The cycle budget ran out before any breakpoint was reached
+    CycleLimit,
+}
+
+/// Copy of the registers, timers and active call stack for debugger views
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuSnapshot {
+    pub v: [u8; NUM_REGISTERS],
+    pub i: u16,
+    pub pc: u16,
+    pub sp: u8,
+    /// Return addresses of the unreturned CALLs, outermost first
+    pub stack: Vec<u16>,
+    pub delay_timer: u8,
+    pub sound_timer: u8,
+    pub state: CpuState,
 }
 
 /// CHIP-8 CPU state
+///
+/// Serializable for save states; the random source is not saved, so a
+/// deserialized CPU draws from a freshly seeded generator.
+#[derive(Serialize, Deserialize)]
 pub struct Cpu {
     /// 16 general-purpose 8-bit registers (V0-VF)
     /// VF is used as a flag register by some instructions
@@ -79,13 +157,67 @@ pub struct Cpu {
     /// Sound timer - decrements at 60Hz, beeps while > 0
     sound_timer: u8,
 
+    /// SUPER-CHIP RPL user flags, written by FX75 and read by FX85
+    rpl_flags: [u8; NUM_RPL_FLAGS],
+
+    /// Keep the RPL flags across reset, as the HP-48 calculator does
+    keep_rpl_flags: bool,
+
     /// Current execution state
     state: CpuState,
+
+    /// Seed the random number generator started from (for reproducible runs)
+    seed: u64,
+
+    /// Random source used by RND
+    #[serde(skip, default = "entropy_rng")]
+    rng: Box<dyn RandomSource>,
+
+    /// FX55/FX65 quirk: how far I advances after the register transfer
+    index_increment: IndexIncrementMode,
+
+    /// 8XY1/8XY2/8XY3 quirk: reset VF to 0 after OR, AND and XOR (COSMAC VIP)
+    reset_vf_on_logic: bool,
+
+    /// Decode near-miss EXxx opcodes as the closest key-skip instruction
+    lenient_decode: bool,
+
+    /// FX0A quirk: store the key when it is released, not when pressed (COSMAC VIP)
+    wait_key_on_release: bool,
+
+    /// DXYN quirk: stall after drawing until the next 60Hz tick (COSMAC VIP)
+    display_wait: bool,
+
+    /// FX1E quirk: set VF when I + Vx leaves the 12-bit address space (Amiga)
+    add_index_sets_vf: bool,
+
+    /// 8XY6/8XYE quirk: shift Vy into Vx instead of shifting Vx in place (COSMAC VIP)
+    shift_uses_vy: bool,
+
+    /// BNNN quirk: jump to XNN plus Vx instead of NNN plus V0 (SUPER-CHIP)
+    jump_uses_vx: bool,
+
+    /// Addresses that stop execution before the instruction there is fetched
+    #[serde(skip)]
+    breakpoints: BTreeSet<u16>,
+
+    /// Breakpoint the CPU is currently stopped at; the next cycle runs past it
+    #[serde(skip)]
+    stopped_at: Option<u16>,
+
+    /// Instruction executed by the most recent cycle
+    #[serde(skip)]
+    last_executed: Option<LastInstruction>,
 }
 
 impl Cpu {
-    /// Create a new CPU with default state
+    /// Create a new CPU with default state and a random seed from system entropy
     pub fn new() -> Self {
+        Self::with_seed(entropy_seed())
+    }
+
+    /// Create a new CPU whose RND instruction is driven by the given seed
+    pub fn with_seed(seed: u64) -> Self {
         Self {
             v: [0; NUM_REGISTERS],
             i: 0,
@@ -94,11 +226,28 @@ impl Cpu {
             stack: [0; STACK_SIZE],
             delay_timer: 0,
             sound_timer: 0,
+            rpl_flags: [0; NUM_RPL_FLAGS],
+            keep_rpl_flags: true,
             state: CpuState::Running,
+            seed,
+            rng: Box::new(SmallRng::seed_from_u64(seed)),
+            index_increment: IndexIncrementMode::Unchanged,
+            reset_vf_on_logic: false,
+            lenient_decode: false,
+            wait_key_on_release: false,
+            display_wait: false,
+            add_index_sets_vf: false,
+            shift_uses_vy: false,
+            jump_uses_vx: false,
+            breakpoints: BTreeSet::new(),
+            stopped_at: None,
+            last_executed: None,
         }
     }
 
     /// Reset CPU to initial state
+    ///
+    /// The RPL flags survive unless [`Cpu::set_keep_rpl_flags`] turned that off.
     pub fn reset(&mut self) {
         self.v.fill(0);
         self.i = 0;
@@ -108,6 +257,22 @@ impl Cpu {
         self.delay_timer = 0;
         self.sound_timer = 0;
         self.state = CpuState::Running;
+        self.rng = Box::new(SmallRng::seed_from_u64(self.seed));
+        self.stopped_at = None;
+        self.last_executed = None;
+        if !self.keep_rpl_flags {
+            self.rpl_flags.fill(0);
+        }
+    }
+
+    /// Replace the random source used by RND (reset restores the seeded source)
+    pub fn set_random_source(&mut self, rng: Box<dyn RandomSource>) {
+        self.rng = rng;
+    }
+
+    /// Copy the random source RND currently draws from, at its current position
+    pub fn clone_random_source(&self) -> Box<dyn RandomSource> {
+        self.rng.clone_box()
     }
 
     /// Execute one CPU cycle based on current execution state
@@ -117,25 +282,42 @@ impl Cpu {
         display: &mut D,
         input: &mut I,
     ) -> Result<(), CpuError> {
+        self.last_executed = None;
         match self.state {
             CpuState::Running => {
+                // Stop in front of a breakpoint; the following cycle resumes past it
+                if self.will_break() {
+                    self.stopped_at = Some(self.pc);
+                    return Ok(());
+                }
+                self.stopped_at = None;
+
                 // Normal execution: fetch, decode, execute
                 let instruction_addr = self.pc;
-                let instruction = self.fetch_instruction(memory)?;
+                let opcode = self.fetch_instruction(memory)?;
 
-                self.execute_instruction(instruction, memory, display, input)
+                let instruction = self
+                    .execute_instruction(opcode, memory, display, input)
                     .map_err(|err| CpuError::InstructionExecutionFailed {
-                        instruction,
+                        instruction: opcode,
                         addr: instruction_addr,
                         source: Box::new(err),
-                    })
+                    })?;
+                self.last_executed = Some(LastInstruction {
+                    addr: instruction_addr,
+                    opcode,
+                    instruction,
+                    pc_before: instruction_addr,
+                    pc_after: self.pc,
+                });
+                Ok(())
             }
             CpuState::WaitingForKey { vx } => {
                 // Blocked on key input - check if key is now available
                 match input.try_get_key_press() {
                     Some(key) => {
-                        self.v[vx] = key;
                         self.state = CpuState::Running;
+                        self.accept_key(vx, key);
                         Ok(())
                     }
                     None => {
@@ -144,6 +326,96 @@ impl Cpu {
                     }
                 }
             }
+            CpuState::WaitingForRelease { vx, key } => {
+                // Key latched - finish once it lets go
+                if input.is_key_released(key)? {
+                    self.v[vx] = key;
+                    self.state = CpuState::Running;
+                }
+                Ok(())
+            }
+            CpuState::WaitingForVblank => {
+                // Already drew this frame - update_timers releases the wait
+                Ok(())
+            }
+        }
+    }
+
+    /// Run cycles until PC reaches a breakpoint or `max_cycles` have run
+    ///
+    /// When already stopped at a breakpoint, the first cycle executes the
+    /// instruction there, so repeated calls walk from one break to the next.
+    pub fn step_until_break<M: MemoryBus, D: DisplayBus, I: InputBus>(
+        &mut self,
+        memory: &mut M,
+        display: &mut D,
+        input: &mut I,
+        max_cycles: usize,
+    ) -> Result<BreakReason, CpuError> {
+        for _ in 0..max_cycles {
+            self.execute_cycle(memory, display, input)?;
+            if let Some(addr) = self.breakpoint_hit() {
+                return Ok(BreakReason::Breakpoint { addr });
+            }
+        }
+        Ok(BreakReason::CycleLimit)
+    }
+
+    /// Stop execution whenever PC reaches `addr`
+    pub fn add_breakpoint(&mut self, addr: u16) {
+        self.breakpoints.insert(addr);
+    }
+
+    /// Remove a breakpoint, returning whether it was set
+    pub fn remove_breakpoint(&mut self, addr: u16) -> bool {
+        self.breakpoints.remove(&addr)
+    }
+
+    /// Addresses with a breakpoint set
+    pub fn breakpoints(&self) -> &BTreeSet<u16> {
+        &self.breakpoints
+    }
+
+    /// Get the instruction the last [`Cpu::execute_cycle`] executed
+    ///
+    /// None if that cycle ran nothing: the CPU was waiting for a key or the
+    /// next frame, or stopped at a breakpoint.
+    pub fn last_executed(&self) -> Option<LastInstruction> {
+        self.last_executed.clone()
+    }
+
+    /// Breakpoint address the CPU is currently stopped at, if any
+    pub fn breakpoint_hit(&self) -> Option<u16> {
+        self.stopped_at.filter(|&addr| addr == self.pc)
+    }
+
+    /// Check whether the next cycle stops at a breakpoint instead of fetching
+    pub fn will_break(&self) -> bool {
+        self.state == CpuState::Running
+            && self.breakpoints.contains(&self.pc)
+            && self.stopped_at != Some(self.pc)
+    }
+
+    /// Capture the registers, timers and call stack
+    pub fn snapshot(&self) -> CpuSnapshot {
+        CpuSnapshot {
+            v: self.v,
+            i: self.i,
+            pc: self.pc,
+            sp: self.sp,
+            stack: self.stack[..self.sp as usize].to_vec(),
+            delay_timer: self.delay_timer,
+            sound_timer: self.sound_timer,
+            state: self.state.clone(),
+        }
+    }
+
+    /// Take a key pressed for FX0A: store it now, or latch it until release
+    fn accept_key(&mut self, vx: usize, key: u8) {
+        if self.wait_key_on_release {
+            self.state = CpuState::WaitingForRelease { vx, key };
+        } else {
+            self.v[vx] = key;
         }
     }
 
@@ -177,17 +449,39 @@ impl Cpu {
         Ok(instruction)
     }
 
-    /// Decode and execute an instruction
+    /// Decode and execute an instruction, returning what it decoded to
     fn execute_instruction<M: MemoryBus, D: DisplayBus, I: InputBus>(
         &mut self,
         opcode: u16,
         memory: &mut M,
         display: &mut D,
         input: &mut I,
-    ) -> Result<(), CpuError> {
+    ) -> Result<Instruction, CpuError> {
         // Decode the instruction using centralized decoding
-        let instruction = decode_opcode(opcode)?;
+        let instruction = if self.lenient_decode {
+            decode_opcode_lenient(opcode)?
+        } else {
+            decode_opcode(opcode)?
+        };
+        // The decoded shifts drop Y, so the VIP shift quirk reads it from the
+        // opcode: copying Vy into Vx first makes the in-place shift Vy's
+        if self.shift_uses_vy
+            && let Instruction::ShrReg { vx } | Instruction::ShlReg { vx } = instruction
+        {
+            self.v[vx] = self.v[((opcode >> 4) & 0xF) as usize];
+        }
+        self.run_instruction(instruction.clone(), memory, display, input)?;
+        Ok(instruction)
+    }
 
+    /// Execute a decoded instruction
+    fn run_instruction<M: MemoryBus, D: DisplayBus, I: InputBus>(
+        &mut self,
+        instruction: Instruction,
+        memory: &mut M,
+        display: &mut D,
+        input: &mut I,
+    ) -> Result<(), CpuError> {
         // Execute based on the decoded instruction
         match instruction {
             Instruction::Cls => {
@@ -195,17 +489,35 @@ impl Cpu {
                 Ok(())
             }
             Instruction::Ret => self.return_from_subroutine(),
+            Instruction::ScrollDown { n } => {
+                display.scroll_down(n);
+                Ok(())
+            }
+            Instruction::ScrollRight => {
+                display.scroll_right();
+                Ok(())
+            }
+            Instruction::ScrollLeft => {
+                display.scroll_left();
+                Ok(())
+            }
             Instruction::Sys { .. } => {
                 // System calls are rarely used in modern CHIP-8 programs
                 Ok(())
             }
             Instruction::Jump { addr } => {
-                self.pc = addr;
+                self.pc = validate_jump_target(addr)?;
                 Ok(())
             }
             Instruction::Call { addr } => self.call_subroutine(addr),
             Instruction::JumpV0 { addr } => {
-                self.pc = addr + (self.v[0] as u16);
+                // SUPER-CHIP reads BNNN as BXNN and adds Vx
+                let offset = if self.jump_uses_vx {
+                    self.v[((addr >> 8) & 0xF) as usize]
+                } else {
+                    self.v[0]
+                };
+                self.pc = validate_jump_target(addr + offset as u16)?;
                 Ok(())
             }
             Instruction::SkipEqImm { vx, value } => {
@@ -248,44 +560,46 @@ impl Cpu {
                 self.v[vx] = self.v[vx].wrapping_add(value);
                 Ok(())
             }
+            // 8XY4-8XYE: compute the flag first, write the result, then write VF
+            // last so that VF always ends up holding the flag, even when vx is F
             Instruction::AddReg { vx, vy } => {
                 let (result, overflow) = self.v[vx].overflowing_add(self.v[vy]);
-                self.v[vx] = result;
-                self.v[0xF] = if overflow { 1 } else { 0 };
+                self.set_arithmetic_result(vx, result, overflow as u8);
                 Ok(())
             }
             Instruction::SubReg { vx, vy } => {
                 let (result, borrow) = self.v[vx].overflowing_sub(self.v[vy]);
-                self.v[vx] = result;
-                self.v[0xF] = if borrow { 0 } else { 1 };
+                self.set_arithmetic_result(vx, result, !borrow as u8);
                 Ok(())
             }
             Instruction::SubnReg { vx, vy } => {
                 let (result, borrow) = self.v[vy].overflowing_sub(self.v[vx]);
-                self.v[vx] = result;
-                self.v[0xF] = if borrow { 0 } else { 1 };
+                self.set_arithmetic_result(vx, result, !borrow as u8);
                 Ok(())
             }
             Instruction::OrReg { vx, vy } => {
                 self.v[vx] |= self.v[vy];
+                self.apply_logic_vf_quirk();
                 Ok(())
             }
             Instruction::AndReg { vx, vy } => {
                 self.v[vx] &= self.v[vy];
+                self.apply_logic_vf_quirk();
                 Ok(())
             }
             Instruction::XorReg { vx, vy } => {
                 self.v[vx] ^= self.v[vy];
+                self.apply_logic_vf_quirk();
                 Ok(())
             }
             Instruction::ShrReg { vx } => {
-                self.v[0xF] = self.v[vx] & 0x01;
-                self.v[vx] >>= 1;
+                let flag = self.v[vx] & 0x01;
+                self.set_arithmetic_result(vx, self.v[vx] >> 1, flag);
                 Ok(())
             }
             Instruction::ShlReg { vx } => {
-                self.v[0xF] = (self.v[vx] & 0x80) >> 7;
-                self.v[vx] <<= 1;
+                let flag = (self.v[vx] & 0x80) >> 7;
+                self.set_arithmetic_result(vx, self.v[vx] << 1, flag);
                 Ok(())
             }
             Instruction::Draw { vx, vy, n } => {
@@ -296,13 +610,17 @@ impl Cpu {
                 // Read sprite data from memory starting at I register
                 let mut sprite_data = Vec::new();
                 for i in 0..n {
-                    let byte = memory.read_byte(self.i + i as u16)?;
+                    // Out-of-range reads surface as memory errors rather than overflow
+                    let byte = memory.read_byte(self.i.wrapping_add(i as u16))?;
                     sprite_data.push(byte);
                 }
 
                 // Draw sprite and get collision flag
                 let collision = display.draw_sprite(x, y, &sprite_data)?;
                 self.v[0xF] = if collision { 1 } else { 0 };
+                if self.display_wait {
+                    self.state = CpuState::WaitingForVblank;
+                }
                 Ok(())
             }
             Instruction::SkipKeyPressed { vx } => {
@@ -320,9 +638,7 @@ impl Cpu {
                 Ok(())
             }
             Instruction::Random { vx, mask } => {
-                // TODO: Use proper random number generator
-                let random_value = 0x42; // Placeholder
-                self.v[vx] = random_value & mask;
+                self.v[vx] = self.rng.next_byte()? & mask;
                 Ok(())
             }
             Instruction::LoadDelayTimer { vx } => {
@@ -341,7 +657,7 @@ impl Cpu {
                 // Try to get a key press immediately
                 match input.try_get_key_press() {
                     Some(key) => {
-                        self.v[vx] = key;
+                        self.accept_key(vx, key);
                         Ok(())
                     }
                     None => {
@@ -352,7 +668,12 @@ impl Cpu {
                 }
             }
             Instruction::AddIndex { vx } => {
-                self.i += self.v[vx] as u16;
+                // Keep I inside the 12-bit address space instead of overflowing
+                let sum = self.i as usize + self.v[vx] as usize;
+                self.i = (sum & (MEMORY_SIZE - 1)) as u16;
+                if self.add_index_sets_vf {
+                    self.v[0xF] = (sum >= MEMORY_SIZE) as u8;
+                }
                 Ok(())
             }
             Instruction::LoadFont { vx } => {
@@ -360,23 +681,83 @@ impl Cpu {
                 self.i = FONT_START_ADDR + (self.v[vx] as u16 * 5);
                 Ok(())
             }
-            Instruction::StoreBcd { .. } => {
-                // TODO: Implement BCD conversion
+            Instruction::StoreBcd { vx } => {
+                // Store hundreds, tens and ones digits of Vx at I, I+1, I+2
+                let value = self.v[vx];
+                // Check the whole range first so a fault never leaves a partial result
+                if let Some(addr) = (0..3)
+                    .map(|offset| self.i.wrapping_add(offset))
+                    .find(|&addr| addr as usize >= MEMORY_SIZE)
+                {
+                    return Err(MemoryError::OutOfBounds {
+                        addr,
+                        max: (MEMORY_SIZE - 1) as u16,
+                    }
+                    .into());
+                }
+                memory.write_byte(self.i, value / 100)?;
+                memory.write_byte(self.i.wrapping_add(1), (value / 10) % 10)?;
+                memory.write_byte(self.i.wrapping_add(2), value % 10)?;
+                Ok(())
+            }
+            Instruction::StoreRegisters { vx } => {
+                // Write V0..=Vx to memory starting at I
+                for reg in 0..=vx {
+                    memory.write_byte(self.i.wrapping_add(reg as u16), self.v[reg])?;
+                }
+                self.apply_load_store_quirk(vx);
+                Ok(())
+            }
+            Instruction::LoadRegisters { vx } => {
+                // Read V0..=Vx from memory starting at I
+                for reg in 0..=vx {
+                    self.v[reg] = memory.read_byte(self.i.wrapping_add(reg as u16))?;
+                }
+                self.apply_load_store_quirk(vx);
                 Ok(())
             }
-            Instruction::StoreRegisters { .. } => {
-                // TODO: Implement register storage
+            Instruction::StoreFlags { vx } => {
+                // Decode only produces x <= 7
+                self.rpl_flags[..=vx].copy_from_slice(&self.v[..=vx]);
                 Ok(())
             }
-            Instruction::LoadRegisters { .. } => {
-                // TODO: Implement register loading
+            Instruction::LoadFlags { vx } => {
+                self.v[..=vx].copy_from_slice(&self.rpl_flags[..=vx]);
                 Ok(())
             }
         }
     }
 
+    /// Apply the FX55/FX65 index quirk after transferring V0..=Vx
+    ///
+    /// Modern interpreters leave I unchanged; the original COSMAC VIP left it at I + x + 1.
+    fn apply_load_store_quirk(&mut self, vx: usize) {
+        let step = match self.index_increment {
+            IndexIncrementMode::Unchanged => return,
+            IndexIncrementMode::X => vx as u16,
+            IndexIncrementMode::XPlusOne => vx as u16 + 1,
+        };
+        self.i = self.i.wrapping_add(step) & (MEMORY_SIZE - 1) as u16;
+    }
+
+    /// Write an arithmetic result to Vx, then its flag to VF (the flag wins when x is F)
+    fn set_arithmetic_result(&mut self, vx: usize, result: u8, flag: u8) {
+        self.v[vx] = result;
+        self.v[0xF] = flag;
+    }
+
+    /// Apply the 8XY1/8XY2/8XY3 VF quirk after a logic operation
+    ///
+    /// Modern interpreters leave VF unchanged; the original COSMAC VIP cleared it.
+    fn apply_logic_vf_quirk(&mut self) {
+        if self.reset_vf_on_logic {
+            self.v[0xF] = 0;
+        }
+    }
+
     /// Call a subroutine at the given address
     fn call_subroutine(&mut self, addr: u16) -> Result<(), CpuError> {
+        let addr = validate_jump_target(addr)?;
         if self.sp as usize >= STACK_SIZE {
             return Err(CpuError::StackOverflow {
                 max_depth: STACK_SIZE,
@@ -405,7 +786,13 @@ impl Cpu {
     }
 
     /// Update timers (should be called at 60Hz)
+    ///
+    /// The tick doubles as the vertical blank, so it also ends a display wait.
     pub fn update_timers(&mut self) {
+        if self.state == CpuState::WaitingForVblank {
+            self.state = CpuState::Running;
+        }
+
         if self.delay_timer > 0 {
             self.delay_timer -= 1;
         }
@@ -437,11 +824,28 @@ impl Cpu {
         self.pc
     }
 
+    /// Get the stack pointer (number of unreturned CALLs)
+    pub fn get_sp(&self) -> u8 {
+        self.sp
+    }
+
     /// Get current index register value
     pub fn get_index(&self) -> u16 {
         self.i
     }
 
+    /// Set the index register (for debug and test setup)
+    pub fn set_index(&mut self, value: u16) -> Result<(), CpuError> {
+        if value as usize >= MEMORY_SIZE {
+            return Err(CpuError::InvalidIndex {
+                value,
+                max: (MEMORY_SIZE - 1) as u16,
+            });
+        }
+        self.i = value;
+        Ok(())
+    }
+
     /// Get current delay timer value
     pub fn get_delay_timer(&self) -> u8 {
         self.delay_timer
@@ -462,6 +866,117 @@ impl Cpu {
         self.sound_timer = value;
     }
 
+    /// Get both timers as (delay, sound)
+    pub fn timers(&self) -> (u8, u8) {
+        (self.delay_timer, self.sound_timer)
+    }
+
+    /// Set both timers at once (for save states and debugging)
+    pub fn set_timers(&mut self, delay: u8, sound: u8) {
+        self.delay_timer = delay;
+        self.sound_timer = sound;
+    }
+
+    /// Enable or disable the COSMAC VIP quirk where OR, AND and XOR reset VF
+    pub fn set_reset_vf_on_logic(&mut self, enabled: bool) {
+        self.reset_vf_on_logic = enabled;
+    }
+
+    /// Check if OR, AND and XOR reset VF
+    pub fn is_reset_vf_on_logic_enabled(&self) -> bool {
+        self.reset_vf_on_logic
+    }
+
+    /// Set how far FX55/FX65 advance I after the register transfer
+    pub fn set_index_increment(&mut self, mode: IndexIncrementMode) {
+        self.index_increment = mode;
+    }
+
+    /// Get how far FX55/FX65 advance I after the register transfer
+    pub fn index_increment(&self) -> IndexIncrementMode {
+        self.index_increment
+    }
+
+    /// Enable or disable lenient decoding of corrupted EXxx opcodes
+    pub fn set_lenient_decode(&mut self, enabled: bool) {
+        self.lenient_decode = enabled;
+    }
+
+    /// Check if corrupted EXxx opcodes decode as the nearest key-skip instruction
+    pub fn is_lenient_decode_enabled(&self) -> bool {
+        self.lenient_decode
+    }
+
+    /// Enable or disable the FX0A quirk that waits for the key to be released
+    pub fn set_wait_key_on_release(&mut self, enabled: bool) {
+        self.wait_key_on_release = enabled;
+    }
+
+    /// Check whether FX0A waits for the key to be released
+    pub fn is_wait_key_on_release_enabled(&self) -> bool {
+        self.wait_key_on_release
+    }
+
+    /// Enable or disable the DXYN quirk that waits for the next timer tick after drawing
+    pub fn set_display_wait(&mut self, enabled: bool) {
+        self.display_wait = enabled;
+    }
+
+    /// Check whether DXYN stalls until the next timer tick
+    pub fn is_display_wait_enabled(&self) -> bool {
+        self.display_wait
+    }
+
+    /// Enable or disable the FX1E quirk that flags I overflowing past 0xFFF in VF
+    pub fn set_add_index_sets_vf(&mut self, enabled: bool) {
+        self.add_index_sets_vf = enabled;
+    }
+
+    /// Check whether FX1E sets VF when I overflows past 0xFFF
+    pub fn is_add_index_sets_vf_enabled(&self) -> bool {
+        self.add_index_sets_vf
+    }
+
+    /// Enable or disable the COSMAC VIP quirk where 8XY6/8XYE shift Vy into Vx
+    pub fn set_shift_uses_vy(&mut self, enabled: bool) {
+        self.shift_uses_vy = enabled;
+    }
+
+    /// Check whether 8XY6/8XYE shift Vy into Vx
+    pub fn is_shift_uses_vy_enabled(&self) -> bool {
+        self.shift_uses_vy
+    }
+
+    /// Enable or disable the SUPER-CHIP quirk where BXNN jumps to XNN plus Vx
+    pub fn set_jump_uses_vx(&mut self, enabled: bool) {
+        self.jump_uses_vx = enabled;
+    }
+
+    /// Check whether BXNN jumps to XNN plus Vx
+    pub fn is_jump_uses_vx_enabled(&self) -> bool {
+        self.jump_uses_vx
+    }
+
+    /// Get the SUPER-CHIP RPL user flags
+    pub fn rpl_flags(&self) -> [u8; NUM_RPL_FLAGS] {
+        self.rpl_flags
+    }
+
+    /// Overwrite the SUPER-CHIP RPL user flags, e.g. to restore them from disk
+    pub fn set_rpl_flags(&mut self, flags: [u8; NUM_RPL_FLAGS]) {
+        self.rpl_flags = flags;
+    }
+
+    /// Choose whether reset keeps the RPL flags (the default) or clears them
+    pub fn set_keep_rpl_flags(&mut self, enabled: bool) {
+        self.keep_rpl_flags = enabled;
+    }
+
+    /// Check whether reset keeps the RPL flags
+    pub fn is_keep_rpl_flags_enabled(&self) -> bool {
+        self.keep_rpl_flags
+    }
+
     /// Check if sound should be playing (sound timer > 0)
     pub fn should_beep(&self) -> bool {
         self.sound_timer > 0
@@ -471,6 +986,22 @@ impl Cpu {
     pub fn get_state(&self) -> &CpuState {
         &self.state
     }
+
+    /// Get the seed the random number generator started from
+    pub fn get_seed(&self) -> u64 {
+        self.seed
+    }
+}
+
+/// Derive a random number generator seed from system entropy
+fn entropy_seed() -> u64 {
+    use std::hash::BuildHasher;
+    std::collections::hash_map::RandomState::new().hash_one(std::time::SystemTime::now())
+}
+
+/// Random source for a deserialized CPU
+fn entropy_rng() -> Box<dyn RandomSource> {
+    Box::new(SmallRng::seed_from_u64(entropy_seed()))
 }
 
 impl Default for Cpu {
@@ -479,6 +1010,15 @@ impl Default for Cpu {
     }
 }
 
+/// Check that a jump or call target is an even address an instruction can be
+/// fetched from, so a bad target fails at the jump rather than the next fetch
+fn validate_jump_target(addr: u16) -> Result<u16, CpuError> {
+    if !addr.is_multiple_of(2) || addr as usize >= MEMORY_SIZE - 1 {
+        return Err(CpuError::InvalidProgramCounter { pc: addr });
+    }
+    Ok(addr)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -613,6 +1153,259 @@ mod tests {
         assert_eq!(cpu.get_index(), 0x300);
     }
 
+    #[test]
+    fn test_add_index_wraps_within_address_space() {
+        let mut cpu = Cpu::new();
+        let mut memory = Memory::new(true);
+        let mut display = crate::Display::new();
+        let mut input = MockInput::new();
+
+        // ADD I, V0 (instruction: 0xF01E) with I = 0xFFF, V0 = 0x02
+        cpu.set_index(0xFFF).unwrap();
+        cpu.set_register(0, 0x02).unwrap();
+        memory.write_word(PROGRAM_START_ADDR, 0xF01E).unwrap();
+
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+
+        assert_eq!(cpu.get_index(), 0x001);
+    }
+
+    #[test]
+    fn test_jump_targets_are_validated() {
+        let run = |opcode: u16, v0: u8| -> (Cpu, Result<(), CpuError>) {
+            let mut cpu = Cpu::new();
+            let mut memory = Memory::new(true);
+            let mut display = crate::Display::new();
+            let mut input = MockInput::new();
+
+            cpu.set_register(0, v0).unwrap();
+            memory.write_word(PROGRAM_START_ADDR, opcode).unwrap();
+            let result = cpu.execute_cycle(&mut memory, &mut display, &mut input);
+            (cpu, result)
+        };
+        let failed_target = |result: Result<(), CpuError>| match result {
+            Err(CpuError::InstructionExecutionFailed { source, .. }) => match *source {
+                CpuError::InvalidProgramCounter { pc } => pc,
+                other => panic!("unexpected error: {}", other),
+            },
+            other => panic!("expected a failed jump, got {:?}", other),
+        };
+
+        // JP FFE is the highest target whose whole opcode is in memory
+        let (cpu, result) = run(0x1FFE, 0);
+        result.unwrap();
+        assert_eq!(cpu.get_pc(), 0xFFE);
+
+        // Odd targets, and JP V0 past the end of memory, fail at the jump
+        assert_eq!(failed_target(run(0x1FFF, 0).1), 0xFFF);
+        assert_eq!(failed_target(run(0x1201, 0).1), 0x201);
+        assert_eq!(failed_target(run(0xBFFE, 0x02).1), 0x1000);
+        assert_eq!(failed_target(run(0xBF00, 0x01).1), 0xF01);
+        run(0xBFF0, 0x0E).1.unwrap();
+
+        // A bad CALL fails before touching the stack
+        let (cpu, result) = run(0x2FFF, 0);
+        assert_eq!(failed_target(result), 0xFFF);
+        assert!(cpu.snapshot().stack.is_empty());
+        assert_eq!(cpu.get_pc(), PROGRAM_START_ADDR + 2);
+    }
+
+    #[test]
+    fn test_add_index_overflow_quirk() {
+        // ADD I, V0 (instruction: 0xF01E) from I = start with V0 = 0x10 and VF = 7
+        let add_index = |quirk: bool, start: u16| -> (u16, u8) {
+            let mut cpu = Cpu::new();
+            let mut memory = Memory::new(true);
+            let mut display = crate::Display::new();
+            let mut input = MockInput::new();
+
+            cpu.set_add_index_sets_vf(quirk);
+            cpu.set_index(start).unwrap();
+            cpu.set_register(0, 0x10).unwrap();
+            cpu.set_register(0xF, 7).unwrap();
+            memory.write_word(PROGRAM_START_ADDR, 0xF01E).unwrap();
+            cpu.execute_cycle(&mut memory, &mut display, &mut input)
+                .unwrap();
+            (cpu.get_index(), cpu.get_register(0xF).unwrap())
+        };
+
+        // Overflow wraps I either way; only the quirk reports it in VF
+        assert_eq!(add_index(true, 0xFF8), (0x008, 1));
+        assert_eq!(add_index(false, 0xFF8), (0x008, 7));
+        // Landing exactly on 0xFFF is not an overflow
+        assert_eq!(add_index(true, 0xFEF), (0xFFF, 0));
+        assert_eq!(add_index(false, 0xFEF), (0xFFF, 7));
+    }
+
+    #[test]
+    fn test_shift_quirk() {
+        // Run `opcode` with V1 = 0x81 and V2 = 0x06, returning (V1, VF)
+        let shift = |quirk: bool, opcode: u16| -> (u8, u8) {
+            let mut cpu = Cpu::new();
+            let mut memory = Memory::new(true);
+            let mut display = crate::Display::new();
+            let mut input = MockInput::new();
+
+            cpu.set_shift_uses_vy(quirk);
+            cpu.set_register(1, 0x81).unwrap();
+            cpu.set_register(2, 0x06).unwrap();
+            memory.write_word(PROGRAM_START_ADDR, opcode).unwrap();
+            cpu.execute_cycle(&mut memory, &mut display, &mut input)
+                .unwrap();
+            (cpu.get_register(1).unwrap(), cpu.get_register(0xF).unwrap())
+        };
+
+        // SHR V1, V2 and SHL V1, V2: in place, or V2 shifted into V1
+        assert_eq!(shift(false, 0x8126), (0x40, 1));
+        assert_eq!(shift(true, 0x8126), (0x03, 0));
+        assert_eq!(shift(false, 0x812E), (0x02, 1));
+        assert_eq!(shift(true, 0x812E), (0x0C, 0));
+    }
+
+    #[test]
+    fn test_jump_quirk() {
+        // JP V0, 234 (instruction: 0xB234) with V0 = 0x10 and V2 = 0x20
+        let jump = |quirk: bool| -> u16 {
+            let mut cpu = Cpu::new();
+            let mut memory = Memory::new(true);
+            let mut display = crate::Display::new();
+            let mut input = MockInput::new();
+
+            cpu.set_jump_uses_vx(quirk);
+            cpu.set_register(0, 0x10).unwrap();
+            cpu.set_register(2, 0x20).unwrap();
+            memory.write_word(PROGRAM_START_ADDR, 0xB234).unwrap();
+            cpu.execute_cycle(&mut memory, &mut display, &mut input)
+                .unwrap();
+            cpu.get_pc()
+        };
+
+        assert_eq!(jump(false), 0x244);
+        // SUPER-CHIP reads it as JP V2, 234
+        assert_eq!(jump(true), 0x254);
+    }
+
+    #[test]
+    fn test_random_is_reproducible_from_seed() {
+        // Run RND V0, 0xFF (instruction: 0xC0FF) repeatedly and collect V0
+        let rnd_stream = |cpu: &mut Cpu| -> Vec<u8> {
+            let mut memory = Memory::new(true);
+            let mut display = crate::Display::new();
+            let mut input = MockInput::new();
+            (0..16)
+                .map(|_| {
+                    memory.write_word(PROGRAM_START_ADDR, 0xC0FF).unwrap();
+                    cpu.execute_cycle(&mut memory, &mut display, &mut input)
+                        .unwrap();
+                    cpu.pc = PROGRAM_START_ADDR;
+                    cpu.get_register(0).unwrap()
+                })
+                .collect()
+        };
+
+        let mut cpu = Cpu::with_seed(1234);
+        assert_eq!(cpu.get_seed(), 1234);
+        let first = rnd_stream(&mut cpu);
+
+        // Same seed, same stream - both from a fresh CPU and after reset
+        assert_eq!(rnd_stream(&mut Cpu::with_seed(1234)), first);
+        cpu.reset();
+        assert_eq!(rnd_stream(&mut cpu), first);
+
+        assert_ne!(rnd_stream(&mut Cpu::with_seed(4321)), first);
+        assert!(first.iter().any(|&value| value != first[0]));
+    }
+
+    #[test]
+    fn test_random_known_sequence_for_fixed_seed() {
+        let mut cpu = Cpu::with_seed(42);
+        let mut memory = Memory::new(true);
+        let mut display = crate::Display::new();
+        let mut input = MockInput::new();
+
+        // RND V0, 0xFF then RND V1, 0x0F
+        let mut values = Vec::new();
+        for _ in 0..4 {
+            memory.write_word(PROGRAM_START_ADDR, 0xC0FF).unwrap();
+            memory.write_word(PROGRAM_START_ADDR + 2, 0xC10F).unwrap();
+            cpu.pc = PROGRAM_START_ADDR;
+            cpu.execute_cycle(&mut memory, &mut display, &mut input)
+                .unwrap();
+            cpu.execute_cycle(&mut memory, &mut display, &mut input)
+                .unwrap();
+            values.push(cpu.get_register(0).unwrap());
+            values.push(cpu.get_register(1).unwrap());
+        }
+
+        assert_eq!(values, vec![79, 4, 251, 0, 56, 0, 255, 7]);
+    }
+
+    #[test]
+    fn test_random_from_scripted_sequence() {
+        use crate::random::{FileSequence, SequenceEnd};
+
+        let mut cpu = Cpu::with_seed(42);
+        let mut memory = Memory::new(true);
+        let mut display = crate::Display::new();
+        let mut input = MockInput::new();
+        let sequence = FileSequence::new(vec![0x12, 0xF7, 0x80], SequenceEnd::Error).unwrap();
+        cpu.set_random_source(Box::new(sequence));
+
+        // RND V0, 0xFF; RND V1, 0x0F; RND V2, 0xFF; RND V3, 0xFF
+        for (offset, opcode) in [0xC0FF, 0xC10F, 0xC2FF, 0xC3FF].into_iter().enumerate() {
+            memory
+                .write_word(PROGRAM_START_ADDR + 2 * offset as u16, opcode)
+                .unwrap();
+        }
+        for _ in 0..3 {
+            cpu.execute_cycle(&mut memory, &mut display, &mut input)
+                .unwrap();
+        }
+        assert_eq!(cpu.get_register(0).unwrap(), 0x12);
+        assert_eq!(cpu.get_register(1).unwrap(), 0x07);
+        assert_eq!(cpu.get_register(2).unwrap(), 0x80);
+
+        // Out of bytes: RND fails instead of inventing a value
+        let err = cpu
+            .execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            CpuError::InstructionExecutionFailed { ref source, .. }
+                if matches!(**source, CpuError::Random(RandomError::Exhausted { len: 3 }))
+        ));
+    }
+
+    #[test]
+    fn test_set_index_used_by_draw() {
+        let mut cpu = Cpu::new();
+        let mut memory = Memory::new(true);
+        let mut display = crate::Display::new();
+        let mut input = MockInput::new();
+
+        // Out-of-range values are rejected and leave I untouched
+        assert!(matches!(
+            cpu.set_index(MEMORY_SIZE as u16),
+            Err(CpuError::InvalidIndex { .. })
+        ));
+        assert_eq!(cpu.get_index(), 0);
+
+        // Point I at sprite data directly, then draw from it
+        memory.write_byte(0x400, 0b11000000).unwrap();
+        cpu.set_index(0x400).unwrap();
+        assert_eq!(cpu.get_index(), 0x400);
+
+        // DRW V0, V1, 1 (instruction: 0xD011)
+        memory.write_word(PROGRAM_START_ADDR, 0xD011).unwrap();
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+
+        assert!(display.get_pixel(0, 0));
+        assert!(display.get_pixel(1, 0));
+        assert!(!display.get_pixel(2, 0));
+    }
+
     #[test]
     fn test_timer_updates() {
         let mut cpu = Cpu::new();
@@ -633,6 +1426,20 @@ mod tests {
         assert!(!cpu.should_beep());
     }
 
+    #[test]
+    fn test_timers_pair_round_trip() {
+        let mut cpu = Cpu::new();
+        assert_eq!(cpu.timers(), (0, 0));
+
+        cpu.set_timers(42, 7);
+        assert_eq!(cpu.timers(), (42, 7));
+        assert_eq!(cpu.get_delay_timer(), 42);
+        assert_eq!(cpu.get_sound_timer(), 7);
+
+        cpu.update_timers();
+        assert_eq!(cpu.timers(), (41, 6));
+    }
+
     #[test]
     fn test_unknown_instruction() {
         let mut cpu = Cpu::new();
@@ -676,6 +1483,39 @@ mod tests {
         assert!(!display.get_pixel(20, 15));
     }
 
+    /// Execute a single opcode against a display with one lit pixel at (10, 5)
+    fn run_scroll(opcode: u16) -> crate::Display {
+        let mut cpu = Cpu::new();
+        let mut memory = Memory::new(true);
+        let mut display = crate::Display::new();
+        let mut input = MockInput::new();
+
+        display.set_pixel(10, 5, true);
+        memory.write_word(PROGRAM_START_ADDR, opcode).unwrap();
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+        assert_eq!(display.get_stats().pixels_on, 1);
+        display
+    }
+
+    #[test]
+    fn test_scroll_down_instruction() {
+        // SCD 4 (0x00C4)
+        assert!(run_scroll(0x00C4).get_pixel(10, 9));
+    }
+
+    #[test]
+    fn test_scroll_right_instruction() {
+        // SCR (0x00FB)
+        assert!(run_scroll(0x00FB).get_pixel(14, 5));
+    }
+
+    #[test]
+    fn test_scroll_left_instruction() {
+        // SCL (0x00FC)
+        assert!(run_scroll(0x00FC).get_pixel(6, 5));
+    }
+
     #[test]
     fn test_draw_instruction() {
         let mut cpu = Cpu::new();
@@ -712,6 +1552,224 @@ mod tests {
         assert_eq!(cpu.get_register(0xF).unwrap(), 0);
     }
 
+    #[test]
+    fn test_store_bcd_instruction() {
+        for (value, digits) in [(0, [0, 0, 0]), (255, [2, 5, 5]), (100, [1, 0, 0])] {
+            let mut cpu = Cpu::new();
+            let mut memory = Memory::new(true);
+            let mut display = crate::Display::new();
+            let mut input = MockInput::new();
+
+            // Set up CPU state for the conversion
+            let bcd_addr = 0x300;
+            cpu.v[3] = value;
+            cpu.i = bcd_addr;
+
+            // LD B, V3 (instruction: 0xF333)
+            memory.write_word(PROGRAM_START_ADDR, 0xF333).unwrap();
+
+            cpu.execute_cycle(&mut memory, &mut display, &mut input)
+                .unwrap();
+
+            // Verify hundreds, tens and ones were written in order
+            assert_eq!(memory.read_byte(bcd_addr).unwrap(), digits[0]);
+            assert_eq!(memory.read_byte(bcd_addr + 1).unwrap(), digits[1]);
+            assert_eq!(memory.read_byte(bcd_addr + 2).unwrap(), digits[2]);
+
+            // I is left unchanged
+            assert_eq!(cpu.get_index(), bcd_addr);
+        }
+    }
+
+    #[test]
+    fn test_store_bcd_out_of_bounds_writes_nothing() {
+        let mut cpu = Cpu::new();
+        let mut memory = Memory::new(true);
+        let mut display = crate::Display::new();
+        let mut input = MockInput::new();
+
+        // I + 2 runs past the end of memory
+        let last = (MEMORY_SIZE - 1) as u16;
+        cpu.v[0] = 123;
+        cpu.i = last - 1;
+
+        // LD B, V0 (instruction: 0xF033)
+        memory.write_word(PROGRAM_START_ADDR, 0xF033).unwrap();
+
+        let result = cpu.execute_cycle(&mut memory, &mut display, &mut input);
+        assert!(matches!(
+            result,
+            Err(CpuError::InstructionExecutionFailed { source, .. })
+                if matches!(*source, CpuError::Memory(MemoryError::OutOfBounds { addr, .. }) if addr == last + 1)
+        ));
+        assert_eq!(memory.read_byte(last - 1).unwrap(), 0);
+        assert_eq!(memory.read_byte(last).unwrap(), 0);
+    }
+
+    #[test]
+    fn test_store_bcd_write_protected() {
+        let mut cpu = Cpu::new();
+        let mut memory = Memory::new(true);
+        let mut display = crate::Display::new();
+        let mut input = MockInput::new();
+
+        // Point I into the protected interpreter area
+        cpu.v[0] = 123;
+        cpu.i = 0x100;
+
+        // LD B, V0 (instruction: 0xF033)
+        memory.write_word(PROGRAM_START_ADDR, 0xF033).unwrap();
+
+        let result = cpu.execute_cycle(&mut memory, &mut display, &mut input);
+        assert!(matches!(
+            result,
+            Err(CpuError::InstructionExecutionFailed { source, .. })
+                if matches!(*source, CpuError::Memory(MemoryError::WriteProtected { addr: 0x100 }))
+        ));
+    }
+
+    #[test]
+    fn test_store_and_load_registers() {
+        let mut cpu = Cpu::new();
+        let mut memory = Memory::new(true);
+        let mut display = crate::Display::new();
+        let mut input = MockInput::new();
+
+        // Fill V0..V5 and leave V6 as a sentinel that must not be stored
+        let values = [0x11, 0x22, 0x33, 0x44, 0x55, 0x66];
+        cpu.v[..6].copy_from_slice(&values);
+        cpu.v[6] = 0x77;
+        cpu.i = 0x300;
+
+        // LD [I], V5 (instruction: 0xF555)
+        memory.write_word(PROGRAM_START_ADDR, 0xF555).unwrap();
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+
+        assert_eq!(cpu.get_index(), 0x300); // Modern behavior: I unchanged
+        for (offset, &value) in values.iter().enumerate() {
+            assert_eq!(memory.read_byte(0x300 + offset as u16).unwrap(), value);
+        }
+        assert_eq!(memory.read_byte(0x306).unwrap(), 0);
+
+        // LD V5, [I] (instruction: 0xF565) into a fresh CPU
+        let mut fresh = Cpu::new();
+        fresh.i = 0x300;
+        memory.write_word(PROGRAM_START_ADDR, 0xF565).unwrap();
+        fresh
+            .execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+
+        assert_eq!(fresh.v[..6], values);
+        assert_eq!(fresh.v[6], 0);
+        assert_eq!(fresh.get_index(), 0x300);
+    }
+
+    #[test]
+    fn test_store_registers_index_quirk() {
+        // LD [I], V2 and LD V2, [I] starting from I = 0x300
+        for opcode in [0xF255, 0xF265] {
+            for (mode, expected) in [
+                (IndexIncrementMode::Unchanged, 0x300),
+                (IndexIncrementMode::X, 0x302),
+                (IndexIncrementMode::XPlusOne, 0x303),
+            ] {
+                let mut cpu = Cpu::new();
+                let mut memory = Memory::new(true);
+                let mut display = crate::Display::new();
+                let mut input = MockInput::new();
+
+                cpu.set_index_increment(mode);
+                cpu.i = 0x300;
+                memory.write_word(PROGRAM_START_ADDR, opcode).unwrap();
+                cpu.execute_cycle(&mut memory, &mut display, &mut input)
+                    .unwrap();
+
+                assert_eq!(cpu.get_index(), expected, "{:04X} {:?}", opcode, mode);
+            }
+        }
+    }
+
+    #[test]
+    fn test_arithmetic_on_vf_keeps_the_flag() {
+        let run = |opcode: u16, vf: u8, v1: u8| -> u8 {
+            let mut cpu = Cpu::new();
+            let mut memory = Memory::new(true);
+            let mut display = crate::Display::new();
+            let mut input = MockInput::new();
+
+            cpu.v[0xF] = vf;
+            cpu.v[1] = v1;
+            memory.write_word(PROGRAM_START_ADDR, opcode).unwrap();
+            cpu.execute_cycle(&mut memory, &mut display, &mut input)
+                .unwrap();
+            cpu.v[0xF]
+        };
+
+        // ADD VF, V1: 0x10 + 0x20 = 0x30 with no carry
+        assert_eq!(run(0x8F14, 0x10, 0x20), 0);
+        assert_eq!(run(0x8F14, 0xF0, 0x20), 1);
+
+        // SUB VF, V1: 0x30 - 0x10 = 0x20 with no borrow
+        assert_eq!(run(0x8F15, 0x30, 0x10), 1);
+        assert_eq!(run(0x8F15, 0x10, 0x30), 0);
+
+        // SUBN VF, V1: 0x10 - 0x30 borrows
+        assert_eq!(run(0x8F17, 0x30, 0x10), 0);
+
+        // SHR VF: 0x03 >> 1 = 0x01, shifted-out bit 1
+        assert_eq!(run(0x8F06, 0x03, 0), 1);
+        assert_eq!(run(0x8F06, 0x04, 0), 0);
+
+        // SHL VF: 0x81 << 1 = 0x02, shifted-out bit 1
+        assert_eq!(run(0x8F0E, 0x81, 0), 1);
+        assert_eq!(run(0x8F0E, 0x41, 0), 0);
+    }
+
+    #[test]
+    fn test_logic_ops_reset_vf_only_with_quirk() {
+        let run_and = |quirk: bool| -> (u8, u8) {
+            let mut cpu = Cpu::new();
+            let mut memory = Memory::new(true);
+            let mut display = crate::Display::new();
+            let mut input = MockInput::new();
+
+            cpu.set_reset_vf_on_logic(quirk);
+            cpu.v[0] = 0b1100;
+            cpu.v[1] = 0b1010;
+            cpu.v[0xF] = 0x55;
+
+            // AND V0, V1 (instruction: 0x8012)
+            memory.write_word(PROGRAM_START_ADDR, 0x8012).unwrap();
+            cpu.execute_cycle(&mut memory, &mut display, &mut input)
+                .unwrap();
+            (cpu.v[0], cpu.v[0xF])
+        };
+
+        assert_eq!(run_and(false), (0b1000, 0x55));
+        assert_eq!(run_and(true), (0b1000, 0));
+    }
+
+    #[test]
+    fn test_store_registers_write_protected() {
+        let mut cpu = Cpu::new();
+        let mut memory = Memory::new(true);
+        let mut display = crate::Display::new();
+        let mut input = MockInput::new();
+
+        cpu.i = 0x1FE;
+
+        // LD [I], V1 (instruction: 0xF155) into the protected interpreter area
+        memory.write_word(PROGRAM_START_ADDR, 0xF155).unwrap();
+        let result = cpu.execute_cycle(&mut memory, &mut display, &mut input);
+
+        assert!(matches!(
+            result,
+            Err(CpuError::InstructionExecutionFailed { source, .. })
+                if matches!(*source, CpuError::Memory(MemoryError::WriteProtected { .. }))
+        ));
+    }
+
     #[test]
     fn test_draw_instruction_collision() {
         let mut cpu = Cpu::new();
@@ -744,6 +1802,41 @@ mod tests {
         assert_eq!(cpu.get_register(0xF).unwrap(), 1);
     }
 
+    #[test]
+    fn test_draw_instruction_resets_collision_flag() {
+        let mut cpu = Cpu::new();
+        let mut memory = Memory::new(true);
+        let mut display = crate::Display::new();
+        let mut input = MockInput::new();
+
+        // Single pixel sprite
+        let sprite_addr = 0x300;
+        memory.write_byte(sprite_addr, 0b10000000).unwrap();
+        cpu.i = sprite_addr;
+
+        // Three DRW V0, V1, 1 instructions in a row
+        memory.write_word(PROGRAM_START_ADDR, 0xD011).unwrap();
+        memory.write_word(PROGRAM_START_ADDR + 2, 0xD011).unwrap();
+        memory.write_word(PROGRAM_START_ADDR + 4, 0xD011).unwrap();
+
+        // First draw lights the pixel, second draw erases it (collision)
+        cpu.v[0] = 10;
+        cpu.v[1] = 5;
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+        assert_eq!(cpu.get_register(0xF).unwrap(), 1);
+
+        // Third draw into empty space must clear the stale flag
+        cpu.v[0] = 40;
+        cpu.v[1] = 20;
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+        assert_eq!(cpu.get_register(0xF).unwrap(), 0);
+        assert!(display.get_pixel(40, 20));
+    }
+
     #[test]
     fn test_skip_key_pressed_instruction() {
         let mut cpu = Cpu::new();
@@ -839,6 +1932,55 @@ mod tests {
         assert_eq!(*cpu.get_state(), CpuState::Running);
     }
 
+    #[test]
+    fn test_last_executed_records_skip_taken() {
+        let run_skip = |v0: u8| -> LastInstruction {
+            let mut cpu = Cpu::new();
+            let mut memory = Memory::new(true);
+            let mut display = Display::new();
+            let mut input = MockInput::new();
+
+            // SE V0, 42 (instruction: 0x3042)
+            cpu.set_register(0, v0).unwrap();
+            memory.write_word(PROGRAM_START_ADDR, 0x3042).unwrap();
+            assert_eq!(cpu.last_executed(), None);
+            cpu.execute_cycle(&mut memory, &mut display, &mut input)
+                .unwrap();
+            cpu.last_executed().unwrap()
+        };
+
+        let taken = run_skip(0x42);
+        assert_eq!(taken.addr, PROGRAM_START_ADDR);
+        assert_eq!(taken.opcode, 0x3042);
+        assert_eq!(
+            taken.instruction,
+            Instruction::SkipEqImm { vx: 0, value: 0x42 }
+        );
+        assert_eq!(taken.pc_after, PROGRAM_START_ADDR + 4);
+        assert_eq!(taken.pc_delta(), 4);
+
+        let not_taken = run_skip(0x41);
+        assert_eq!(not_taken.pc_before, PROGRAM_START_ADDR);
+        assert_eq!(not_taken.pc_delta(), 2);
+    }
+
+    #[test]
+    fn test_last_executed_cleared_while_waiting() {
+        let mut cpu = Cpu::new();
+        let mut memory = Memory::new(true);
+        let mut display = Display::new();
+        let mut input = MockInput::new();
+
+        // LD V1, K (instruction: 0xF10A) executes, then the next cycle only waits
+        memory.write_word(PROGRAM_START_ADDR, 0xF10A).unwrap();
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+        assert_eq!(cpu.last_executed().unwrap().opcode, 0xF10A);
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+        assert_eq!(cpu.last_executed(), None);
+    }
+
     #[test]
     fn test_skip_key_pressed_skips_when_pressed() {
         let mut cpu = Cpu::new();
@@ -970,6 +2112,116 @@ mod tests {
         assert_eq!(cpu.get_pc(), initial_pc + 2);
     }
 
+    #[test]
+    fn test_display_wait_quirk() {
+        let mut memory = Memory::new(true);
+        let mut display = Display::new();
+        let mut input = MockInput::new();
+        // DRW V0, V1, 1; ADD V0, 8
+        memory.write_word(PROGRAM_START_ADDR, 0xD011).unwrap();
+        memory.write_word(PROGRAM_START_ADDR + 2, 0x7008).unwrap();
+
+        let mut cpu = Cpu::new();
+        cpu.set_display_wait(true);
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+        assert_eq!(*cpu.get_state(), CpuState::WaitingForVblank);
+
+        // Stalled until the frame ends
+        for _ in 0..5 {
+            cpu.execute_cycle(&mut memory, &mut display, &mut input)
+                .unwrap();
+        }
+        assert_eq!(cpu.get_pc(), PROGRAM_START_ADDR + 2);
+        assert_eq!(cpu.get_register(0).unwrap(), 0);
+
+        cpu.update_timers();
+        assert_eq!(*cpu.get_state(), CpuState::Running);
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+        assert_eq!(cpu.get_register(0).unwrap(), 8);
+    }
+
+    #[test]
+    fn test_rpl_flags_store_and_load() {
+        let mut memory = Memory::new(true);
+        let mut display = Display::new();
+        let mut input = MockInput::new();
+        // LD R, V3; LD V3, R
+        memory.write_word(PROGRAM_START_ADDR, 0xF375).unwrap();
+        memory.write_word(PROGRAM_START_ADDR + 2, 0xF385).unwrap();
+
+        let mut cpu = Cpu::new();
+        for (reg, value) in [0x11, 0x22, 0x33, 0x44, 0x55].into_iter().enumerate() {
+            cpu.v[reg] = value;
+        }
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+        assert_eq!(cpu.rpl_flags(), [0x11, 0x22, 0x33, 0x44, 0, 0, 0, 0]);
+
+        // Flags outlive a reset; V0..V3 come back into a cleared register file
+        cpu.reset();
+        assert_eq!(cpu.get_register(0).unwrap(), 0);
+        cpu.pc = PROGRAM_START_ADDR + 2;
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+        assert_eq!(&cpu.v[..5], &[0x11, 0x22, 0x33, 0x44, 0]);
+
+        cpu.set_keep_rpl_flags(false);
+        cpu.reset();
+        assert_eq!(cpu.rpl_flags(), [0; NUM_RPL_FLAGS]);
+    }
+
+    #[test]
+    fn test_wait_key_on_release_quirk() {
+        let mut memory = Memory::new(true);
+        let mut display = Display::new();
+        // LD V3, K; LD V4, K
+        memory.write_word(PROGRAM_START_ADDR, 0xF30A).unwrap();
+        memory.write_word(PROGRAM_START_ADDR + 2, 0xF40A).unwrap();
+
+        // Release edge: the key is latched on press and stored on release
+        let mut cpu = Cpu::new();
+        cpu.set_wait_key_on_release(true);
+        let mut input = MockInput::new();
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+        assert_eq!(*cpu.get_state(), CpuState::WaitingForKey { vx: 3 });
+
+        input.press_key(0x5).unwrap();
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+        assert_eq!(
+            *cpu.get_state(),
+            CpuState::WaitingForRelease { vx: 3, key: 0x5 }
+        );
+        assert_eq!(cpu.get_register(3).unwrap(), 0);
+
+        // Held: still waiting
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+        assert_eq!(
+            *cpu.get_state(),
+            CpuState::WaitingForRelease { vx: 3, key: 0x5 }
+        );
+
+        input.release_key(0x5).unwrap();
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+        assert_eq!(*cpu.get_state(), CpuState::Running);
+        assert_eq!(cpu.get_register(3).unwrap(), 0x5);
+        assert_eq!(cpu.get_pc(), PROGRAM_START_ADDR + 2);
+
+        // Press edge (default): the same press finishes FX0A at once
+        let mut cpu = Cpu::new();
+        let mut input = MockInput::new();
+        input.press_key(0x5).unwrap();
+        cpu.execute_cycle(&mut memory, &mut display, &mut input)
+            .unwrap();
+        assert_eq!(*cpu.get_state(), CpuState::Running);
+        assert_eq!(cpu.get_register(3).unwrap(), 0x5);
+    }
+
     #[test]
     fn test_wait_key_state_persistence() {
         let mut cpu = Cpu::new();
@@ -1000,4 +2252,57 @@ mod tests {
         assert_eq!(cpu.get_register(1).unwrap(), 0xA);
         assert_eq!(*cpu.get_state(), CpuState::Running);
     }
+
+    #[test]
+    fn test_breakpoints_halt_before_fetch() {
+        let mut cpu = Cpu::new();
+        let mut memory = Memory::new(true);
+        let mut display = Display::new();
+        let mut input = MockInput::new();
+
+        // LD V0, 01; CALL 208; JP 204; (pad); ADD V0, 01; RET
+        memory
+            .load_rom(&[
+                0x60, 0x01, 0x22, 0x08, 0x12, 0x04, 0x00, 0x00, 0x70, 0x01, 0x00, 0xEE,
+            ])
+            .unwrap();
+
+        cpu.add_breakpoint(0x208);
+        let reason = cpu
+            .step_until_break(&mut memory, &mut display, &mut input, 100)
+            .unwrap();
+        assert_eq!(reason, BreakReason::Breakpoint { addr: 0x208 });
+        assert_eq!(cpu.breakpoint_hit(), Some(0x208));
+
+        // Stopped in front of ADD V0, 01 inside the subroutine
+        let snapshot = cpu.snapshot();
+        assert_eq!(snapshot.pc, 0x208);
+        assert_eq!(snapshot.v[0], 0x01);
+        assert_eq!(snapshot.sp, 1);
+        assert_eq!(snapshot.stack, vec![0x204]);
+        assert_eq!(snapshot.state, CpuState::Running);
+
+        // Resuming runs past the breakpoint and on to the next one
+        cpu.add_breakpoint(0x204);
+        let reason = cpu
+            .step_until_break(&mut memory, &mut display, &mut input, 100)
+            .unwrap();
+        assert_eq!(reason, BreakReason::Breakpoint { addr: 0x204 });
+        assert_eq!(cpu.get_register(0).unwrap(), 0x02);
+        assert!(cpu.snapshot().stack.is_empty());
+
+        // The JP 204 loop hits its breakpoint on every pass
+        let reason = cpu
+            .step_until_break(&mut memory, &mut display, &mut input, 100)
+            .unwrap();
+        assert_eq!(reason, BreakReason::Breakpoint { addr: 0x204 });
+
+        assert!(cpu.remove_breakpoint(0x204));
+        assert!(!cpu.remove_breakpoint(0x204));
+        let reason = cpu
+            .step_until_break(&mut memory, &mut display, &mut input, 10)
+            .unwrap();
+        assert_eq!(reason, BreakReason::CycleLimit);
+        assert_eq!(cpu.breakpoint_hit(), None);
+    }
 }
@@ -3,9 +3,11 @@
 //! This module provides basic disassembly capabilities to help understand
 //! what instructions a ROM uses, which is useful for implementing missing opcodes.
 
-use crate::constants::PROGRAM_START_ADDR;
+use crate::constants::{MEMORY_SIZE, PROGRAM_START_ADDR};
 use crate::instruction::{DecodeError, Instruction, decode_opcode};
 use crate::memory::{Memory, MemoryError};
+use serde::Serialize;
+use std::collections::{BTreeSet, HashSet};
 use thiserror::Error;
 
 /// Disassembly errors
@@ -16,74 +18,555 @@ pub enum DisassemblyError {
 
     #[error("Instruction decode error: {0}")]
     Decode(#[from] DecodeError),
+
+    #[error("Unknown instruction family: {name}")]
+    UnknownFamily { name: String },
+}
+
+/// Instruction families counted by `InstructionAnalysis`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InstructionFamily {
+    System,
+    Jump,
+    Call,
+    SkipEqImm,
+    SkipNeImm,
+    SkipEqReg,
+    LoadImm,
+    AddImm,
+    Arithmetic,
+    SkipNeReg,
+    SetIndex,
+    JumpV0,
+    Random,
+    Draw,
+    Input,
+    Misc,
+    Unknown,
+}
+
+impl InstructionFamily {
+    /// All families, in summary order
+    pub const ALL: [Self; 17] = [
+        Self::System,
+        Self::Jump,
+        Self::Call,
+        Self::SkipEqImm,
+        Self::SkipNeImm,
+        Self::SkipEqReg,
+        Self::LoadImm,
+        Self::AddImm,
+        Self::Arithmetic,
+        Self::SkipNeReg,
+        Self::SetIndex,
+        Self::JumpV0,
+        Self::Random,
+        Self::Draw,
+        Self::Input,
+        Self::Misc,
+        Self::Unknown,
+    ];
+
+    /// Name used to address the family on the command line
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::System => "system",
+            Self::Jump => "jump",
+            Self::Call => "call",
+            Self::SkipEqImm => "skip_eq_imm",
+            Self::SkipNeImm => "skip_ne_imm",
+            Self::SkipEqReg => "skip_eq_reg",
+            Self::LoadImm => "load_imm",
+            Self::AddImm => "add_imm",
+            Self::Arithmetic => "arithmetic",
+            Self::SkipNeReg => "skip_ne_reg",
+            Self::SetIndex => "set_index",
+            Self::JumpV0 => "jump_v0",
+            Self::Random => "random",
+            Self::Draw => "draw",
+            Self::Input => "input",
+            Self::Misc => "misc",
+            Self::Unknown => "unknown",
+        }
+    }
+
+    /// Human-readable label used in the analysis summary
+    fn label(self) -> &'static str {
+        match self {
+            Self::System => "System instructions",
+            Self::Jump => "Jump instructions",
+            Self::Call => "Call instructions",
+            Self::SkipEqImm => "Skip if equal (immediate)",
+            Self::SkipNeImm => "Skip if not equal (immediate)",
+            Self::SkipEqReg => "Skip if equal (register)",
+            Self::LoadImm => "Load immediate",
+            Self::AddImm => "Add immediate",
+            Self::Arithmetic => "Arithmetic instructions",
+            Self::SkipNeReg => "Skip if not equal (register)",
+            Self::SetIndex => "Set index register",
+            Self::JumpV0 => "Jump with V0 offset",
+            Self::Random => "Random number",
+            Self::Draw => "Draw sprite",
+            Self::Input => "Input instructions",
+            Self::Misc => "Miscellaneous F-type",
+            Self::Unknown => "Unknown instructions",
+        }
+    }
+}
+
+impl std::str::FromStr for InstructionFamily {
+    type Err = DisassemblyError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "system" => Ok(Self::System),
+            "jump" => Ok(Self::Jump),
+            "call" => Ok(Self::Call),
+            "skip_eq_imm" => Ok(Self::SkipEqImm),
+            "skip_ne_imm" => Ok(Self::SkipNeImm),
+            "skip_eq_reg" => Ok(Self::SkipEqReg),
+            "load_imm" => Ok(Self::LoadImm),
+            "add_imm" => Ok(Self::AddImm),
+            "arithmetic" => Ok(Self::Arithmetic),
+            "skip_ne_reg" => Ok(Self::SkipNeReg),
+            "set_index" => Ok(Self::SetIndex),
+            "jump_v0" => Ok(Self::JumpV0),
+            "random" => Ok(Self::Random),
+            "draw" => Ok(Self::Draw),
+            "input" => Ok(Self::Input),
+            "misc" => Ok(Self::Misc),
+            "unknown" => Ok(Self::Unknown),
+            _ => Err(DisassemblyError::UnknownFamily {
+                name: name.to_string(),
+            }),
+        }
+    }
 }
 
 /// Disassemble a ROM and return a list of instructions with their addresses
+///
+/// Scans every word of the loaded ROM ([`Memory::rom_len`] bytes from
+/// PROGRAM_START_ADDR). Words that don't decode, and zero words, become data
+/// entries so code after sprite data is still listed. An odd trailing byte is
+/// read as a word padded with zero.
 pub fn disassemble_rom(memory: &Memory) -> Result<Vec<DisassembledInstruction>, DisassemblyError> {
     let mut instructions = Vec::new();
-    let mut addr = PROGRAM_START_ADDR;
-
-    // Scan through memory looking for valid instructions
-    while addr < 0x1000 - 1 {
-        // Try to read a word at this address
-        if let Ok(opcode) = memory.read_word(addr) {
-            // If it's all zeros, we've probably hit the end of the ROM
-            if opcode == 0x0000 {
-                break;
-            }
+    let end = PROGRAM_START_ADDR as usize + memory.rom_len();
 
-            // Try to decode the opcode - if it fails, we've probably hit sprite data
-            match decode_opcode(opcode) {
-                Ok(decoded) => {
-                    let instruction = DisassembledInstruction {
-                        address: addr,
-                        opcode,
-                        instruction: decoded,
-                    };
-                    instructions.push(instruction);
-                }
-                Err(_) => {
-                    // Hit unknown instruction (probably sprite data) - stop disassembly
-                    break;
-                }
-            }
+    for addr in (PROGRAM_START_ADDR as usize..end).step_by(2) {
+        let address = addr as u16;
+        let opcode = memory.read_word(address)?;
+        let instruction = match opcode {
+            0x0000 => None,
+            _ => decode_opcode(opcode).ok(),
+        };
+        instructions.push(DisassembledInstruction {
+            address,
+            opcode,
+            instruction,
+            label: None,
+            labeled_target: false,
+            data_byte: false,
+        });
+    }
 
-            addr += 2;
+    Ok(instructions)
+}
+
+/// Disassemble by following control flow from `entry` instead of decoding linearly
+///
+/// Every address reachable through fall-through, skips, jumps and calls is
+/// code; everything else in the loaded ROM is listed as data words, so sprite
+/// bytes aren't misread as instructions. As in [`emit_asm`], the addresses in
+/// the [`branch_targets`] of the traced code get `L_NNNN` labels, so sprite
+/// data loaded with `LD I` is named too. `JP V0, NNN` depends on a runtime
+/// register, so its target is treated as unknown and tracing stops there.
+///
+/// Like the CPU, tracing only follows even jump and call targets below
+/// 0xFFF; any other target faults at the jump, so tracing stops there too.
+/// The one way to reach odd-aligned code is an odd `entry`, and then the
+/// byte in front of it is listed on its own (`DB NN`).
+pub fn trace_disassemble(
+    memory: &Memory,
+    entry: u16,
+) -> Result<Vec<DisassembledInstruction>, DisassemblyError> {
+    let start = PROGRAM_START_ADDR;
+    let end = PROGRAM_START_ADDR as usize + memory.rom_len();
+    let in_rom = |addr: u16| addr >= start && (addr as usize) < end;
+    let fetchable = |addr: u16| addr.is_multiple_of(2) && (addr as usize) < MEMORY_SIZE - 1;
+
+    let mut code: HashSet<u16> = HashSet::new();
+    let mut pending = vec![entry];
+    while let Some(addr) = pending.pop() {
+        if !in_rom(addr) || code.contains(&addr) {
+            continue;
+        }
+        let Ok(instruction) = decode_opcode(memory.read_word(addr)?) else {
+            continue;
+        };
+        code.insert(addr);
+        let next = addr.wrapping_add(2);
+        match instruction {
+            Instruction::Jump { addr: target } if fetchable(target) => pending.push(target),
+            Instruction::Call { addr: target } if fetchable(target) => {
+                pending.extend([target, next]);
+            }
+            Instruction::Jump { .. }
+            | Instruction::Call { .. }
+            | Instruction::Ret
+            | Instruction::JumpV0 { .. } => {}
+            Instruction::SkipEqImm { .. }
+            | Instruction::SkipNeImm { .. }
+            | Instruction::SkipEqReg { .. }
+            | Instruction::SkipNeReg { .. }
+            | Instruction::SkipKeyPressed { .. }
+            | Instruction::SkipKeyNotPressed { .. } => pending.extend([next, next.wrapping_add(2)]),
+            _ => pending.push(next),
+        }
+    }
+    let mut instructions = Vec::new();
+    let mut addr = start;
+    while (addr as usize) < end {
+        // A data word here would swallow the first byte of odd-aligned code
+        let data_byte = !code.contains(&addr) && code.contains(&(addr + 1));
+        let opcode = if data_byte {
+            memory.read_byte(addr)? as u16
+        } else {
+            memory.read_word(addr)?
+        };
+        let instruction = if code.contains(&addr) {
+            decode_opcode(opcode).ok()
         } else {
-            break;
+            None
+        };
+        instructions.push(DisassembledInstruction {
+            address: addr,
+            opcode,
+            instruction,
+            label: None,
+            labeled_target: false,
+            data_byte,
+        });
+        // Step one byte when odd-aligned code starts inside this word
+        addr += if code.contains(&(addr + 1)) { 1 } else { 2 };
+    }
+
+    let targets = branch_targets(&instructions);
+    let labeled: HashSet<u16> = instructions
+        .iter()
+        .map(|entry| entry.address)
+        .filter(|address| targets.contains(address))
+        .collect();
+    for entry in &mut instructions {
+        if labeled.contains(&entry.address) {
+            entry.label = Some(label_name(entry.address));
         }
+        entry.labeled_target = match entry.instruction {
+            Some(
+                Instruction::Jump { addr }
+                | Instruction::Call { addr }
+                | Instruction::JumpV0 { addr }
+                | Instruction::SetIndex { addr },
+            ) => labeled.contains(&addr),
+            _ => false,
+        };
     }
 
     Ok(instructions)
 }
 
+/// Label name for a jump or call target
+fn label_name(addr: u16) -> String {
+    format!("L_{:04X}", addr)
+}
+
 /// Represents a disassembled instruction with its address and decoded form
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct DisassembledInstruction {
     pub address: u16,
     pub opcode: u16,
-    pub instruction: Instruction,
+    /// Decoded instruction, or None for a data word
+    pub instruction: Option<Instruction>,
+    /// Label at this address, when traced code jumps to it or points I at it
+    pub label: Option<String>,
+    /// Whether the mnemonic names the address operand by its label
+    pub labeled_target: bool,
+    /// Whether this is a single data byte (`DB NN`) in front of odd-aligned code
+    pub data_byte: bool,
 }
 
 impl DisassembledInstruction {
-    /// Get the mnemonic for this instruction
+    /// Number of ROM bytes this entry covers
+    pub fn byte_len(&self) -> usize {
+        if self.data_byte { 1 } else { 2 }
+    }
+
+    /// Get the mnemonic for this instruction (`DW NNNN` for a data word,
+    /// `DB NN` for a data byte)
     pub fn mnemonic(&self) -> String {
-        self.instruction.mnemonic()
+        if self.data_byte {
+            return format!("DB {:02X}", self.opcode);
+        }
+        match (&self.instruction, self.labeled_target) {
+            (Some(Instruction::Jump { addr }), true) => format!("JP {}", label_name(*addr)),
+            (Some(Instruction::Call { addr }), true) => format!("CALL {}", label_name(*addr)),
+            (Some(Instruction::JumpV0 { addr }), true) => format!("JP V0, {}", label_name(*addr)),
+            (Some(Instruction::SetIndex { addr }), true) => format!("LD I, {}", label_name(*addr)),
+            (Some(instruction), _) => instruction.mnemonic(),
+            (None, _) => format!("DW {:04X}", self.opcode),
+        }
+    }
+}
+
+/// One disassembly entry in [`disassembly_to_json`] output
+#[derive(Serialize)]
+struct JsonEntry {
+    address: u16,
+    opcode: u16,
+    mnemonic: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    label: Option<String>,
+}
+
+/// Render a disassembly as a JSON array of `{address, opcode, mnemonic}` objects
+///
+/// Traced disassemblies also carry a `label` on addresses their code names.
+pub fn disassembly_to_json(instructions: &[DisassembledInstruction]) -> String {
+    let entries: Vec<JsonEntry> = instructions
+        .iter()
+        .map(|instruction| JsonEntry {
+            address: instruction.address,
+            opcode: instruction.opcode,
+            mnemonic: instruction.mnemonic(),
+            label: instruction.label.clone(),
+        })
+        .collect();
+    serde_json::to_string_pretty(&entries).expect("disassembly entries always serialize")
+}
+
+/// Addresses named by jump, call and `LD I` operands in a disassembly
+pub fn branch_targets(instructions: &[DisassembledInstruction]) -> BTreeSet<u16> {
+    instructions
+        .iter()
+        .filter_map(|entry| match entry.instruction {
+            Some(
+                Instruction::Jump { addr }
+                | Instruction::Call { addr }
+                | Instruction::JumpV0 { addr }
+                | Instruction::SetIndex { addr },
+            ) => Some(addr),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Render a linear disassembly (from [`disassemble_rom`]) as source for [`crate::assemble`]
+///
+/// Every entry in `targets` gets an `L_NNNN:` label, and operands pointing at
+/// a labeled address use the label. Data words, and the odd opcode whose
+/// mnemonic drops bits (e.g. the unused VY of `8XY6`), come out as `DW` so the
+/// source reassembles to the same bytes.
+pub fn emit_asm(instructions: &[DisassembledInstruction], targets: &BTreeSet<u16>) -> String {
+    let labeled: BTreeSet<u16> = instructions
+        .iter()
+        .map(|entry| entry.address)
+        .filter(|address| targets.contains(address))
+        .collect();
+    let operand = |addr: u16| {
+        if labeled.contains(&addr) {
+            label_name(addr)
+        } else {
+            format!("{:03X}", addr)
+        }
+    };
+
+    let mut source = String::new();
+    for entry in instructions {
+        if labeled.contains(&entry.address) {
+            source.push_str(&format!("{}:\n", label_name(entry.address)));
+        }
+        let line = match &entry.instruction {
+            Some(instruction) if instruction.opcode() != entry.opcode => {
+                format!(
+                    "DW {:04X}        ; {}",
+                    entry.opcode,
+                    instruction.mnemonic()
+                )
+            }
+            Some(Instruction::Jump { addr }) => format!("JP {}", operand(*addr)),
+            Some(Instruction::Call { addr }) => format!("CALL {}", operand(*addr)),
+            Some(Instruction::JumpV0 { addr }) => format!("JP V0, {}", operand(*addr)),
+            Some(Instruction::SetIndex { addr }) => format!("LD I, {}", operand(*addr)),
+            Some(instruction) => instruction.mnemonic(),
+            None => format!("DW {:04X}", entry.opcode),
+        };
+        source.push_str(&format!("    {}\n", line));
     }
+    source
 }
 
-/// Print disassembly to stdout
-pub fn print_disassembly(instructions: &[DisassembledInstruction]) {
+/// Kind of a non-instruction byte region
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DataKind {
+    /// Bytes that look like sprite rows
+    Sprite,
+    /// Anything else
+    Data,
+}
+
+/// A contiguous run of data entries in a disassembly, bounded by code or the ROM end
+#[derive(Debug, Clone)]
+pub struct DataRegion {
+    pub address: u16,
+    pub bytes: Vec<u8>,
+    pub kind: DataKind,
+}
+
+impl DataKind {
+    /// Name shown in the disassembly listing
+    fn name(self) -> &'static str {
+        match self {
+            Self::Sprite => "sprite",
+            Self::Data => "data",
+        }
+    }
+}
+
+impl DataRegion {
+    /// Whether `addr` falls inside this region
+    pub fn contains(&self, addr: u16) -> bool {
+        (self.address as usize..self.address as usize + self.bytes.len()).contains(&(addr as usize))
+    }
+}
+
+/// Print disassembly to stdout, with each data region labeled where it starts
+///
+/// Regions come from [`find_data_regions`]; sprite regions are listed one
+/// byte per row with an ASCII preview instead of as data words.
+pub fn print_disassembly(instructions: &[DisassembledInstruction], regions: &[DataRegion]) {
     println!("Address  Opcode  Mnemonic");
     println!("------------------------");
     for instruction in instructions {
-        println!(
-            "{:04X}     {:04X}    {}",
-            instruction.address,
-            instruction.opcode,
-            instruction.mnemonic()
-        );
+        if let Some(label) = &instruction.label {
+            println!("{}:", label);
+        }
+        let region = regions
+            .iter()
+            .find(|region| region.contains(instruction.address));
+        if let Some(region) = region
+            && region.address == instruction.address
+        {
+            println!(
+                "{:04X}     {} ({} bytes)",
+                region.address,
+                region.kind.name(),
+                region.bytes.len()
+            );
+        }
+        match region {
+            Some(region) if region.kind == DataKind::Sprite => {
+                let offset = (instruction.address - region.address) as usize;
+                let end = (offset + instruction.byte_len()).min(region.bytes.len());
+                let rows = &region.bytes[offset..end];
+                for (i, (row, preview)) in rows.iter().zip(sprite_preview(rows)).enumerate() {
+                    println!(
+                        "{:04X}     {:02X}      {}",
+                        instruction.address as usize + i,
+                        row,
+                        preview
+                    );
+                }
+            }
+            _ => println!(
+                "{:04X}     {:04X}    {}",
+                instruction.address,
+                instruction.opcode,
+                instruction.mnemonic()
+            ),
+        }
+    }
+}
+
+/// Heuristically decide whether a byte run is sprite data rather than code
+///
+/// Sprites are assumed when at least half of the byte pairs fail to decode or
+/// decode to the (practically unused) SYS call, or when every row fits in the
+/// left nibble like the font glyphs.
+pub fn classify_as_sprite(bytes: &[u8]) -> bool {
+    if bytes.is_empty() {
+        return false;
+    }
+
+    let words: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| u16::from_be_bytes([pair[0], pair[1]]))
+        .collect();
+    let implausible = words
+        .iter()
+        .filter(|&&word| matches!(decode_opcode(word), Err(_) | Ok(Instruction::Sys { .. })))
+        .count();
+    let narrow_glyph_rows = bytes.iter().all(|&row| row & 0x0F == 0);
+
+    (!words.is_empty() && implausible * 2 >= words.len()) || narrow_glyph_rows
+}
+
+/// Render sprite bytes as ASCII rows ('#' = on, '.' = off)
+pub fn sprite_preview(bytes: &[u8]) -> Vec<String> {
+    bytes
+        .iter()
+        .map(|&row| {
+            (0..8)
+                .map(|bit| if row & (0x80 >> bit) != 0 { '#' } else { '.' })
+                .collect()
+        })
+        .collect()
+}
+
+/// Group the data entries of a disassembly into regions bounded by code
+///
+/// Each run of consecutive data entries becomes one region, clipped at the
+/// end of the ROM. A run is a sprite when [`classify_as_sprite`] says so or
+/// when traced code points `I` into it with `LD I`.
+pub fn find_data_regions(
+    memory: &Memory,
+    instructions: &[DisassembledInstruction],
+) -> Vec<DataRegion> {
+    let ram = memory.as_slice();
+    let end = PROGRAM_START_ADDR as usize + memory.rom_len();
+    let index_targets: HashSet<u16> = instructions
+        .iter()
+        .filter_map(|entry| match entry.instruction {
+            Some(Instruction::SetIndex { addr }) => Some(addr),
+            _ => None,
+        })
+        .collect();
+
+    let mut regions = Vec::new();
+    for run in instructions.chunk_by(|a, b| a.instruction.is_none() == b.instruction.is_none()) {
+        if run[0].instruction.is_some() {
+            continue;
+        }
+        let start = run[0].address as usize;
+        let last = &run[run.len() - 1];
+        let run_end = (last.address as usize + last.byte_len()).min(end);
+        if start >= run_end {
+            continue;
+        }
+
+        let bytes = ram[start..run_end].to_vec();
+        let pointed_at = (start..run_end).any(|addr| index_targets.contains(&(addr as u16)));
+        let kind = if pointed_at || classify_as_sprite(&bytes) {
+            DataKind::Sprite
+        } else {
+            DataKind::Data
+        };
+        regions.push(DataRegion {
+            address: start as u16,
+            bytes,
+            kind,
+        });
     }
+    regions
 }
 
 /// Analyze what instruction families are used in a ROM
@@ -91,7 +574,11 @@ pub fn analyze_instruction_usage(instructions: &[DisassembledInstruction]) -> In
     let mut analysis = InstructionAnalysis::default();
 
     for dis_instruction in instructions {
-        match &dis_instruction.instruction {
+        let Some(instruction) = &dis_instruction.instruction else {
+            analysis.unknown += 1;
+            continue;
+        };
+        match instruction {
             Instruction::Cls => {
                 analysis.system += 1;
                 analysis.cls = true;
@@ -104,6 +591,9 @@ pub fn analyze_instruction_usage(instructions: &[DisassembledInstruction]) -> In
                 analysis.system += 1;
                 analysis.sys = true;
             }
+            Instruction::ScrollDown { .. } | Instruction::ScrollRight | Instruction::ScrollLeft => {
+                analysis.system += 1;
+            }
             Instruction::Jump { .. } => analysis.jump += 1,
             Instruction::Call { .. } => analysis.call += 1,
             Instruction::JumpV0 { .. } => analysis.jump_v0 += 1,
@@ -191,6 +681,9 @@ pub fn analyze_instruction_usage(instructions: &[DisassembledInstruction]) -> In
                 analysis.misc += 1;
                 analysis.load_regs = true;
             }
+            Instruction::StoreFlags { .. } | Instruction::LoadFlags { .. } => {
+                analysis.misc += 1;
+            }
         }
     }
 
@@ -244,62 +737,45 @@ pub struct InstructionAnalysis {
 }
 
 impl InstructionAnalysis {
-    /// Print a summary of instruction usage
-    pub fn print_summary(&self) {
+    /// Get the instruction count for a family
+    pub fn family_count(&self, family: InstructionFamily) -> usize {
+        match family {
+            InstructionFamily::System => self.system,
+            InstructionFamily::Jump => self.jump,
+            InstructionFamily::Call => self.call,
+            InstructionFamily::SkipEqImm => self.skip_eq_imm,
+            InstructionFamily::SkipNeImm => self.skip_ne_imm,
+            InstructionFamily::SkipEqReg => self.skip_eq_reg,
+            InstructionFamily::LoadImm => self.load_imm,
+            InstructionFamily::AddImm => self.add_imm,
+            InstructionFamily::Arithmetic => self.arithmetic,
+            InstructionFamily::SkipNeReg => self.skip_ne_reg,
+            InstructionFamily::SetIndex => self.set_index,
+            InstructionFamily::JumpV0 => self.jump_v0,
+            InstructionFamily::Random => self.random,
+            InstructionFamily::Draw => self.draw,
+            InstructionFamily::Input => self.input,
+            InstructionFamily::Misc => self.misc,
+            InstructionFamily::Unknown => self.unknown,
+        }
+    }
+
+    /// Print instruction counts for the given families only
+    pub fn print_family_summary(&self, families: &[InstructionFamily]) {
         println!("\nInstruction Analysis:");
         println!("====================");
 
-        if self.system > 0 {
-            println!("System instructions: {}", self.system);
-        }
-        if self.jump > 0 {
-            println!("Jump instructions: {}", self.jump);
-        }
-        if self.call > 0 {
-            println!("Call instructions: {}", self.call);
-        }
-        if self.skip_eq_imm > 0 {
-            println!("Skip if equal (immediate): {}", self.skip_eq_imm);
-        }
-        if self.skip_ne_imm > 0 {
-            println!("Skip if not equal (immediate): {}", self.skip_ne_imm);
-        }
-        if self.skip_eq_reg > 0 {
-            println!("Skip if equal (register): {}", self.skip_eq_reg);
-        }
-        if self.load_imm > 0 {
-            println!("Load immediate: {}", self.load_imm);
-        }
-        if self.add_imm > 0 {
-            println!("Add immediate: {}", self.add_imm);
-        }
-        if self.arithmetic > 0 {
-            println!("Arithmetic instructions: {}", self.arithmetic);
-        }
-        if self.skip_ne_reg > 0 {
-            println!("Skip if not equal (register): {}", self.skip_ne_reg);
-        }
-        if self.set_index > 0 {
-            println!("Set index register: {}", self.set_index);
-        }
-        if self.jump_v0 > 0 {
-            println!("Jump with V0 offset: {}", self.jump_v0);
-        }
-        if self.random > 0 {
-            println!("Random number: {}", self.random);
-        }
-        if self.draw > 0 {
-            println!("Draw sprite: {}", self.draw);
-        }
-        if self.input > 0 {
-            println!("Input instructions: {}", self.input);
-        }
-        if self.misc > 0 {
-            println!("Miscellaneous F-type: {}", self.misc);
-        }
-        if self.unknown > 0 {
-            println!("Unknown instructions: {}", self.unknown);
+        for &family in families {
+            let count = self.family_count(family);
+            if count > 0 {
+                println!("{}: {}", family.label(), count);
+            }
         }
+    }
+
+    /// Print a summary of instruction usage
+    pub fn print_summary(&self) {
+        self.print_family_summary(&InstructionFamily::ALL);
 
         println!("\nSpecific Instructions Needed:");
         println!("=============================");
@@ -368,3 +844,286 @@ impl InstructionAnalysis {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// IBM logo ROM: 21 instructions followed by six 15-row sprites
+    const IBM_LOGO: &[u8] = include_bytes!("../roms/ibm_logo.ch8");
+
+    #[test]
+    fn test_font_glyphs_classified_as_sprite() {
+        // Glyphs "0", "1" and "2" from the built-in font
+        let glyphs = [
+            0xF0, 0x90, 0x90, 0x90, 0xF0, 0x20, 0x60, 0x20, 0x20, 0x70, 0xF0, 0x10, 0xF0, 0x80,
+            0xF0,
+        ];
+        assert!(classify_as_sprite(&glyphs));
+        assert_eq!(sprite_preview(&glyphs[..2]), vec!["####....", "#..#...."]);
+    }
+
+    #[test]
+    fn test_code_not_classified_as_sprite() {
+        // CLS; LD I, 0x22A; LD V0, 0x0C; LD V1, 0x08; DRW V0, V1, 15
+        let code = [0x00, 0xE0, 0xA2, 0x2A, 0x60, 0x0C, 0x61, 0x08, 0xD0, 0x1F];
+        assert!(!classify_as_sprite(&code));
+        assert!(!classify_as_sprite(&[]));
+    }
+
+    #[test]
+    fn test_data_region_after_code_labeled_sprite() {
+        let mut memory = Memory::new(true);
+        memory.load_rom(IBM_LOGO).unwrap();
+
+        let instructions = trace_disassemble(&memory, PROGRAM_START_ADDR).unwrap();
+        let regions = find_data_regions(&memory, &instructions);
+
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].address, 0x22A);
+        assert_eq!(regions[0].bytes.len(), 90);
+        assert_eq!(regions[0].kind, DataKind::Sprite);
+    }
+
+    #[test]
+    fn test_traced_data_regions_are_bounded_by_code() {
+        let rom = [
+            0x12, 0x08, // 200: JP 208
+            0xF0, 0x90, 0x90, 0xF0, 0x00, 0x00, // 202: sprite rows
+            0xA2, 0x02, // 208: LD I, 202
+            0xD0, 0x14, // 20A: DRW V0, V1, 4
+            0x12, 0x0C, // 20C: JP 20C (spin)
+            0x12, 0x34, 0x63, 0x21, // 20E: unreachable, would decode as JP 234; LD V3, 21
+        ];
+        let mut memory = Memory::new(true);
+        memory.load_rom(&rom).unwrap();
+
+        let instructions = trace_disassemble(&memory, PROGRAM_START_ADDR).unwrap();
+        let regions = find_data_regions(&memory, &instructions);
+        let summary: Vec<(u16, usize, DataKind)> = regions
+            .iter()
+            .map(|region| (region.address, region.bytes.len(), region.kind))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![(0x202, 6, DataKind::Sprite), (0x20E, 4, DataKind::Data)]
+        );
+        assert_eq!(regions[0].bytes, [0xF0, 0x90, 0x90, 0xF0, 0x00, 0x00]);
+        assert!(regions[0].contains(0x207));
+        assert!(!regions[0].contains(0x208));
+    }
+
+    #[test]
+    fn test_disassembly_continues_past_data() {
+        // CLS; LD V0, 05; then a zero word and a data word; then JP 200; RET
+        let rom = [
+            0x00, 0xE0, 0x60, 0x05, 0x00, 0x00, 0xFF, 0xFF, 0x12, 0x00, 0x00, 0xEE,
+        ];
+        let mut memory = Memory::new(true);
+        memory.load_rom(&rom).unwrap();
+
+        let instructions = disassemble_rom(&memory).unwrap();
+        let mnemonics: Vec<String> = instructions.iter().map(|i| i.mnemonic()).collect();
+        assert_eq!(
+            mnemonics,
+            vec!["CLS", "LD V0, 05", "DW 0000", "DW FFFF", "JP 200", "RET"]
+        );
+        assert_eq!(instructions[4].address, 0x208);
+        assert_eq!(
+            instructions[4].instruction,
+            Some(Instruction::Jump { addr: 0x200 })
+        );
+
+        let analysis = analyze_instruction_usage(&instructions);
+        assert_eq!(analysis.unknown, 2);
+        assert_eq!(analysis.jump, 1);
+
+        // The data region stops where the code picks up again
+        let regions = find_data_regions(&memory, &instructions);
+        assert_eq!(regions.len(), 1);
+        assert_eq!(regions[0].address, 0x204);
+        assert_eq!(regions[0].bytes, [0x00, 0x00, 0xFF, 0xFF]);
+        assert!(!regions[0].contains(0x208));
+    }
+
+    #[test]
+    fn test_trace_follows_calls_and_jumps() {
+        let rom = [
+            0x22, 0x08, // 200: CALL 208
+            0x12, 0x06, // 202: JP 206
+            0x60, 0x05, // 204: unreachable, would decode as LD V0, 05
+            0x12, 0x06, // 206: JP 206 (spin)
+            0x30, 0x01, // 208: SE V0, 01
+            0x61, 0x02, // 20A: LD V1, 02
+            0x00, 0xEE, // 20C: RET
+            0x12, 0x34, // 20E: unreachable, would decode as JP 234
+        ];
+        let mut memory = Memory::new(true);
+        memory.load_rom(&rom).unwrap();
+
+        let instructions = trace_disassemble(&memory, PROGRAM_START_ADDR).unwrap();
+        let mnemonics: Vec<String> = instructions.iter().map(|i| i.mnemonic()).collect();
+        assert_eq!(
+            mnemonics,
+            vec![
+                "CALL L_0208",
+                "JP L_0206",
+                "DW 6005",
+                "JP L_0206",
+                "SE V0, 01",
+                "LD V1, 02",
+                "RET",
+                "DW 1234",
+            ]
+        );
+
+        let labels: Vec<(u16, &str)> = instructions
+            .iter()
+            .filter_map(|i| Some((i.address, i.label.as_deref()?)))
+            .collect();
+        assert_eq!(labels, vec![(0x206, "L_0206"), (0x208, "L_0208")]);
+    }
+
+    #[test]
+    fn test_trace_treats_jump_v0_target_as_unknown() {
+        // JP V0, 204 can't be followed, so what comes after it is data; the
+        // table base still gets a label
+        let mut memory = Memory::new(true);
+        memory
+            .load_rom(&[0x60, 0x02, 0xB2, 0x04, 0x00, 0xE0])
+            .unwrap();
+
+        let instructions = trace_disassemble(&memory, PROGRAM_START_ADDR).unwrap();
+        assert_eq!(instructions[1].mnemonic(), "JP V0, L_0204");
+        assert_eq!(instructions[2].mnemonic(), "DW 00E0");
+        assert_eq!(instructions[2].label.as_deref(), Some("L_0204"));
+    }
+
+    #[test]
+    fn test_trace_labels_sprite_data() {
+        let rom = [
+            0xA2, 0x06, // 200: LD I, 206
+            0xD0, 0x15, // 202: DRW V0, V1, 5
+            0x12, 0x04, // 204: JP 204 (spin)
+            0xF0, 0x90, 0x90, 0x90, 0xF0, 0x00, // 206: a "0" glyph
+        ];
+        let mut memory = Memory::new(true);
+        memory.load_rom(&rom).unwrap();
+
+        let instructions = trace_disassemble(&memory, PROGRAM_START_ADDR).unwrap();
+        assert_eq!(instructions[0].mnemonic(), "LD I, L_0206");
+        assert_eq!(instructions[3].mnemonic(), "DW F090");
+        let labels: Vec<(u16, &str)> = instructions
+            .iter()
+            .filter_map(|i| Some((i.address, i.label.as_deref()?)))
+            .collect();
+        assert_eq!(labels, vec![(0x204, "L_0204"), (0x206, "L_0206")]);
+    }
+
+    #[test]
+    fn test_trace_stops_at_odd_targets() {
+        // JP 203 faults on the CPU, so it isn't followed or labeled
+        let mut memory = Memory::new(true);
+        memory.load_rom(&[0x12, 0x03, 0xFF, 0x12, 0x03]).unwrap();
+
+        let instructions = trace_disassemble(&memory, PROGRAM_START_ADDR).unwrap();
+        let listing: Vec<(u16, String)> = instructions
+            .iter()
+            .map(|i| (i.address, i.mnemonic()))
+            .collect();
+        assert_eq!(
+            listing,
+            vec![
+                (0x200, "JP 203".to_string()),
+                (0x202, "DW FF12".to_string()),
+                (0x204, "DW 0300".to_string()),
+            ]
+        );
+        assert!(instructions.iter().all(|i| i.label.is_none()));
+    }
+
+    #[test]
+    fn test_trace_from_odd_entry_lists_lone_data_byte() {
+        let rom = [
+            0x00, 0xE0, // 200: CLS
+            0xFF, // 202: data byte
+            0x00, 0xE0, // 203: CLS
+            0x12, 0x00, // 205: JP 200
+        ];
+        let mut memory = Memory::new(true);
+        memory.load_rom(&rom).unwrap();
+
+        let instructions = trace_disassemble(&memory, 0x203).unwrap();
+        let listing: Vec<(u16, String)> = instructions
+            .iter()
+            .map(|i| (i.address, i.mnemonic()))
+            .collect();
+        assert_eq!(
+            listing,
+            vec![
+                (0x200, "CLS".to_string()),
+                (0x202, "DB FF".to_string()),
+                (0x203, "CLS".to_string()),
+                (0x205, "JP L_0200".to_string()),
+            ]
+        );
+        assert_eq!(instructions[1].opcode, 0xFF);
+    }
+
+    #[test]
+    fn test_disassembly_to_json() {
+        let mut memory = Memory::new(true);
+        memory.load_rom(&[0x00, 0xE0, 0xA2, 0x2A]).unwrap();
+        let instructions = disassemble_rom(&memory).unwrap();
+
+        let json: serde_json::Value =
+            serde_json::from_str(&disassembly_to_json(&instructions)).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([
+                { "address": 0x200, "opcode": 0x00E0, "mnemonic": "CLS" },
+                { "address": 0x202, "opcode": 0xA22A, "mnemonic": "LD I, 22A" },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_emit_asm_round_trip() {
+        let mut memory = Memory::new(true);
+        memory.load_rom(IBM_LOGO).unwrap();
+        let instructions = disassemble_rom(&memory).unwrap();
+        let asm = emit_asm(&instructions, &branch_targets(&instructions));
+
+        assert!(asm.contains("L_0228:\n    JP L_0228\n"));
+        assert!(asm.contains("LD I, L_022A"));
+        assert_eq!(crate::assemble(&asm).unwrap(), IBM_LOGO);
+    }
+
+    #[test]
+    fn test_emit_asm_keeps_lossy_opcodes_as_data() {
+        // SHR V1 with a stray VY nibble; CALL 200; an odd trailing byte
+        let rom = [0x81, 0x26, 0x22, 0x00, 0xF0];
+        let mut memory = Memory::new(true);
+        memory.load_rom(&rom).unwrap();
+        let instructions = disassemble_rom(&memory).unwrap();
+        let asm = emit_asm(&instructions, &branch_targets(&instructions));
+
+        assert!(asm.starts_with("L_0200:\n    DW 8126        ; SHR V1\n    CALL L_0200\n"));
+        // The code region matches; the odd byte's padding becomes a zero byte
+        let bytes = crate::assemble(&asm).unwrap();
+        assert_eq!(&bytes[..rom.len()], rom);
+        assert_eq!(bytes.len(), rom.len() + 1);
+    }
+
+    #[test]
+    fn test_disassembly_stops_at_rom_end() {
+        let mut memory = Memory::new(true);
+        assert!(disassemble_rom(&memory).unwrap().is_empty());
+
+        // An odd trailing byte is padded into a final word
+        memory.load_rom(&[0x00, 0xE0, 0xF0]).unwrap();
+        let instructions = disassemble_rom(&memory).unwrap();
+        assert_eq!(instructions.len(), 2);
+        assert_eq!(instructions[1].mnemonic(), "DW F000");
+    }
+}
@@ -15,20 +15,23 @@ use crossterm::{
 use ratatui::{
     Frame, Terminal,
     backend::CrosstermBackend,
-    layout::{Constraint, Direction, Layout, Rect},
+    layout::{Constraint, Direction, Layout, Margin, Rect},
     style::{Color, Modifier, Style},
     text::{Line, Span},
-    widgets::{Block, Borders, Paragraph, Wrap},
+    widgets::{Block, BorderType, Borders, Paragraph, Wrap},
 };
+use serde::{Deserialize, Serialize};
 use std::{
     collections::VecDeque,
-    io::{self, Stdout, stdout},
+    io::{self, Stdout, Write, stdout},
     sync::mpsc::Sender,
     time::{Duration, Instant},
 };
 use thiserror::Error;
 
+use crate::disassembler::DisassembledInstruction;
 use crate::input::KeyEvent;
+use crate::instruction::Instruction;
 
 /// Display width in pixels
 pub const DISPLAY_WIDTH: usize = 64;
@@ -36,9 +39,15 @@ pub const DISPLAY_WIDTH: usize = 64;
 /// Display height in pixels
 pub const DISPLAY_HEIGHT: usize = 32;
 
+/// Pixels the SUPER-CHIP 00FB/00FC instructions scroll by
+pub const HORIZONTAL_SCROLL_PIXELS: usize = 4;
+
 /// Maximum sprite width (always 8 pixels in CHIP-8)
 pub const SPRITE_WIDTH: usize = 8;
 
+/// Size of a packed framebuffer (8 pixels per byte, row-major)
+pub const BITPLANE_SIZE: usize = DISPLAY_WIDTH * DISPLAY_HEIGHT / 8;
+
 /// Display errors
 #[derive(Debug, Error)]
 pub enum DisplayError {
@@ -47,6 +56,19 @@ pub enum DisplayError {
 
     #[error("Sprite too tall: {height} rows (max: {max_height})")]
     SpriteTooTall { height: usize, max_height: usize },
+
+    #[error("Invalid bitplane size: {size} bytes (expected: {expected})")]
+    InvalidBitplaneSize { size: usize, expected: usize },
+}
+
+/// How [`Display::blit`] combines sprite pixels with the framebuffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum DrawMode {
+    /// Toggle pixels, like DXYN
+    #[default]
+    Xor,
+    /// Turn pixels on, never off
+    Or,
 }
 
 /// Control action requested by the renderer
@@ -56,10 +78,17 @@ pub enum ControlAction {
     None,
     /// Reset the emulator
     Reset,
-    /// Toggle pause/resume (future feature)
+    /// Toggle pause/resume
     TogglePause,
+    /// Re-read the ROM from its source and restart it
+    Reload,
+    /// Save the display as a PNG (needs the `image` feature)
+    Screenshot,
     /// Quit the emulator
     Quit,
+    /// Save a state to resume from with `joe run --resume`, then quit
+    /// (needs the `save-state` feature)
+    QuitAndSave,
 }
 
 /// Display bus trait for CPU to interact with display system
@@ -80,6 +109,17 @@ pub trait DisplayBus {
 
     /// Set pixel state at coordinates (for testing)
     fn set_pixel(&mut self, x: usize, y: usize, on: bool);
+
+    /// Scroll the display down `n` rows (SUPER-CHIP 00CN)
+    ///
+    /// Pixels scrolled off the bottom are discarded; vacated rows are off.
+    fn scroll_down(&mut self, n: u8);
+
+    /// Scroll the display left by [`HORIZONTAL_SCROLL_PIXELS`] (SUPER-CHIP 00FC)
+    fn scroll_left(&mut self);
+
+    /// Scroll the display right by [`HORIZONTAL_SCROLL_PIXELS`] (SUPER-CHIP 00FB)
+    fn scroll_right(&mut self);
 }
 
 /// Renderer errors
@@ -102,9 +142,96 @@ pub enum RendererError {
 }
 
 /// CHIP-8 Display implementation with 64x32 framebuffer
+#[derive(Serialize, Deserialize)]
 pub struct Display {
     /// 64x32 framebuffer: framebuffer[row][col] = pixel_on
+    #[serde(with = "framebuffer_rows")]
     framebuffer: [[bool; DISPLAY_WIDTH]; DISPLAY_HEIGHT],
+
+    /// Clip sprites at the screen edges instead of wrapping them
+    clip_sprites: bool,
+
+    /// Rows changed since the last [`Display::clear_dirty_rows`]
+    #[serde(skip, default = "all_rows_dirty")]
+    dirty_rows: [bool; DISPLAY_HEIGHT],
+}
+
+/// Dirty-row mask for a display nothing has been rendered from yet
+fn all_rows_dirty() -> [bool; DISPLAY_HEIGHT] {
+    [true; DISPLAY_HEIGHT]
+}
+
+/// Serde support for the framebuffer, packing each 64-pixel row into a `u64`
+/// (bit 63 is the leftmost pixel)
+mod framebuffer_rows {
+    use super::{DISPLAY_HEIGHT, DISPLAY_WIDTH};
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    type Framebuffer = [[bool; DISPLAY_WIDTH]; DISPLAY_HEIGHT];
+
+    pub fn serialize<S: Serializer>(
+        framebuffer: &Framebuffer,
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        let rows = framebuffer.map(|row| {
+            row.iter()
+                .fold(0u64, |bits, &pixel| (bits << 1) | pixel as u64)
+        });
+        rows.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<Framebuffer, D::Error> {
+        let rows = <[u64; DISPLAY_HEIGHT]>::deserialize(deserializer)?;
+        let mut framebuffer = [[false; DISPLAY_WIDTH]; DISPLAY_HEIGHT];
+        for (row, bits) in framebuffer.iter_mut().zip(rows) {
+            for (col, pixel) in row.iter_mut().enumerate() {
+                *pixel = bits & (1 << (DISPLAY_WIDTH - 1 - col)) != 0;
+            }
+        }
+        Ok(framebuffer)
+    }
+}
+
+/// Minimal PNG writer for 8-bit RGB images
+#[cfg(feature = "image")]
+mod png {
+    use flate2::Compression;
+    use flate2::write::ZlibEncoder;
+    use std::io::{self, Write};
+
+    const SIGNATURE: [u8; 8] = [0x89, b'P', b'N', b'G', b'\r', b'\n', 0x1A, b'\n'];
+
+    /// Encode filtered scanlines (filter byte + RGB triples per row) as a PNG file
+    pub fn encode_rgb(width: u32, height: u32, scanlines: &[u8]) -> io::Result<Vec<u8>> {
+        let mut header = Vec::with_capacity(13);
+        header.extend_from_slice(&width.to_be_bytes());
+        header.extend_from_slice(&height.to_be_bytes());
+        // Bit depth 8, color type 2 (RGB), default compression, filter and no interlace
+        header.extend_from_slice(&[8, 2, 0, 0, 0]);
+
+        let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(scanlines)?;
+        let data = encoder.finish()?;
+
+        let mut png = SIGNATURE.to_vec();
+        write_chunk(&mut png, b"IHDR", &header);
+        write_chunk(&mut png, b"IDAT", &data);
+        write_chunk(&mut png, b"IEND", &[]);
+        Ok(png)
+    }
+
+    /// Append a length-prefixed, CRC-terminated chunk
+    fn write_chunk(png: &mut Vec<u8>, kind: &[u8; 4], data: &[u8]) {
+        png.extend_from_slice(&(data.len() as u32).to_be_bytes());
+        png.extend_from_slice(kind);
+        png.extend_from_slice(data);
+        let mut crc = crc32fast::Hasher::new();
+        crc.update(kind);
+        crc.update(data);
+        png.extend_from_slice(&crc.finalize().to_be_bytes());
+    }
 }
 
 impl Display {
@@ -112,7 +239,224 @@ impl Display {
     pub fn new() -> Self {
         Self {
             framebuffer: [[false; DISPLAY_WIDTH]; DISPLAY_HEIGHT],
+            clip_sprites: false,
+            dirty_rows: all_rows_dirty(),
+        }
+    }
+
+    /// Create a display from a packed bitplane (8 pixels per byte, MSB = leftmost)
+    pub fn from_bitplane(bitplane: &[u8]) -> Result<Self, DisplayError> {
+        if bitplane.len() != BITPLANE_SIZE {
+            return Err(DisplayError::InvalidBitplaneSize {
+                size: bitplane.len(),
+                expected: BITPLANE_SIZE,
+            });
+        }
+
+        let mut display = Self::new();
+        for (y, row) in display.framebuffer.iter_mut().enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                let byte = bitplane[(y * DISPLAY_WIDTH + x) / 8];
+                *pixel = (byte >> (7 - (x % 8))) & 1 == 1;
+            }
+        }
+        Ok(display)
+    }
+
+    /// Pack the framebuffer into a bitplane (8 pixels per byte, MSB = leftmost)
+    pub fn to_bitplane(&self) -> Vec<u8> {
+        let mut bitplane = vec![0u8; BITPLANE_SIZE];
+        for (y, row) in self.framebuffer.iter().enumerate() {
+            for (x, &pixel) in row.iter().enumerate() {
+                if pixel {
+                    bitplane[(y * DISPLAY_WIDTH + x) / 8] |= 0x80 >> (x % 8);
+                }
+            }
+        }
+        bitplane
+    }
+
+    /// Get the whole framebuffer: `pixels()[row][col]` is true when lit
+    pub fn pixels(&self) -> &[[bool; DISPLAY_WIDTH]; DISPLAY_HEIGHT] {
+        &self.framebuffer
+    }
+
+    /// Get a whole row of pixels, or None if `y` is off-screen
+    pub fn row(&self, y: usize) -> Option<[bool; DISPLAY_WIDTH]> {
+        self.framebuffer.get(y).copied()
+    }
+
+    /// Get a whole column of pixels, or None if `x` is off-screen
+    pub fn column(&self, x: usize) -> Option<[bool; DISPLAY_HEIGHT]> {
+        if x >= DISPLAY_WIDTH {
+            return None;
         }
+        Some(std::array::from_fn(|y| self.framebuffer[y][x]))
+    }
+
+    /// Get the bounding box `(min_x, min_y, max_x, max_y)` of lit pixels, or None if blank
+    pub fn lit_bounds(&self) -> Option<(usize, usize, usize, usize)> {
+        let mut bounds: Option<(usize, usize, usize, usize)> = None;
+        for (y, row) in self.framebuffer.iter().enumerate() {
+            for (x, _) in row.iter().enumerate().filter(|(_, pixel)| **pixel) {
+                bounds = Some(match bounds {
+                    None => (x, y, x, y),
+                    Some((min_x, min_y, max_x, max_y)) => {
+                        (min_x.min(x), min_y.min(y), max_x.max(x), max_y.max(y))
+                    }
+                });
+            }
+        }
+        bounds
+    }
+
+    /// Draw a sprite outside the CPU, returning true if it overlapped lit pixels
+    ///
+    /// Unlike [`DisplayBus::draw_sprite`], any sprite height is accepted and
+    /// pixels past the screen edges are clipped rather than wrapped, whatever
+    /// the origin. In [`DrawMode::Xor`] an overlap is a DXYN collision.
+    pub fn blit(&mut self, x: usize, y: usize, sprite: &[u8], mode: DrawMode) -> bool {
+        let mut collision = false;
+        for (row_offset, &sprite_byte) in sprite.iter().enumerate() {
+            let screen_y = y.saturating_add(row_offset);
+            if screen_y >= DISPLAY_HEIGHT {
+                break;
+            }
+            for bit_pos in 0..8 {
+                let screen_x = x.saturating_add(bit_pos);
+                if screen_x >= DISPLAY_WIDTH {
+                    break;
+                }
+                if (sprite_byte >> (7 - bit_pos)) & 1 == 0 {
+                    continue;
+                }
+
+                let pixel = &mut self.framebuffer[screen_y][screen_x];
+                collision |= *pixel;
+                *pixel = match mode {
+                    DrawMode::Xor => !*pixel,
+                    DrawMode::Or => true,
+                };
+                self.dirty_rows[screen_y] = true;
+            }
+        }
+        collision
+    }
+
+    /// Scale the framebuffer up for image export: one byte per output pixel,
+    /// row-major, 1 lit and 0 unlit, each pixel a `scale`×`scale` block
+    pub fn to_indexed(&self, scale: usize) -> Vec<u8> {
+        let width = DISPLAY_WIDTH * scale;
+        let mut pixels = Vec::with_capacity(width * DISPLAY_HEIGHT * scale);
+        for row in &self.framebuffer {
+            let start = pixels.len();
+            for &pixel in row {
+                pixels.extend(std::iter::repeat_n(pixel as u8, scale));
+            }
+            for _ in 1..scale {
+                pixels.extend_from_within(start..start + width);
+            }
+        }
+        pixels
+    }
+
+    /// Save the framebuffer as an RGB PNG, each pixel a `scale`×`scale` block
+    ///
+    /// Lit pixels are drawn in `color` on black; see [`RatatuiConfig::pixel_rgb`].
+    #[cfg(feature = "image")]
+    pub fn to_png(
+        &self,
+        path: impl AsRef<std::path::Path>,
+        scale: u32,
+        color: (u8, u8, u8),
+    ) -> io::Result<()> {
+        if scale == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "screenshot scale must be at least 1",
+            ));
+        }
+        let scale = scale as usize;
+        let (width, height) = (DISPLAY_WIDTH * scale, DISPLAY_HEIGHT * scale);
+
+        // Each scanline starts with filter type 0 (none)
+        let mut scanlines = Vec::with_capacity((width * 3 + 1) * height);
+        for line in self.to_indexed(scale).chunks(width) {
+            scanlines.push(0);
+            for &pixel in line {
+                let (r, g, b) = if pixel == 1 { color } else { (0, 0, 0) };
+                scanlines.extend_from_slice(&[r, g, b]);
+            }
+        }
+
+        std::fs::write(
+            path,
+            png::encode_rgb(width as u32, height as u32, &scanlines)?,
+        )
+    }
+
+    /// Build a display with only the pixels that differ between `a` and `b` lit
+    pub fn render_diff(a: &Display, b: &Display) -> Display {
+        let mut diff = Display::new();
+        for (y, row) in diff.framebuffer.iter_mut().enumerate() {
+            for (x, pixel) in row.iter_mut().enumerate() {
+                *pixel = a.framebuffer[y][x] != b.framebuffer[y][x];
+            }
+        }
+        diff
+    }
+
+    /// Mirror the framebuffer left to right
+    pub fn flip_horizontal(&mut self) {
+        for row in &mut self.framebuffer {
+            row.reverse();
+        }
+        self.dirty_rows = all_rows_dirty();
+    }
+
+    /// Mirror the framebuffer top to bottom
+    pub fn flip_vertical(&mut self) {
+        self.framebuffer.reverse();
+        self.dirty_rows = all_rows_dirty();
+    }
+
+    /// Turn the framebuffer upside down (both flips at once)
+    pub fn rotate_180(&mut self) {
+        self.flip_horizontal();
+        self.flip_vertical();
+    }
+
+    /// Render the framebuffer as text, one line per row: `#` lit, `.` unlit
+    pub fn render_ascii(&self) -> String {
+        let mut text = String::with_capacity((DISPLAY_WIDTH + 1) * DISPLAY_HEIGHT);
+        for row in &self.framebuffer {
+            text.extend(row.iter().map(|&pixel| if pixel { '#' } else { '.' }));
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Get the rows changed since the last [`Display::clear_dirty_rows`]
+    ///
+    /// `dirty_rows()[y]` is true when row `y` may differ from what was last
+    /// rendered. A new display starts with every row dirty.
+    pub fn dirty_rows(&self) -> &[bool] {
+        &self.dirty_rows
+    }
+
+    /// Mark every row as rendered
+    pub fn clear_dirty_rows(&mut self) {
+        self.dirty_rows = [false; DISPLAY_HEIGHT];
+    }
+
+    /// Enable or disable clipping of sprites at the screen edges
+    pub fn set_clip_sprites(&mut self, enabled: bool) {
+        self.clip_sprites = enabled;
+    }
+
+    /// Check if sprites are clipped at the screen edges
+    pub fn is_clip_sprites_enabled(&self) -> bool {
+        self.clip_sprites
     }
 
     /// Get display statistics
@@ -138,6 +482,7 @@ impl Display {
 impl DisplayBus for Display {
     fn clear(&mut self) {
         self.framebuffer = [[false; DISPLAY_WIDTH]; DISPLAY_HEIGHT];
+        self.dirty_rows = all_rows_dirty();
     }
 
     fn draw_sprite(&mut self, x: u8, y: u8, sprite_data: &[u8]) -> Result<bool, DisplayError> {
@@ -154,14 +499,26 @@ impl DisplayBus for Display {
 
         let mut collision = false;
 
+        // The starting position always wraps; clipping only affects the sprite body
+        let start_x = (x as usize) % DISPLAY_WIDTH;
+        let start_y = (y as usize) % DISPLAY_HEIGHT;
+
         // Draw each row of the sprite
         for (row_offset, &sprite_byte) in sprite_data.iter().enumerate() {
-            // Calculate wrapped coordinates
-            let screen_y = ((y as usize) + row_offset) % DISPLAY_HEIGHT;
+            let row = start_y + row_offset;
+            if self.clip_sprites && row >= DISPLAY_HEIGHT {
+                // Clipped rows are never drawn, so they can't collide
+                break;
+            }
+            let screen_y = row % DISPLAY_HEIGHT;
 
             // Draw each pixel in the row (8 pixels per byte)
             for bit_pos in 0..8 {
-                let screen_x = ((x as usize) + bit_pos) % DISPLAY_WIDTH;
+                let col = start_x + bit_pos;
+                if self.clip_sprites && col >= DISPLAY_WIDTH {
+                    break;
+                }
+                let screen_x = col % DISPLAY_WIDTH;
 
                 // Extract pixel from sprite byte (MSB = leftmost pixel)
                 let sprite_pixel = (sprite_byte >> (7 - bit_pos)) & 1 == 1;
@@ -171,6 +528,7 @@ impl DisplayBus for Display {
                     let old_pixel = self.framebuffer[screen_y][screen_x];
                     let new_pixel = old_pixel ^ true; // XOR with sprite pixel (on)
                     self.framebuffer[screen_y][screen_x] = new_pixel;
+                    self.dirty_rows[screen_y] = true;
 
                     // Collision occurs when pixel turns off (was on, now off)
                     if old_pixel && !new_pixel {
@@ -193,9 +551,36 @@ impl DisplayBus for Display {
 
     fn set_pixel(&mut self, x: usize, y: usize, on: bool) {
         if x < DISPLAY_WIDTH && y < DISPLAY_HEIGHT {
+            self.dirty_rows[y] |= self.framebuffer[y][x] != on;
             self.framebuffer[y][x] = on;
         }
     }
+
+    fn scroll_down(&mut self, n: u8) {
+        let n = (n as usize).min(DISPLAY_HEIGHT);
+        self.framebuffer.copy_within(..DISPLAY_HEIGHT - n, n);
+        self.framebuffer[..n].fill([false; DISPLAY_WIDTH]);
+        self.dirty_rows = all_rows_dirty();
+    }
+
+    fn scroll_left(&mut self) {
+        for row in &mut self.framebuffer {
+            row.copy_within(HORIZONTAL_SCROLL_PIXELS.., 0);
+            row[DISPLAY_WIDTH - HORIZONTAL_SCROLL_PIXELS..].fill(false);
+        }
+        self.dirty_rows = all_rows_dirty();
+    }
+
+    fn scroll_right(&mut self) {
+        for row in &mut self.framebuffer {
+            row.copy_within(
+                ..DISPLAY_WIDTH - HORIZONTAL_SCROLL_PIXELS,
+                HORIZONTAL_SCROLL_PIXELS,
+            );
+            row[..HORIZONTAL_SCROLL_PIXELS].fill(false);
+        }
+        self.dirty_rows = all_rows_dirty();
+    }
 }
 
 impl Default for Display {
@@ -224,7 +609,16 @@ pub struct RatatuiConfig {
     pub pixel_char: String,
     pub pixel_color: String,
     pub border_style: String,
+    /// Inset of the display widget within its layout area (vertical = rows, horizontal = cols)
+    pub border_padding: Margin,
     pub refresh_rate_ms: u64,
+    /// Tint recently lit pixels to show sprite motion
+    pub motion_trail: bool,
+    /// Number of frames a trail lasts after a pixel turns off
+    pub motion_trail_frames: u8,
+    /// Terminal cell height / width hint; when set, each pixel repeats the first
+    /// `pixel_char` character 2× for tall cells or 1× for square ones
+    pub cell_aspect: Option<f32>,
 }
 
 impl Default for RatatuiConfig {
@@ -238,7 +632,11 @@ impl Default for RatatuiConfig {
             pixel_char: "██".to_string(),
             pixel_color: "Green".to_string(),
             border_style: "rounded".to_string(),
+            border_padding: Margin::new(0, 0),
             refresh_rate_ms: 16,
+            motion_trail: false,
+            motion_trail_frames: 4,
+            cell_aspect: None,
         }
     }
 }
@@ -260,6 +658,69 @@ impl RatatuiConfig {
         }
     }
 
+    /// Parse a border style string into a ratatui BorderType
+    pub fn parse_border_type(style_str: &str) -> BorderType {
+        match style_str.to_lowercase().as_str() {
+            "rounded" => BorderType::Rounded,
+            "double" => BorderType::Double,
+            "plain" => BorderType::Plain,
+            _ => BorderType::Rounded, // Default fallback
+        }
+    }
+
+    /// RGB components of the configured pixel color, for screenshots
+    pub fn pixel_rgb(&self) -> (u8, u8, u8) {
+        Self::color_rgb(Self::parse_color(&self.pixel_color))
+    }
+
+    /// Approximate RGB components of a color, used to dim trail pixels
+    fn color_rgb(color: Color) -> (u8, u8, u8) {
+        match color {
+            Color::Rgb(r, g, b) => (r, g, b),
+            Color::White => (255, 255, 255),
+            Color::Blue => (0, 0, 255),
+            Color::Red => (255, 0, 0),
+            Color::Yellow => (255, 255, 0),
+            Color::Cyan => (0, 255, 255),
+            Color::Magenta => (255, 0, 255),
+            Color::Gray => (192, 192, 192),
+            Color::DarkGray => (128, 128, 128),
+            _ => (0, 255, 0), // Green and default fallback
+        }
+    }
+
+    /// Glyph for one CHIP-8 pixel, adjusted for the cell aspect hint if set
+    ///
+    /// Cells at least 1.5× taller than wide get two characters per pixel so
+    /// pixels look roughly square; squarer cells get one.
+    fn pixel_glyph(&self) -> String {
+        match self.cell_aspect {
+            None => self.pixel_char.clone(),
+            Some(aspect) => {
+                let repeat = if aspect >= 1.5 { 2 } else { 1 };
+                let c = self.pixel_char.chars().next().unwrap_or('█');
+                std::iter::repeat_n(c, repeat).collect()
+            }
+        }
+    }
+
+    /// Character width of one pixel and left padding that centers the display in `area_width`
+    fn pixel_layout(&self, area_width: usize) -> (usize, usize) {
+        let pixel_width = self.pixel_glyph().chars().count();
+        let total_display_width = DISPLAY_WIDTH * pixel_width;
+        (
+            pixel_width,
+            area_width.saturating_sub(total_display_width) / 2,
+        )
+    }
+
+    /// Create a bordered block using the configured border style
+    fn block(&self) -> Block<'static> {
+        Block::default()
+            .borders(Borders::ALL)
+            .border_type(Self::parse_border_type(&self.border_style))
+    }
+
     /// Create RatatuiConfig from user DisplaySettings
     pub fn from_display_settings(display_settings: &crate::config::DisplaySettings) -> Self {
         Self {
@@ -270,19 +731,283 @@ impl RatatuiConfig {
             show_memory_info: true,
             pixel_char: display_settings.pixel_char.clone(),
             pixel_color: display_settings.pixel_color.clone(),
-            border_style: "rounded".to_string(),
+            border_style: display_settings.border_style.clone(),
+            border_padding: Margin::new(
+                display_settings.border_padding[1],
+                display_settings.border_padding[0],
+            ),
             refresh_rate_ms: display_settings.refresh_rate_ms,
+            motion_trail: display_settings.motion_trail,
+            motion_trail_frames: display_settings.motion_trail_frames,
+            cell_aspect: display_settings.cell_aspect,
         }
     }
 }
 
+/// Skips redraws that come sooner than the configured refresh interval
+#[derive(Debug, Clone)]
+pub struct FrameLimiter {
+    interval: Duration,
+    last_frame: Option<Instant>,
+}
+
+impl FrameLimiter {
+    /// Create a limiter allowing one frame every `refresh_rate_ms` milliseconds
+    pub fn new(refresh_rate_ms: u64) -> Self {
+        Self::with_interval(Duration::from_millis(refresh_rate_ms))
+    }
+
+    /// Create a limiter allowing one frame per `interval`
+    pub fn with_interval(interval: Duration) -> Self {
+        Self {
+            interval,
+            last_frame: None,
+        }
+    }
+
+    /// Check whether a frame is due at `now`, recording it as rendered if so
+    pub fn should_render(&mut self, now: Instant) -> bool {
+        if let Some(last_frame) = self.last_frame
+            && now.duration_since(last_frame) < self.interval
+        {
+            return false;
+        }
+        self.last_frame = Some(now);
+        true
+    }
+}
+
 /// Ratatui-based terminal renderer for rich interactive display
+/// Disassembly color for an instruction, or for a data word (`None`)
+///
+/// Jumps, calls and skips are yellow, drawing is green, arithmetic is cyan,
+/// data is gray and everything else (loads, timers, keys) is white.
+pub fn instruction_color(instruction: Option<&Instruction>) -> Color {
+    use Instruction::*;
+    match instruction {
+        None => Color::Gray,
+        Some(
+            Sys { .. }
+            | Jump { .. }
+            | JumpV0 { .. }
+            | Call { .. }
+            | Ret
+            | SkipEqImm { .. }
+            | SkipNeImm { .. }
+            | SkipEqReg { .. }
+            | SkipNeReg { .. }
+            | SkipKeyPressed { .. }
+            | SkipKeyNotPressed { .. },
+        ) => Color::Yellow,
+        Some(Cls | Draw { .. } | ScrollDown { .. } | ScrollRight | ScrollLeft) => Color::Green,
+        Some(
+            AddImm { .. }
+            | AddReg { .. }
+            | SubReg { .. }
+            | SubnReg { .. }
+            | OrReg { .. }
+            | AndReg { .. }
+            | XorReg { .. }
+            | ShrReg { .. }
+            | ShlReg { .. }
+            | AddIndex { .. },
+        ) => Color::Cyan,
+        Some(_) => Color::White,
+    }
+}
+
+/// Syntax-highlighted disassembly listing, with the instruction at `pc` reversed
+pub fn disassembly_view(
+    instructions: &[DisassembledInstruction],
+    pc: Option<u16>,
+) -> Paragraph<'static> {
+    let mut lines = Vec::with_capacity(instructions.len());
+    for entry in instructions {
+        if let Some(label) = &entry.label {
+            lines.push(Line::styled(
+                format!("{}:", label),
+                Style::default().fg(Color::Magenta),
+            ));
+        }
+        let mut style = Style::default().fg(instruction_color(entry.instruction.as_ref()));
+        if pc == Some(entry.address) {
+            style = style.add_modifier(Modifier::REVERSED);
+        }
+        lines.push(Line::from(vec![
+            Span::styled(
+                format!("  0x{:04X}: {:04X}  ", entry.address, entry.opcode),
+                Style::default().fg(Color::DarkGray),
+            ),
+            Span::styled(entry.mnemonic(), style),
+        ]));
+    }
+    Paragraph::new(lines).block(Block::default().borders(Borders::ALL).title("Disassembly"))
+}
+
 pub struct RatatuiRenderer {
     terminal: Terminal<CrosstermBackend<Stdout>>,
     config: RatatuiConfig,
     stats_history: VecDeque<(Instant, usize)>, // (timestamp, cycles) for FPS calculation
-    last_render: Instant,
+    frame_limiter: FrameLimiter,
     key_sender: Sender<KeyEvent>,
+    display_state: DisplayWidgetState,
+}
+
+/// State carried between redraws of the display widget
+struct DisplayWidgetState {
+    /// Frames since each pixel was last lit, for the motion trail
+    trail_history: TrailHistory,
+    /// Lines from the last redraw, rebuilt only for changed rows
+    lines: Vec<Line<'static>>,
+    /// Rows changed since their line was last built
+    dirty: [bool; DISPLAY_HEIGHT],
+    /// Display area width the lines were laid out for
+    width: u16,
+}
+
+impl DisplayWidgetState {
+    fn new() -> Self {
+        Self {
+            trail_history: [[u8::MAX; DISPLAY_WIDTH]; DISPLAY_HEIGHT],
+            lines: Vec::new(),
+            dirty: [true; DISPLAY_HEIGHT],
+            width: 0,
+        }
+    }
+
+    /// Collect rows the display reports as changed, to rebuild on the next redraw
+    fn mark_dirty(&mut self, dirty_rows: &[bool]) {
+        for (pending, &dirty) in self.dirty.iter_mut().zip(dirty_rows) {
+            *pending |= dirty;
+        }
+    }
+}
+
+/// Per-pixel count of rendered frames since the pixel was last lit
+type TrailHistory = [[u8; DISPLAY_WIDTH]; DISPLAY_HEIGHT];
+
+/// A rendering backend driven by the emulator loop
+///
+/// [`Emulator::run_with_renderer`](crate::Emulator::run_with_renderer) calls
+/// [`Renderer::render`] after each cycle (or each frame with
+/// `cycles_per_frame`) and applies the returned [`ControlAction`].
+pub trait Renderer {
+    /// Present the display with emulator stats, as of `now` on the emulator's clock
+    ///
+    /// The emulator clears the display's dirty rows after each call.
+    fn render(
+        &mut self,
+        display: &Display,
+        cycles_executed: usize,
+        now: Instant,
+        paused: bool,
+    ) -> Result<ControlAction, RendererError>;
+
+    /// Whether a user is at the controls
+    ///
+    /// An interactive renderer feeds live keypad input and lets breakpoints
+    /// pause emulation. Otherwise the emulator scripts input with
+    /// `auto_input` and stops at breakpoints, since nothing could resume it.
+    fn is_interactive(&self) -> bool {
+        true
+    }
+
+    /// RGB components of lit pixels, for screenshots
+    fn pixel_rgb(&self) -> (u8, u8, u8) {
+        (255, 255, 255)
+    }
+}
+
+impl Renderer for RatatuiRenderer {
+    fn render(
+        &mut self,
+        display: &Display,
+        cycles_executed: usize,
+        now: Instant,
+        paused: bool,
+    ) -> Result<ControlAction, RendererError> {
+        RatatuiRenderer::render(self, display, cycles_executed, now, paused)
+    }
+
+    fn pixel_rgb(&self) -> (u8, u8, u8) {
+        self.config.pixel_rgb()
+    }
+}
+
+/// Renderer that draws nothing, for headless runs
+#[derive(Debug, Default, Clone, Copy)]
+pub struct HeadlessRenderer;
+
+impl Renderer for HeadlessRenderer {
+    fn render(
+        &mut self,
+        _display: &Display,
+        _cycles_executed: usize,
+        _now: Instant,
+        _paused: bool,
+    ) -> Result<ControlAction, RendererError> {
+        Ok(ControlAction::None)
+    }
+
+    fn is_interactive(&self) -> bool {
+        false
+    }
+}
+
+/// Renderer that writes the display as [`Display::render_ascii`] text
+///
+/// A frame is written only when the display changed, at most once per
+/// refresh interval, followed by a blank line. No terminal setup is needed,
+/// so the output can be piped or captured.
+pub struct AsciiRenderer<W: Write = Stdout> {
+    out: W,
+    frame_limiter: FrameLimiter,
+    pending: bool,
+}
+
+impl AsciiRenderer {
+    /// Create an ASCII renderer writing to stdout every `refresh_rate_ms` at most
+    pub fn stdout(refresh_rate_ms: u64) -> Self {
+        Self::new(stdout(), refresh_rate_ms)
+    }
+}
+
+impl<W: Write> AsciiRenderer<W> {
+    /// Create an ASCII renderer writing to `out` every `refresh_rate_ms` at most
+    pub fn new(out: W, refresh_rate_ms: u64) -> Self {
+        Self {
+            out,
+            frame_limiter: FrameLimiter::new(refresh_rate_ms),
+            pending: false,
+        }
+    }
+
+    /// Get the writer back
+    pub fn into_inner(self) -> W {
+        self.out
+    }
+}
+
+impl<W: Write> Renderer for AsciiRenderer<W> {
+    fn render(
+        &mut self,
+        display: &Display,
+        _cycles_executed: usize,
+        now: Instant,
+        _paused: bool,
+    ) -> Result<ControlAction, RendererError> {
+        self.pending |= display.dirty_rows().contains(&true);
+        if self.pending && self.frame_limiter.should_render(now) {
+            writeln!(self.out, "{}", display.render_ascii())?;
+            self.out.flush()?;
+            self.pending = false;
+        }
+        Ok(ControlAction::None)
+    }
+
+    fn is_interactive(&self) -> bool {
+        false
+    }
 }
 
 impl RatatuiRenderer {
@@ -300,13 +1025,19 @@ impl RatatuiRenderer {
 
         Ok(Self {
             terminal,
+            frame_limiter: FrameLimiter::new(config.refresh_rate_ms),
             config,
             stats_history: VecDeque::with_capacity(100),
-            last_render: Instant::now(),
             key_sender,
+            display_state: DisplayWidgetState::new(),
         })
     }
 
+    /// Get the renderer configuration
+    pub fn config(&self) -> &RatatuiConfig {
+        &self.config
+    }
+
     fn validate_terminal() -> Result<(), RendererError> {
         // Check if we're in a TTY - check stdout since that's where we render
         if !IsTty::is_tty(&io::stdout()) {
@@ -322,17 +1053,22 @@ impl RatatuiRenderer {
         Ok(())
     }
 
-    /// Render the display with emulator stats
+    /// Render the display with emulator stats, as of `now` on the emulator's clock
+    ///
+    /// Only rows in [`Display::dirty_rows`] are rebuilt, so the caller should
+    /// clear the display's dirty rows after each call.
     pub fn render(
         &mut self,
         display: &Display,
         cycles_executed: usize,
+        now: Instant,
+        paused: bool,
     ) -> Result<ControlAction, RendererError> {
         // Process any pending terminal events and get any control actions
         let control_action = self.handle_events()?;
+        self.display_state.mark_dirty(display.dirty_rows());
 
         // Update stats history for FPS calculation
-        let now = Instant::now();
         self.stats_history.push_back((now, cycles_executed));
 
         // Keep only recent history (last 2 seconds)
@@ -345,16 +1081,29 @@ impl RatatuiRenderer {
         }
 
         // Only render at configured rate to avoid excessive redraws
-        if now.duration_since(self.last_render).as_millis() < self.config.refresh_rate_ms as u128 {
+        if !self.frame_limiter.should_render(now) {
             return Ok(control_action);
         }
-        self.last_render = now;
+
+        if self.config.motion_trail {
+            Self::update_trail_history(&mut self.display_state.trail_history, display);
+        }
 
         // Render the UI
         let config = &self.config;
         let stats_history = &self.stats_history;
-        self.terminal
-            .draw(|f| Self::draw_ui_static(f, display, cycles_executed, config, stats_history))?;
+        let display_state = &mut self.display_state;
+        self.terminal.draw(|f| {
+            Self::draw_ui_static(
+                f,
+                display,
+                cycles_executed,
+                paused,
+                config,
+                stats_history,
+                display_state,
+            )
+        })?;
 
         Ok(control_action)
     }
@@ -371,9 +1120,18 @@ impl RatatuiRenderer {
                         KeyCode::Char('r') if key.modifiers == KeyModifiers::CONTROL => {
                             return Ok(ControlAction::Reset);
                         }
+                        KeyCode::Char('s') if key.modifiers == KeyModifiers::CONTROL => {
+                            return Ok(ControlAction::Screenshot);
+                        }
+                        KeyCode::Char('q') if key.modifiers == KeyModifiers::CONTROL => {
+                            return Ok(ControlAction::QuitAndSave);
+                        }
                         KeyCode::Char(' ') => {
                             return Ok(ControlAction::TogglePause);
                         }
+                        KeyCode::F(5) => {
+                            return Ok(ControlAction::Reload);
+                        }
                         KeyCode::Esc => {
                             return Ok(ControlAction::Quit);
                         }
@@ -412,8 +1170,10 @@ impl RatatuiRenderer {
         f: &mut Frame,
         display: &Display,
         cycles_executed: usize,
+        paused: bool,
         config: &RatatuiConfig,
         stats_history: &VecDeque<(Instant, usize)>,
+        display_state: &mut DisplayWidgetState,
     ) {
         // Create main layout
         let chunks = Layout::default()
@@ -426,16 +1186,16 @@ impl RatatuiRenderer {
             .split(f.area());
 
         // Header
-        Self::draw_header_static(f, chunks[0]);
+        Self::draw_header_static(f, chunks[0], config);
 
         // Use the whole width for the display
-        Self::draw_display_static(f, chunks[1], display, config);
+        Self::draw_display_static(f, chunks[1], display, config, display_state);
 
         // Status bar
-        Self::draw_status_bar_static(f, chunks[2], cycles_executed, stats_history, config);
+        Self::draw_status_bar_static(f, chunks[2], cycles_executed, paused, stats_history, config);
     }
 
-    fn draw_header_static(f: &mut Frame, area: Rect) {
+    fn draw_header_static(f: &mut Frame, area: Rect, config: &RatatuiConfig) {
         let title = Line::from(vec![
             Span::styled(
                 "JOE ",
@@ -447,83 +1207,147 @@ impl RatatuiRenderer {
         ]);
 
         let header = Paragraph::new(title)
-            .block(Block::default().borders(Borders::ALL))
+            .block(config.block())
             .wrap(Wrap { trim: true });
 
         f.render_widget(header, area);
     }
 
-    fn draw_display_static(f: &mut Frame, area: Rect, display: &Display, config: &RatatuiConfig) {
-        let mut lines = Vec::new();
-        let chip8_width = DISPLAY_WIDTH;
-        let chip8_height = DISPLAY_HEIGHT;
-        let area_width = area.width as usize;
-
-        // Calculate the actual character width of each pixel
-        let pixel_char_width = config.pixel_char.chars().count();
-        let total_display_width = chip8_width * pixel_char_width;
+    fn draw_display_static(
+        f: &mut Frame,
+        area: Rect,
+        display: &Display,
+        config: &RatatuiConfig,
+        state: &mut DisplayWidgetState,
+    ) {
+        // Inset the display widget by the configured overscan border
+        let area = area.inner(config.border_padding);
+
+        // Trails fade every frame and a resize moves the padding, so rebuild everything
+        if config.motion_trail || state.width != area.width || state.lines.len() != DISPLAY_HEIGHT {
+            state.dirty = [true; DISPLAY_HEIGHT];
+            state.lines.resize(DISPLAY_HEIGHT, Line::default());
+            state.width = area.width;
+        }
 
         // Calculate horizontal padding for centering
-        let pad_left = if area_width > total_display_width {
-            (area_width - total_display_width) / 2
-        } else {
-            0
-        };
+        let (_, pad_left) = config.pixel_layout(area.width as usize);
 
-        for y in 0..chip8_height {
-            let mut line_spans = Vec::new();
-            // Add left padding if needed
-            for _ in 0..pad_left {
-                line_spans.push(Span::raw(" "));
+        for (y, dirty) in state.dirty.iter_mut().enumerate() {
+            if std::mem::take(dirty) {
+                state.lines[y] =
+                    Self::display_line(display, y, config, &state.trail_history[y], pad_left);
             }
-            for x in 0..chip8_width {
-                let pixel = display.get_pixel(x, y);
-                let pixel_color = RatatuiConfig::parse_color(&config.pixel_color);
-                line_spans.push(Span::styled(
-                    &config.pixel_char,
-                    if pixel {
-                        Style::default().fg(pixel_color)
-                    } else {
-                        Style::default().fg(Color::DarkGray)
-                    },
-                ));
-            }
-            lines.push(Line::from(line_spans));
         }
 
-        let display_widget = Paragraph::new(lines)
-            .block(
-                Block::default()
-                    .borders(Borders::ALL)
-                    .title("CHIP-8 Display"),
-            )
+        let display_widget = Paragraph::new(state.lines.clone())
+            .block(config.block().title("CHIP-8 Display"))
             .wrap(Wrap { trim: false });
 
         f.render_widget(display_widget, area);
     }
 
+    /// Build the line for display row `y`
+    fn display_line(
+        display: &Display,
+        y: usize,
+        config: &RatatuiConfig,
+        trail_row: &[u8; DISPLAY_WIDTH],
+        pad_left: usize,
+    ) -> Line<'static> {
+        let pixel_glyph = config.pixel_glyph();
+        let pixel_color = RatatuiConfig::parse_color(&config.pixel_color);
+        let pixel_row = display.row(y).unwrap_or([false; DISPLAY_WIDTH]);
+        let mut line_spans = Vec::new();
+        // Add left padding if needed
+        for _ in 0..pad_left {
+            line_spans.push(Span::raw(" "));
+        }
+        for (x, &frames_since_lit) in trail_row.iter().enumerate() {
+            let pixel = pixel_row[x];
+            let trail = if config.motion_trail {
+                Self::trail_intensity(frames_since_lit, config.motion_trail_frames)
+            } else {
+                None
+            };
+            line_spans.push(Span::styled(
+                pixel_glyph.clone(),
+                match (pixel, trail) {
+                    (true, _) => Style::default().fg(pixel_color),
+                    (false, Some(intensity)) => {
+                        let (r, g, b) = RatatuiConfig::color_rgb(pixel_color);
+                        let dim = |c: u8| (c as u16 * intensity as u16 / 255) as u8;
+                        Style::default().fg(Color::Rgb(dim(r), dim(g), dim(b)))
+                    }
+                    (false, None) => Style::default().fg(Color::DarkGray),
+                },
+            ));
+        }
+        Line::from(line_spans)
+    }
+
     // Side panel removed
 
     fn draw_status_bar_static(
         f: &mut Frame,
         area: Rect,
         cycles_executed: usize,
+        paused: bool,
         stats_history: &VecDeque<(Instant, usize)>,
         config: &RatatuiConfig,
     ) {
         let fps = Self::calculate_fps_static(stats_history);
-        let status_text = Line::from(format!(
-            "Running • Cycles: {} • FPS: {:.1} • Theme: {} | Controls: Ctrl+C=Quit, Space=Pause, Ctrl+R=Reset",
-            cycles_executed, fps, config.theme
+        let status_text = Line::from(Self::status_text(
+            paused,
+            cycles_executed,
+            fps,
+            &config.theme,
         ));
 
         let status = Paragraph::new(status_text)
-            .block(Block::default().borders(Borders::ALL))
+            .block(config.block())
             .wrap(Wrap { trim: true });
 
         f.render_widget(status, area);
     }
 
+    /// Status bar text: run state, counters and control hints
+    fn status_text(paused: bool, cycles_executed: usize, fps: f64, theme: &str) -> String {
+        let (state, pause_hint) = if paused {
+            ("Paused", "Space=Resume")
+        } else {
+            ("Running", "Space=Pause")
+        };
+        format!(
+            "{} • Cycles: {} • FPS: {:.1} • Theme: {} | Controls: Ctrl+C=Quit, {}, Ctrl+R=Reset, F5=Reload, Ctrl+S=Screenshot, Ctrl+Q=Save & Quit",
+            state, cycles_executed, fps, theme, pause_hint
+        )
+    }
+
+    /// Advance the trail history by one rendered frame
+    fn update_trail_history(trail_history: &mut TrailHistory, display: &Display) {
+        for (y, row) in trail_history.iter_mut().enumerate() {
+            for (x, frames_since_lit) in row.iter_mut().enumerate() {
+                *frames_since_lit = if display.get_pixel(x, y) {
+                    0
+                } else {
+                    frames_since_lit.saturating_add(1)
+                };
+            }
+        }
+    }
+
+    /// Trail intensity (0-255) for a pixel that was lit `frames_since_lit` frames ago
+    ///
+    /// Returns None for currently lit pixels (0) and for trails older than `trail_frames`.
+    fn trail_intensity(frames_since_lit: u8, trail_frames: u8) -> Option<u8> {
+        if frames_since_lit == 0 || frames_since_lit > trail_frames {
+            return None;
+        }
+        let steps = trail_frames as u16 + 1;
+        Some((255 * (steps - frames_since_lit as u16) / steps) as u8)
+    }
+
     fn calculate_fps_static(stats_history: &VecDeque<(Instant, usize)>) -> f64 {
         if stats_history.len() < 2 {
             return 0.0;
@@ -665,6 +1489,89 @@ mod tests {
         assert!(display.get_pixel(0, 1)); // Second wrapped row
     }
 
+    #[test]
+    fn test_clipped_sprite_collision_only_counts_drawn_rows() {
+        let mut display = Display::new();
+        display.set_clip_sprites(true);
+
+        // Pixel at the top-left would be hit by a wrapped row, but not a clipped one
+        display.set_pixel(0, 0, true);
+
+        // 3-row sprite starting at the last row: rows 1-2 are clipped
+        let sprite = [0b10000000; 3];
+        let collision = display.draw_sprite(0, 31, &sprite).unwrap();
+
+        assert!(!collision);
+        assert!(display.get_pixel(0, 31));
+        assert!(display.get_pixel(0, 0)); // Untouched by clipped rows
+        assert!(!display.get_pixel(0, 1));
+    }
+
+    #[test]
+    fn test_clipped_sprite_collision_on_drawn_row() {
+        let mut display = Display::new();
+        display.set_clip_sprites(true);
+
+        // Existing pixel on the one row that is actually drawn
+        display.set_pixel(0, 31, true);
+
+        let sprite = [0b10000000; 3];
+        let collision = display.draw_sprite(0, 31, &sprite).unwrap();
+
+        assert!(collision);
+        assert!(!display.get_pixel(0, 31));
+    }
+
+    #[test]
+    fn test_clipped_sprite_start_position_wraps() {
+        let mut display = Display::new();
+        display.set_clip_sprites(true);
+
+        // Starting coordinates wrap modulo screen size even when clipping
+        display.draw_sprite(64 + 2, 32 + 3, &[0b10000000]).unwrap();
+        assert!(display.get_pixel(2, 3));
+    }
+
+    /// Lit columns of row `y`
+    fn lit_columns(display: &Display, y: usize) -> Vec<usize> {
+        let row = display.row(y).unwrap();
+        (0..DISPLAY_WIDTH).filter(|&x| row[x]).collect()
+    }
+
+    #[test]
+    fn test_byte_crossing_right_edge_clip_and_wrap() {
+        // #.##.##. starting at x=60: bits 0-3 land on 60-63, bits 4-7 cross the edge
+        let sprite = [0b1011_0110, 0b1111_1111];
+
+        let mut clipped = Display::new();
+        clipped.set_clip_sprites(true);
+        clipped.set_pixel(1, 0, true); // Where bit 5 would wrap to
+        let collision = clipped.draw_sprite(60, 0, &sprite).unwrap();
+        assert!(!collision); // Dropped bits can't collide
+        assert_eq!(lit_columns(&clipped, 0), vec![1, 60, 62, 63]);
+        assert_eq!(lit_columns(&clipped, 1), vec![60, 61, 62, 63]);
+
+        let mut wrapped = Display::new();
+        wrapped.set_pixel(1, 0, true);
+        let collision = wrapped.draw_sprite(60, 0, &sprite).unwrap();
+        assert!(collision);
+        assert_eq!(lit_columns(&wrapped, 0), vec![2, 60, 62, 63]);
+        assert_eq!(lit_columns(&wrapped, 1), vec![0, 1, 2, 3, 60, 61, 62, 63]);
+    }
+
+    #[test]
+    fn test_byte_crossing_right_edge_after_start_wrap() {
+        // x=124 wraps to 60 first; clipping then drops the bits past column 63
+        let mut clipped = Display::new();
+        clipped.set_clip_sprites(true);
+        clipped.draw_sprite(124, 5, &[0b1100_0011]).unwrap();
+        assert_eq!(lit_columns(&clipped, 5), vec![60, 61]);
+
+        let mut wrapped = Display::new();
+        wrapped.draw_sprite(124, 5, &[0b1100_0011]).unwrap();
+        assert_eq!(lit_columns(&wrapped, 5), vec![2, 3, 60, 61]);
+    }
+
     #[test]
     fn test_multi_row_sprite() {
         let mut display = Display::new();
@@ -691,6 +1598,340 @@ mod tests {
         assert!(!display.get_pixel(13, 6));
     }
 
+    #[test]
+    fn test_scroll_down() {
+        let mut display = Display::new();
+        display.set_pixel(10, 5, true);
+        display.set_pixel(20, 30, true);
+
+        display.scroll_down(3);
+        assert!(display.get_pixel(10, 8));
+        assert!(!display.get_pixel(10, 5));
+        // Scrolled off the bottom, not wrapped to the top
+        assert_eq!(display.get_stats().pixels_on, 1);
+
+        display.scroll_down(40);
+        assert_eq!(display.get_stats().pixels_on, 0);
+    }
+
+    #[test]
+    fn test_scroll_left_and_right() {
+        let mut display = Display::new();
+        display.set_pixel(10, 5, true);
+        display.set_pixel(62, 6, true);
+
+        display.scroll_right();
+        assert!(display.get_pixel(14, 5));
+        assert_eq!(display.get_stats().pixels_on, 1);
+
+        display.scroll_left();
+        display.scroll_left();
+        assert!(display.get_pixel(6, 5));
+        assert_eq!(display.get_stats().pixels_on, 1);
+
+        display.set_pixel(2, 7, true);
+        display.scroll_left();
+        assert!(display.get_pixel(2, 5));
+        assert!(!display.get_pixel(DISPLAY_WIDTH - 2, 7));
+        assert_eq!(display.get_stats().pixels_on, 1);
+    }
+
+    fn dirty_row_indices(display: &Display) -> Vec<usize> {
+        (0..DISPLAY_HEIGHT)
+            .filter(|&y| display.dirty_rows()[y])
+            .collect()
+    }
+
+    #[test]
+    fn test_single_row_sprite_marks_one_dirty_row() {
+        let mut display = Display::new();
+        assert_eq!(dirty_row_indices(&display).len(), DISPLAY_HEIGHT);
+        display.clear_dirty_rows();
+        assert!(dirty_row_indices(&display).is_empty());
+
+        display.draw_sprite(10, 7, &[0xF0]).unwrap();
+        assert_eq!(dirty_row_indices(&display), vec![7]);
+
+        // Erasing it again dirties the same row; blank sprite rows don't
+        display.clear_dirty_rows();
+        display.draw_sprite(10, 7, &[0xF0, 0x00]).unwrap();
+        assert_eq!(dirty_row_indices(&display), vec![7]);
+
+        // Wrapped sprite rows mark the row they land on
+        display.clear_dirty_rows();
+        display.draw_sprite(0, 31, &[0x80, 0x80]).unwrap();
+        assert_eq!(dirty_row_indices(&display), vec![0, 31]);
+    }
+
+    #[test]
+    fn test_dirty_rows_from_set_pixel_clear_and_scroll() {
+        let mut display = Display::new();
+        display.clear_dirty_rows();
+
+        // Only an actual change dirties a row
+        display.set_pixel(3, 4, false);
+        assert!(dirty_row_indices(&display).is_empty());
+        display.set_pixel(3, 4, true);
+        assert_eq!(dirty_row_indices(&display), vec![4]);
+
+        display.clear_dirty_rows();
+        display.clear();
+        assert_eq!(dirty_row_indices(&display).len(), DISPLAY_HEIGHT);
+
+        display.clear_dirty_rows();
+        display.scroll_left();
+        assert_eq!(dirty_row_indices(&display).len(), DISPLAY_HEIGHT);
+    }
+
+    #[test]
+    fn test_row_and_column_accessors() {
+        let mut display = Display::new();
+        display.set_pixel(0, 5, true);
+        display.set_pixel(63, 5, true);
+        display.set_pixel(10, 20, true);
+
+        let row = display.row(5).unwrap();
+        assert!(row[0] && row[63]);
+        assert_eq!(row.iter().filter(|&&pixel| pixel).count(), 2);
+        assert!(display.row(100).is_none());
+
+        let column = display.column(10).unwrap();
+        assert!(column[20]);
+        assert_eq!(column.iter().filter(|&&pixel| pixel).count(), 1);
+        assert!(display.column(64).is_none());
+
+        let pixels = display.pixels();
+        assert!(pixels[5][0] && pixels[5][63] && pixels[20][10]);
+        assert_eq!(pixels.iter().flatten().filter(|&&pixel| pixel).count(), 3);
+    }
+
+    #[test]
+    fn test_lit_bounds() {
+        let mut display = Display::new();
+        assert_eq!(display.lit_bounds(), None);
+
+        display.set_pixel(12, 7, true);
+        assert_eq!(display.lit_bounds(), Some((12, 7, 12, 7)));
+
+        display.set_pixel(40, 3, true);
+        display.set_pixel(5, 20, true);
+        assert_eq!(display.lit_bounds(), Some((5, 3, 40, 20)));
+    }
+
+    #[test]
+    fn test_blit_modes() {
+        let mut display = Display::new();
+        assert!(!display.blit(0, 0, &[0b11000000], DrawMode::Xor));
+        assert!(display.get_pixel(0, 0) && display.get_pixel(1, 0));
+
+        // XOR over lit pixels toggles them off and reports the collision
+        assert!(display.blit(1, 0, &[0b11000000], DrawMode::Xor));
+        assert!(display.get_pixel(0, 0));
+        assert!(!display.get_pixel(1, 0));
+        assert!(display.get_pixel(2, 0));
+
+        // OR leaves lit pixels on but still reports the overlap
+        assert!(display.blit(0, 0, &[0b11100000], DrawMode::Or));
+        assert!((0..3).all(|x| display.get_pixel(x, 0)));
+        assert!(!display.blit(0, 5, &[0xFF; 20], DrawMode::Or));
+        assert!(display.get_pixel(7, 24));
+    }
+
+    #[test]
+    fn test_blit_clips_out_of_bounds_origins() {
+        let mut display = Display::new();
+
+        // Straddling the bottom-right corner: only the on-screen part lands, no wrap
+        assert!(!display.blit(62, 30, &[0xFF, 0xFF, 0xFF], DrawMode::Xor));
+        assert_eq!(display.get_stats().pixels_on, 4);
+        assert!(display.get_pixel(63, 31));
+        assert!(!display.get_pixel(0, 0));
+
+        // Origins entirely off-screen draw nothing
+        assert!(!display.blit(64, 0, &[0xFF], DrawMode::Xor));
+        assert!(!display.blit(0, 32, &[0xFF], DrawMode::Or));
+        assert!(!display.blit(usize::MAX, usize::MAX, &[0xFF], DrawMode::Or));
+        assert_eq!(display.get_stats().pixels_on, 4);
+    }
+
+    #[test]
+    fn test_to_indexed_scales_pixels() {
+        let mut display = Display::new();
+        display.set_pixel(1, 0, true);
+        display.set_pixel(63, 31, true);
+        let pixels = display.to_indexed(2);
+        assert_eq!(pixels.len(), 128 * 64);
+        // Pixel (1, 0) covers columns 2-3 of the first two rows
+        assert_eq!(&pixels[..5], &[0, 0, 1, 1, 0]);
+        assert_eq!(&pixels[128..133], &[0, 0, 1, 1, 0]);
+        assert_eq!(pixels.iter().filter(|&&p| p == 1).count(), 8);
+        assert_eq!(pixels[128 * 64 - 1], 1);
+        assert!(display.to_indexed(0).is_empty());
+    }
+
+    #[cfg(feature = "image")]
+    #[test]
+    fn test_to_png_writes_scaled_image() {
+        use std::io::Read;
+
+        let mut display = Display::new();
+        display.set_pixel(1, 0, true);
+        let path = std::env::temp_dir().join(format!("joe-screenshot-{}.png", std::process::id()));
+        display.to_png(&path, 4, (0, 255, 0)).unwrap();
+        let png = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+
+        assert_eq!(&png[..8], b"\x89PNG\r\n\x1a\n");
+        assert_eq!(&png[12..16], b"IHDR");
+        let width = u32::from_be_bytes(png[16..20].try_into().unwrap());
+        let height = u32::from_be_bytes(png[20..24].try_into().unwrap());
+        assert_eq!((width, height), (256, 128));
+
+        // IDAT follows the 25-byte IHDR chunk; its first scanline shows pixel (1, 0) lit
+        let idat_len = u32::from_be_bytes(png[33..37].try_into().unwrap()) as usize;
+        assert_eq!(&png[37..41], b"IDAT");
+        let mut scanlines = Vec::new();
+        flate2::read::ZlibDecoder::new(&png[41..41 + idat_len])
+            .read_to_end(&mut scanlines)
+            .unwrap();
+        assert_eq!(scanlines.len(), (256 * 3 + 1) * 128);
+        assert_eq!(&scanlines[1..4], &[0, 0, 0]);
+        assert_eq!(&scanlines[1 + 4 * 3..1 + 5 * 3], &[0, 255, 0]);
+
+        assert!(display.to_png(&path, 0, (0, 255, 0)).is_err());
+    }
+
+    #[test]
+    fn test_render_diff() {
+        let mut a = Display::new();
+        let mut b = Display::new();
+        a.draw_sprite(0, 0, &[0b11110000]).unwrap();
+        b.draw_sprite(0, 0, &[0b11001100]).unwrap();
+        b.set_pixel(63, 31, true);
+
+        let diff = Display::render_diff(&a, &b);
+        let lit: Vec<_> = (0..DISPLAY_HEIGHT)
+            .flat_map(|y| (0..DISPLAY_WIDTH).map(move |x| (x, y)))
+            .filter(|&(x, y)| diff.get_pixel(x, y))
+            .collect();
+        assert_eq!(lit, vec![(2, 0), (3, 0), (4, 0), (5, 0), (63, 31)]);
+        assert_eq!(Display::render_diff(&a, &a).get_stats().pixels_on, 0);
+
+        let ascii = diff.render_ascii();
+        let lines: Vec<_> = ascii.lines().collect();
+        assert_eq!(lines.len(), DISPLAY_HEIGHT);
+        assert_eq!(&lines[0][..8], "..####..");
+        assert!(lines[31].ends_with(".#"));
+    }
+
+    #[test]
+    fn test_flip_and_rotate() {
+        // An L shape: no flip or rotation maps it onto itself
+        let pattern = [(0, 0), (0, 1), (0, 2), (1, 2)];
+        let lit = |display: &Display| -> Vec<(usize, usize)> {
+            let mut pixels: Vec<_> = (0..DISPLAY_HEIGHT)
+                .flat_map(|y| (0..DISPLAY_WIDTH).map(move |x| (x, y)))
+                .filter(|&(x, y)| display.get_pixel(x, y))
+                .collect();
+            pixels.sort();
+            pixels
+        };
+        let original = || {
+            let mut display = Display::new();
+            for &(x, y) in &pattern {
+                display.set_pixel(x, y, true);
+            }
+            display
+        };
+
+        let mut display = original();
+        display.clear_dirty_rows();
+        display.flip_horizontal();
+        assert_eq!(lit(&display), vec![(62, 2), (63, 0), (63, 1), (63, 2)]);
+        assert!(display.dirty_rows().iter().all(|&dirty| dirty));
+
+        let mut display = original();
+        display.flip_vertical();
+        assert_eq!(lit(&display), vec![(0, 29), (0, 30), (0, 31), (1, 29)]);
+
+        let mut display = original();
+        display.rotate_180();
+        assert_eq!(lit(&display), vec![(62, 29), (63, 29), (63, 30), (63, 31)]);
+
+        // Each transform undoes itself
+        display.rotate_180();
+        assert_eq!(lit(&display), lit(&original()));
+    }
+
+    #[test]
+    fn test_ascii_renderer_writes_changed_frames() {
+        let mut display = Display::new();
+        let mut renderer = AsciiRenderer::new(Vec::new(), 0);
+        let now = Instant::now();
+        assert!(!renderer.is_interactive());
+
+        let action = renderer.render(&display, 1, now, false).unwrap();
+        assert_eq!(action, ControlAction::None);
+        display.clear_dirty_rows();
+        // Nothing changed, so nothing more is written
+        renderer.render(&display, 2, now, false).unwrap();
+        display.draw_sprite(0, 0, &[0x80]).unwrap();
+        renderer.render(&display, 3, now, false).unwrap();
+
+        let text = String::from_utf8(renderer.into_inner()).unwrap();
+        let frames: Vec<_> = text.split("\n\n").filter(|f| !f.is_empty()).collect();
+        assert_eq!(frames.len(), 2);
+        assert!(frames[0].starts_with("....."));
+        assert!(frames[1].starts_with("#...."));
+    }
+
+    #[test]
+    fn test_render_ascii_font_zero() {
+        let mut display = Display::new();
+        let zero = &crate::memory::FontSet::Standard.glyphs()[..5];
+        display.draw_sprite(0, 0, zero).unwrap();
+
+        let pad = ".".repeat(DISPLAY_WIDTH - 4);
+        let mut expected = String::new();
+        for glyph_row in ["####", "#..#", "#..#", "#..#", "####"] {
+            expected.push_str(&format!("{glyph_row}{pad}\n"));
+        }
+        for _ in 5..DISPLAY_HEIGHT {
+            expected.push_str(&format!("{}\n", ".".repeat(DISPLAY_WIDTH)));
+        }
+        assert_eq!(display.render_ascii(), expected);
+    }
+
+    #[test]
+    fn test_bitplane_round_trip() {
+        let mut display = Display::new();
+        display
+            .draw_sprite(3, 7, &[0b10110001, 0b01111110])
+            .unwrap();
+        display.set_pixel(63, 31, true);
+
+        let restored = Display::from_bitplane(&display.to_bitplane()).unwrap();
+        assert_eq!(restored.framebuffer, display.framebuffer);
+    }
+
+    #[test]
+    fn test_from_bitplane_lights_expected_pixels() {
+        let mut bitplane = vec![0u8; BITPLANE_SIZE];
+        bitplane[0] = 0b10000001; // (0, 0) and (7, 0)
+        bitplane[9] = 0b01000000; // Row 1, byte 1 -> (9, 1)
+
+        let display = Display::from_bitplane(&bitplane).unwrap();
+        assert!(display.get_pixel(0, 0));
+        assert!(display.get_pixel(7, 0));
+        assert!(display.get_pixel(9, 1));
+        assert_eq!(display.get_stats().pixels_on, 3);
+
+        assert!(matches!(
+            Display::from_bitplane(&[0u8; 10]),
+            Err(DisplayError::InvalidBitplaneSize { size: 10, .. })
+        ));
+    }
+
     #[test]
     fn test_empty_sprite_error() {
         let mut display = Display::new();
@@ -728,6 +1969,130 @@ mod tests {
         assert!(!display.get_pixel(100, 100));
     }
 
+    #[test]
+    fn test_instruction_color_families() {
+        let color = |instruction: Instruction| instruction_color(Some(&instruction));
+
+        for jump in [
+            Instruction::Jump { addr: 0x200 },
+            Instruction::Call { addr: 0x300 },
+            Instruction::Ret,
+            Instruction::SkipEqImm { vx: 1, value: 2 },
+            Instruction::SkipKeyNotPressed { vx: 3 },
+        ] {
+            assert_eq!(color(jump), Color::Yellow);
+        }
+        for draw in [
+            Instruction::Cls,
+            Instruction::Draw { vx: 0, vy: 1, n: 5 },
+            Instruction::ScrollDown { n: 2 },
+        ] {
+            assert_eq!(color(draw), Color::Green);
+        }
+        for arithmetic in [
+            Instruction::AddImm { vx: 0, value: 1 },
+            Instruction::SubnReg { vx: 0, vy: 1 },
+            Instruction::XorReg { vx: 2, vy: 3 },
+            Instruction::ShlReg { vx: 4 },
+            Instruction::AddIndex { vx: 5 },
+        ] {
+            assert_eq!(color(arithmetic), Color::Cyan);
+        }
+        assert_eq!(instruction_color(None), Color::Gray);
+        assert_eq!(
+            color(Instruction::LoadImm { vx: 0, value: 1 }),
+            Color::White
+        );
+        assert_eq!(color(Instruction::SetDelayTimer { vx: 0 }), Color::White);
+    }
+
+    #[test]
+    fn test_status_text_reflects_pause_state() {
+        let running = RatatuiRenderer::status_text(false, 42, 60.0, "classic");
+        assert!(running.starts_with("Running • Cycles: 42 • FPS: 60.0"));
+        assert!(running.contains("Space=Pause"));
+
+        let paused = RatatuiRenderer::status_text(true, 42, 0.0, "classic");
+        assert!(paused.starts_with("Paused"));
+        assert!(paused.contains("Space=Resume"));
+    }
+
+    #[test]
+    fn test_pixel_layout_follows_cell_aspect() {
+        // Without a hint the configured glyph is used as-is
+        let config = RatatuiConfig::default();
+        assert_eq!(config.pixel_glyph(), "██");
+        assert_eq!(config.pixel_layout(148), (2, 10));
+
+        // Tall cells: two characters per pixel
+        let tall = RatatuiConfig {
+            pixel_char: "#".to_string(),
+            cell_aspect: Some(2.0),
+            ..Default::default()
+        };
+        assert_eq!(tall.pixel_glyph(), "##");
+        assert_eq!(tall.pixel_layout(148), (2, 10));
+
+        // Square cells: one character per pixel, so more padding
+        let square = RatatuiConfig {
+            cell_aspect: Some(1.0),
+            ..Default::default()
+        };
+        assert_eq!(square.pixel_glyph(), "█");
+        assert_eq!(square.pixel_layout(148), (1, 42));
+
+        // Narrow areas get no padding
+        assert_eq!(square.pixel_layout(40), (1, 0));
+    }
+
+    #[test]
+    fn test_trail_intensity_fades_with_age() {
+        // Lit pixels and trails past the configured length get no tint
+        assert_eq!(RatatuiRenderer::trail_intensity(0, 4), None);
+        assert_eq!(RatatuiRenderer::trail_intensity(5, 4), None);
+        assert_eq!(RatatuiRenderer::trail_intensity(u8::MAX, 4), None);
+
+        assert_eq!(RatatuiRenderer::trail_intensity(1, 4), Some(204));
+        assert_eq!(RatatuiRenderer::trail_intensity(2, 4), Some(153));
+        assert_eq!(RatatuiRenderer::trail_intensity(4, 4), Some(51));
+
+        // A zero-length trail never tints
+        assert_eq!(RatatuiRenderer::trail_intensity(1, 0), None);
+    }
+
+    #[test]
+    fn test_parse_border_type() {
+        assert_eq!(
+            RatatuiConfig::parse_border_type("rounded"),
+            BorderType::Rounded
+        );
+        assert_eq!(
+            RatatuiConfig::parse_border_type("Double"),
+            BorderType::Double
+        );
+        assert_eq!(RatatuiConfig::parse_border_type("PLAIN"), BorderType::Plain);
+
+        // Unknown styles fall back to rounded
+        assert_eq!(
+            RatatuiConfig::parse_border_type("zigzag"),
+            BorderType::Rounded
+        );
+    }
+
+    #[test]
+    fn test_from_display_settings_reads_border() {
+        let mut settings = crate::config::Config::default().display;
+        let config = RatatuiConfig::from_display_settings(&settings);
+        assert_eq!(config.border_style, "rounded");
+        assert_eq!(config.border_padding, Margin::new(0, 0));
+
+        settings.border_style = "double".to_string();
+        settings.border_padding = [1, 3];
+        let config = RatatuiConfig::from_display_settings(&settings);
+        assert_eq!(config.border_style, "double");
+        assert_eq!(config.border_padding, Margin::new(3, 1));
+    }
+
     #[test]
     fn test_terminal_validation() {
         // We can't easily test terminal validation without mocking,
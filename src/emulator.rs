@@ -5,12 +5,30 @@
 //! their interactions. This simplifies usage and provides a clean API for
 //! running CHIP-8 programs.
 
-use crate::display::{ControlAction, RatatuiRenderer};
-use crate::input::{KeyEvent, resolve_key_mappings};
-use crate::{Cpu, Display, Input, InputBus, Memory};
-use std::sync::Arc;
+use crate::audio::{AudioOutput, NullAudio, TIMER_TICK, effective_beep_duration};
+use crate::clock::{Clock, SystemClock, TickScheduler};
+use crate::constants::{CPU_FREQUENCY, PROGRAM_START_ADDR, TIMER_FREQUENCY};
+use crate::cpu::IndexIncrementMode;
+use crate::display::{
+    ControlAction, DISPLAY_HEIGHT, DISPLAY_WIDTH, FrameLimiter, HeadlessRenderer, RatatuiRenderer,
+    Renderer,
+};
+use crate::input::{
+    ButtonMappings, InputSource, KeyEvent, resolve_button_mappings, resolve_key_mappings_with_case,
+};
+use crate::instruction::{Instruction, decode_opcode, decode_opcode_lenient};
+use crate::memory::FontSet;
+use crate::random::RandomSource;
+use crate::replay::{InputLog, InputPlayer, InputRecorder};
+use crate::rom_loader::{RomLoaderConfig, RomSource, load_rom_data_with_progress};
+use crate::{BreakReason, Cpu, CpuState, Display, Input, InputBus, Memory};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, VecDeque};
+use std::io::Write;
+use std::path::PathBuf;
 use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::mpsc;
+use std::sync::{Arc, Mutex, Once};
 use std::time::{Duration, Instant};
 use thiserror::Error;
 
@@ -31,8 +49,85 @@ pub enum EmulatorError {
 
     #[error("Input error: {0}")]
     Input(#[from] crate::input::InputError),
+
+    #[error("ROM loading error: {message}")]
+    RomLoad { message: String },
+
+    #[error("Unknown error policy: {name} (expected abort, warm-reset or stop)")]
+    UnknownErrorPolicy { name: String },
+
+    #[error("Unknown quirk profile: {name} (expected cosmac-vip, super-chip, xo-chip or modern)")]
+    UnknownQuirkProfile { name: String },
+
+    #[error("Invalid save state: {message}")]
+    InvalidSaveState { message: String },
+
+    #[error("Nothing to reload: the ROM was not loaded from a file or URL")]
+    NoRomSource,
+}
+
+/// What the emulation loop does when the CPU faults
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum ErrorPolicy {
+    /// Stop and return the error
+    #[default]
+    Abort,
+    /// Warm-reset the CPU and display, keeping the loaded program, and continue
+    WarmReset,
+    /// Stop cleanly, recording the error as the halt reason
+    Stop,
+}
+
+impl std::str::FromStr for ErrorPolicy {
+    type Err = EmulatorError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "abort" => Ok(Self::Abort),
+            "warm-reset" | "warm_reset" => Ok(Self::WarmReset),
+            "stop" => Ok(Self::Stop),
+            _ => Err(EmulatorError::UnknownErrorPolicy {
+                name: name.to_string(),
+            }),
+        }
+    }
+}
+
+/// Callback receiving the display once per frame, see [`Emulator::on_frame`]
+pub type FrameCallback = Box<dyn FnMut(&Display)>;
+
+/// Callback receiving `(pc, opcode, instruction)` for each executed
+/// instruction, see [`Emulator::set_trace_hook`]
+pub type TraceHook = Box<dyn FnMut(u16, u16, &Instruction)>;
+
+/// Save state format version, bumped whenever the serialized layout changes
+#[cfg(feature = "save-state")]
+const SAVE_STATE_VERSION: u32 = 7;
+
+/// Write a status line to the emulator's output, unless it is configured quiet
+///
+/// Write errors are ignored: status output is best-effort.
+macro_rules! say {
+    ($emulator:expr, $($arg:tt)*) => {
+        if !$emulator.config.quiet {
+            let _ = writeln!($emulator.output, $($arg)*);
+        }
+    };
 }
 
+/// Size in PNG pixels of one CHIP-8 pixel in TUI screenshots
+#[cfg(feature = "image")]
+const SCREENSHOT_SCALE: u32 = 10;
+
+/// How often the loop polls input and redraws while paused
+const PAUSED_POLL_INTERVAL: Duration = Duration::from_millis(16);
+
+/// Install the process-wide Ctrl+C handler once; it stops whichever loop is running
+static CTRLC_HANDLER: Once = Once::new();
+
+/// Running flag of the emulator the Ctrl+C handler should stop
+static CTRLC_TARGET: Mutex<Option<Arc<AtomicBool>>> = Mutex::new(None);
+
 /// Configuration options for the emulator
 #[derive(Debug, Clone)]
 pub struct EmulatorConfig {
@@ -40,13 +135,112 @@ pub struct EmulatorConfig {
     pub max_cycles: usize,
 
     /// Delay between CPU cycles in milliseconds
+    ///
+    /// Ignored when [`EmulatorConfig::cycles_per_frame`] or
+    /// [`EmulatorConfig::pace_by_cpu_hz`] is set.
     pub cycle_delay_ms: u64,
 
+    /// Run loop frame model: execute this many cycles, then tick the timers
+    /// once and render once, one frame per 1/`timer_hz`. Takes precedence
+    /// over `pace_by_cpu_hz` and `cycle_delay_ms`; 0 (the default) keeps the
+    /// per-cycle delay with wall-clock timers.
+    pub cycles_per_frame: usize,
+
+    /// CPU cycles per second for [`Emulator::run_tick`] and [`Emulator::run_for`],
+    /// and for the run loop with [`EmulatorConfig::pace_by_cpu_hz`]
+    pub cpu_hz: u32,
+
+    /// Run loop frame model with the cycle count of each frame worked out
+    /// from `cpu_hz`/`timer_hz` (8, 8, 9, ... at 500Hz/60Hz, at least one)
+    /// instead of a fixed `cycles_per_frame`
+    pub pace_by_cpu_hz: bool,
+
+    /// Delay/sound timer rate in Hz; timers tick once per 1/timer_hz of clock time
+    pub timer_hz: u32,
+
     /// Show CPU state after each cycle
     pub verbose: bool,
 
     /// Enable memory write protection
     pub write_protection: bool,
+
+    /// Seed for the RND instruction (None = derive from system entropy)
+    pub seed: Option<u64>,
+
+    /// Keys to tap in headless runs as (cycle, key): pressed before that cycle
+    /// executes and released before the next one
+    pub auto_input: Vec<(usize, u8)>,
+
+    /// Font set loaded into the interpreter area
+    pub font: FontSet,
+
+    /// COSMAC VIP quirk: OR, AND and XOR (8XY1/8XY2/8XY3) reset VF to 0.
+    /// Off by default to match modern interpreters; some quirk test ROMs expect it on.
+    pub reset_vf_on_logic: bool,
+
+    /// What to do when the CPU faults mid-run
+    pub on_cpu_error: ErrorPolicy,
+
+    /// FX55/FX65 quirk: how far I advances after the register transfer.
+    /// Unchanged by default; the COSMAC VIP advances it by x + 1.
+    pub index_increment: IndexIncrementMode,
+
+    /// Rescue ROM dumps with bit-rot: an EXxx with an unknown low byte runs as
+    /// the nearest of EX9E/EXA1, with a warning. Off by default.
+    pub lenient_decode: bool,
+
+    /// COSMAC VIP quirk: FX0A stores the key once it is released rather than
+    /// as soon as it is pressed. Off by default.
+    pub wait_key_on_release: bool,
+
+    /// Keep the SUPER-CHIP RPL user flags (FX75/FX85) across resets, as the
+    /// calculator hardware does. On by default.
+    pub keep_rpl_flags: bool,
+
+    /// COSMAC VIP quirk: DXYN stalls the CPU until the next 60Hz timer tick,
+    /// capping draws at one per frame. Off by default.
+    pub display_wait: bool,
+
+    /// Amiga interpreter quirk: FX1E sets VF to 1 when I + Vx passes 0xFFF
+    /// and to 0 otherwise. Off by default; Spacefight 2091! needs it.
+    pub add_index_sets_vf: bool,
+
+    /// COSMAC VIP quirk: 8XY6/8XYE set Vx to Vy shifted, with the shifted-out
+    /// bit of Vy in VF. Off by default, which shifts Vx in place.
+    pub shift_uses_vy: bool,
+
+    /// SUPER-CHIP quirk: BXNN jumps to XNN plus Vx. Off by default, which
+    /// jumps to NNN plus V0.
+    pub jump_uses_vx: bool,
+
+    /// Clip sprites at the screen edges instead of wrapping them, as most
+    /// modern interpreters do. The DXYN origin still wraps. Off by default.
+    pub clip_sprites: bool,
+
+    /// Shortest beep in milliseconds: once started, the beep lasts at least
+    /// this long even when the sound timer runs out sooner, so `LD ST, Vx`
+    /// with tiny values is still audible
+    pub min_beep_ms: u64,
+
+    /// Suppress all status output (startup banner, per-cycle lines, final
+    /// statistics), for embedding the emulator in another program
+    pub quiet: bool,
+
+    /// ROM-debugging aid: warn when a RET returns outside the loaded ROM or a
+    /// run ends with unreturned CALLs on the stack. Off by default.
+    pub track_stack_balance: bool,
+
+    /// Record the RAM high-water mark, readable through
+    /// [`Emulator::memory`]'s [`Memory::get_stats`]. Off by default.
+    pub track_ram_writes: bool,
+
+    /// Number of past machine states kept for [`Emulator::step_back`]; 0
+    /// (the default) disables rewinding. Requires the `save-state` feature.
+    pub rewind_depth: usize,
+
+    /// Number of recently executed instructions kept for crash diagnostics,
+    /// see [`RunReport::fault_trace`]; 0 disables the trace
+    pub trace_depth: usize,
 }
 
 impl Default for EmulatorConfig {
@@ -54,8 +248,163 @@ impl Default for EmulatorConfig {
         Self {
             max_cycles: 0,
             cycle_delay_ms: 16, // ~60fps
+            cycles_per_frame: 0,
+            cpu_hz: CPU_FREQUENCY,
+            pace_by_cpu_hz: false,
+            timer_hz: TIMER_FREQUENCY,
             verbose: false,
             write_protection: true,
+            seed: None,
+            auto_input: Vec::new(),
+            font: FontSet::default(),
+            reset_vf_on_logic: false,
+            index_increment: IndexIncrementMode::Unchanged,
+            on_cpu_error: ErrorPolicy::default(),
+            lenient_decode: false,
+            wait_key_on_release: false,
+            keep_rpl_flags: true,
+            display_wait: false,
+            add_index_sets_vf: false,
+            shift_uses_vy: false,
+            jump_uses_vx: false,
+            clip_sprites: false,
+            min_beep_ms: 50,
+            quiet: false,
+            track_stack_balance: false,
+            track_ram_writes: false,
+            rewind_depth: 0,
+            trace_depth: DEFAULT_TRACE_DEPTH,
+        }
+    }
+}
+
+impl EmulatorConfig {
+    /// Get the compatibility quirks this configuration enables
+    pub fn quirks(&self) -> QuirkSettings {
+        QuirkSettings {
+            reset_vf_on_logic: self.reset_vf_on_logic,
+            index_increment: self.index_increment,
+            wait_key_on_release: self.wait_key_on_release,
+            display_wait: self.display_wait,
+            add_index_sets_vf: self.add_index_sets_vf,
+            shift_uses_vy: self.shift_uses_vy,
+            jump_uses_vx: self.jump_uses_vx,
+            clip_sprites: self.clip_sprites,
+        }
+    }
+
+    /// Enable exactly the given compatibility quirks
+    pub fn set_quirks(&mut self, quirks: QuirkSettings) {
+        self.reset_vf_on_logic = quirks.reset_vf_on_logic;
+        self.index_increment = quirks.index_increment;
+        self.wait_key_on_release = quirks.wait_key_on_release;
+        self.display_wait = quirks.display_wait;
+        self.add_index_sets_vf = quirks.add_index_sets_vf;
+        self.shift_uses_vy = quirks.shift_uses_vy;
+        self.jump_uses_vx = quirks.jump_uses_vx;
+        self.clip_sprites = quirks.clip_sprites;
+    }
+}
+
+/// The interpreter-compatibility quirks of an [`EmulatorConfig`], as one value
+///
+/// Each field is the config field of the same name. Quirks live in the
+/// config, so resets, which rebuild the CPU and display from it, keep them.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct QuirkSettings {
+    /// OR/AND/XOR clear VF (COSMAC VIP)
+    pub reset_vf_on_logic: bool,
+
+    /// How far FX55/FX65 advance I (x + 1 on the COSMAC VIP)
+    pub index_increment: IndexIncrementMode,
+
+    /// FX0A stores the key once it is released (COSMAC VIP)
+    pub wait_key_on_release: bool,
+
+    /// DXYN waits for the next 60Hz tick (COSMAC VIP)
+    pub display_wait: bool,
+
+    /// FX1E sets VF when I passes 0xFFF (Amiga interpreters)
+    pub add_index_sets_vf: bool,
+
+    /// 8XY6/8XYE shift Vy into Vx instead of shifting Vx in place (COSMAC VIP)
+    pub shift_uses_vy: bool,
+
+    /// BXNN jumps to XNN plus Vx instead of NNN plus V0 (SUPER-CHIP)
+    pub jump_uses_vx: bool,
+
+    /// Sprites clip at the screen edges instead of wrapping
+    pub clip_sprites: bool,
+}
+
+/// A named set of quirks matching a well-known interpreter
+///
+/// | Profile      | VF reset | Load/store increment | Key release | Display wait | Clip | Shift Vy | Jump Vx |
+/// |--------------|----------|----------------------|-------------|--------------|------|----------|---------|
+/// | `CosmacVip`  | on       | x + 1                | on          | on           | on   | on       | off     |
+/// | `SuperChip`  | off      | none                 | off         | off          | on   | off      | on      |
+/// | `XoChip`     | off      | x + 1                | off         | off          | off  | off      | off     |
+/// | `Modern`     | off      | none                 | off         | off          | off  | off      | off     |
+///
+/// In config files the names are snake_case (`super_chip`); the kebab-case
+/// and short names [`str::parse`] accepts (`super-chip`, `schip`) work too.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuirkProfile {
+    /// The original COSMAC VIP interpreter
+    #[serde(alias = "cosmac-vip", alias = "vip")]
+    CosmacVip,
+    /// SUPER-CHIP 1.1 on the HP48
+    #[serde(alias = "super-chip", alias = "schip")]
+    SuperChip,
+    /// Octo's XO-CHIP
+    #[serde(alias = "xo-chip")]
+    XoChip,
+    /// What most modern interpreters and ROMs assume (all quirks off)
+    #[default]
+    Modern,
+}
+
+impl QuirkProfile {
+    /// Get the full set of quirk toggles for this profile
+    pub fn to_quirks(self) -> QuirkSettings {
+        match self {
+            Self::CosmacVip => QuirkSettings {
+                reset_vf_on_logic: true,
+                index_increment: IndexIncrementMode::XPlusOne,
+                wait_key_on_release: true,
+                display_wait: true,
+                add_index_sets_vf: false,
+                shift_uses_vy: true,
+                jump_uses_vx: false,
+                clip_sprites: true,
+            },
+            Self::SuperChip => QuirkSettings {
+                jump_uses_vx: true,
+                clip_sprites: true,
+                ..QuirkSettings::default()
+            },
+            Self::XoChip => QuirkSettings {
+                index_increment: IndexIncrementMode::XPlusOne,
+                ..QuirkSettings::default()
+            },
+            Self::Modern => QuirkSettings::default(),
+        }
+    }
+}
+
+impl std::str::FromStr for QuirkProfile {
+    type Err = EmulatorError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.trim().to_ascii_lowercase().replace('_', "-").as_str() {
+            "cosmac-vip" | "vip" => Ok(Self::CosmacVip),
+            "super-chip" | "schip" => Ok(Self::SuperChip),
+            "xo-chip" => Ok(Self::XoChip),
+            "modern" => Ok(Self::Modern),
+            _ => Err(EmulatorError::UnknownQuirkProfile {
+                name: name.to_string(),
+            }),
         }
     }
 }
@@ -77,6 +426,84 @@ pub struct EmulatorStats {
 
     /// Whether emulation is currently running
     pub is_running: bool,
+
+    /// Instructions per second actually achieved by the last run (0 before any run)
+    pub effective_ips: f64,
+
+    /// Clock time the last run took
+    pub run_duration: Duration,
+}
+
+/// Default for [`EmulatorConfig::trace_depth`]
+pub const DEFAULT_TRACE_DEPTH: usize = 16;
+
+/// One recently executed instruction, see [`RunReport::fault_trace`]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TraceEntry {
+    pub pc: u16,
+    pub opcode: u16,
+    /// Disassembly, or `DW NNNN` for an opcode that did not decode
+    pub mnemonic: String,
+}
+
+/// Reason the emulation loop stopped
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case", tag = "kind")]
+pub enum HaltReason {
+    /// The emulation loop has not stopped (or never ran)
+    NotHalted,
+    /// The configured maximum cycle count was reached
+    MaxCycles,
+    /// Interrupted by Ctrl+C
+    Interrupted,
+    /// The user requested quit from the renderer
+    UserQuit,
+    /// A headless run reached a breakpoint
+    Breakpoint { addr: u16 },
+    /// Execution failed with an error
+    Error { message: String },
+}
+
+/// Machine-readable summary of an emulation run
+#[derive(Debug, Clone, Serialize)]
+pub struct RunReport {
+    /// Total cycles executed
+    pub cycles_executed: usize,
+
+    /// Why the emulation loop stopped
+    pub halt_reason: HaltReason,
+
+    /// Final CPU program counter
+    pub program_counter: u16,
+
+    /// Final CPU index register
+    pub index_register: u16,
+
+    /// Number of lit pixels on the final display
+    pub pixels_on: usize,
+
+    /// Executed instruction counts keyed by opcode pattern (e.g. "DXYN")
+    pub opcode_histogram: BTreeMap<String, usize>,
+
+    /// Non-fatal issues encountered during the run
+    pub warnings: Vec<String>,
+
+    /// CPU errors recovered from by warm-resetting ([`ErrorPolicy::WarmReset`])
+    pub warm_resets: usize,
+
+    /// Seed used for the RND instruction; pass to `--seed` to reproduce the run
+    pub rng_seed: u64,
+
+    /// Instructions leading up to the last CPU fault, oldest first and ending
+    /// with the one that faulted (empty if there was no fault)
+    pub fault_trace: Vec<TraceEntry>,
+}
+
+impl RunReport {
+    /// Serialize the report as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
 }
 
 /// Main CHIP-8 emulator that coordinates all components
@@ -87,24 +514,90 @@ pub struct Emulator {
     input: Input,
     config: EmulatorConfig,
     cycles_executed: usize,
+    /// Wall-clock duration of the last emulation loop
+    run_duration: Duration,
+    /// Cycles the last emulation loop executed, which later steps don't add to
+    run_cycle_count: usize,
     is_running: Arc<AtomicBool>,
     last_display_hash: u64,
     last_render_time: Instant,
+    halt_reason: HaltReason,
+    opcode_histogram: BTreeMap<&'static str, usize>,
+    warnings: Vec<String>,
+    /// CPU errors the current run recovered from by warm-resetting
+    warm_resets: usize,
+    auto_input_pressed: Vec<u8>,
+    timer_accumulator: Duration,
+    tick_scheduler: TickScheduler,
+    last_run_cycles: Option<Instant>,
+    audio: Box<dyn AudioOutput>,
+    beeping: bool,
+    /// Timer ticks the current beep lasts at least, per `min_beep_ms`
+    beep_hold_ticks: u32,
+    clock: Box<dyn Clock>,
+    paused: bool,
+    frame_callback: Option<FrameCallback>,
+    trace_hook: Option<TraceHook>,
+    /// Last `trace_depth` instructions about to execute, oldest first
+    recent_instructions: VecDeque<(u16, u16, Option<Instruction>)>,
+    fault_trace: Vec<TraceEntry>,
+    /// Save states from before each recent cycle, oldest first, each with
+    /// the random source as it was then
+    #[cfg(feature = "save-state")]
+    rewind: VecDeque<(Vec<u8>, Box<dyn RandomSource>)>,
+    recorder: Option<InputRecorder>,
+    player: Option<InputPlayer>,
+    /// Random source injected with `set_random_source`, as it was handed in
+    random_source: Option<Box<dyn RandomSource>>,
+    /// File path or URL the ROM was loaded from and the loader settings
+    /// used, for reloading
+    rom_source: Option<(String, RomLoaderConfig)>,
+    /// Where the quit-and-save key writes the state
+    resume_state_path: Option<PathBuf>,
+    /// Where status lines go (stdout unless replaced)
+    output: Box<dyn Write>,
 }
 
 impl Emulator {
     /// Create a new emulator with the given configuration
     pub fn new(config: EmulatorConfig) -> Self {
         Self {
-            cpu: Cpu::new(),
-            memory: Memory::new(config.write_protection),
-            display: Display::new(),
+            cpu: new_cpu(&config, config.seed),
+            memory: new_memory(&config),
+            display: new_display(&config),
             input: Input::new(),
+            tick_scheduler: TickScheduler::new(config.cpu_hz, config.timer_hz),
             config,
             cycles_executed: 0,
+            run_duration: Duration::ZERO,
+            run_cycle_count: 0,
             is_running: Arc::new(AtomicBool::new(false)),
             last_display_hash: 0,
             last_render_time: Instant::now(),
+            halt_reason: HaltReason::NotHalted,
+            opcode_histogram: BTreeMap::new(),
+            warnings: Vec::new(),
+            warm_resets: 0,
+            auto_input_pressed: Vec::new(),
+            timer_accumulator: Duration::ZERO,
+            last_run_cycles: None,
+            audio: Box::new(NullAudio),
+            beeping: false,
+            beep_hold_ticks: 0,
+            clock: Box::new(SystemClock),
+            paused: false,
+            frame_callback: None,
+            trace_hook: None,
+            recent_instructions: VecDeque::new(),
+            fault_trace: Vec::new(),
+            #[cfg(feature = "save-state")]
+            rewind: VecDeque::new(),
+            recorder: None,
+            player: None,
+            random_source: None,
+            rom_source: None,
+            resume_state_path: None,
+            output: Box::new(std::io::stdout()),
         }
     }
 
@@ -113,9 +606,60 @@ impl Emulator {
         Self::new(EmulatorConfig::default())
     }
 
+    /// Create a new emulator from `config` with `quirks` replacing its quirk fields
+    pub fn with_config_and_quirks(mut config: EmulatorConfig, quirks: QuirkSettings) -> Self {
+        config.set_quirks(quirks);
+        Self::new(config)
+    }
+
     /// Load ROM data into the emulator's memory
     pub fn load_rom(&mut self, rom_data: &[u8]) -> Result<(), EmulatorError> {
         self.memory.load_rom(rom_data)?;
+        self.rom_source = None;
+        Ok(())
+    }
+
+    /// Load a ROM from a file path or HTTP(S) URL, returning the detected source
+    pub fn load_rom_from_source(&mut self, source: &str) -> Result<RomSource, EmulatorError> {
+        self.load_rom_from_source_with_progress(source, &RomLoaderConfig::default(), &mut |_, _| {})
+    }
+
+    /// Load a ROM from a file path or URL with custom loader configuration,
+    /// reporting URL download progress
+    pub fn load_rom_from_source_with_progress(
+        &mut self,
+        source: &str,
+        config: &RomLoaderConfig,
+        on_progress: &mut dyn FnMut(usize, Option<u64>),
+    ) -> Result<RomSource, EmulatorError> {
+        let rom_data = load_rom_data_with_progress(source, config, on_progress).map_err(|e| {
+            EmulatorError::RomLoad {
+                message: format!("{:#}", e),
+            }
+        })?;
+        self.load_rom(&rom_data)?;
+        self.rom_source = Some((source.to_string(), config.clone()));
+        Ok(RomSource::from_string(source))
+    }
+
+    /// Re-read the ROM from the file or URL it was loaded from and restart it
+    ///
+    /// The loader settings of the original load (size limit, allowed hosts,
+    /// metadata stripping...) apply again. Memory is rebuilt from the new
+    /// bytes and the CPU and display are warm-reset; on a load error the
+    /// running program is left untouched.
+    pub fn reload_rom(&mut self) -> Result<(), EmulatorError> {
+        let (source, config) = self.rom_source.as_ref().ok_or(EmulatorError::NoRomSource)?;
+        let rom_data =
+            load_rom_data_with_progress(source, config, &mut |_, _| {}).map_err(|e| {
+                EmulatorError::RomLoad {
+                    message: format!("{:#}", e),
+                }
+            })?;
+        let mut memory = new_memory(&self.config);
+        memory.load_rom(&rom_data)?;
+        self.memory = memory;
+        self.warm_reset();
         Ok(())
     }
 
@@ -125,81 +669,149 @@ impl Emulator {
         let user_config = crate::config::ConfigManager::new()
             .and_then(|manager| manager.load())
             .unwrap_or_else(|e| {
-                eprintln!("Warning: Failed to load config: {}. Using defaults.", e);
+                if !self.config.quiet {
+                    eprintln!("Warning: Failed to load config: {}. Using defaults.", e);
+                }
+                self.warnings
+                    .push(format!("Failed to load config: {}. Using defaults.", e));
                 crate::config::Config::default()
             });
 
+        if self.resume_state_path.is_none() {
+            self.resume_state_path = crate::config::ConfigManager::new()
+                .ok()
+                .map(|manager| manager.resume_state_path(&user_config.emulator));
+        }
+
         // Create key event channel
         let (key_sender, key_receiver) = mpsc::channel::<KeyEvent>();
-
-        // Create input system with resolved config mappings and channel receiver
-        let key_mappings = resolve_key_mappings(Some(&user_config.input.key_mappings))?;
-        self.input = Input::with_mappings(key_mappings, Some(key_receiver));
+        self.apply_input_settings(&user_config.input, key_receiver)?;
 
         // Create renderer with key sender
         let ratatui_config =
             crate::display::RatatuiConfig::from_display_settings(&user_config.display);
-        let renderer = RatatuiRenderer::new(ratatui_config, key_sender)?;
+        let mut renderer = RatatuiRenderer::new(ratatui_config, key_sender)?;
+
+        self.run_with_renderer(&mut renderer)
+    }
 
-        self.run_with_renderer(Some(renderer))
+    /// Rebuild the input system from the user's input settings, reading key
+    /// events from `key_receiver`
+    ///
+    /// An attached input source carries over, remapped by the configured
+    /// button mappings.
+    fn apply_input_settings(
+        &mut self,
+        settings: &crate::config::InputSettings,
+        key_receiver: mpsc::Receiver<KeyEvent>,
+    ) -> Result<(), EmulatorError> {
+        let key_mappings =
+            resolve_key_mappings_with_case(Some(&settings.key_mappings), settings.case_sensitive)?;
+        let button_mappings = resolve_button_mappings(Some(&settings.button_mappings))?;
+        let mut input = Input::with_mappings(key_mappings, Some(key_receiver));
+        if let Some(source) = self.input.take_input_source() {
+            input.set_input_source(source, button_mappings);
+        }
+        self.input = input;
+        Ok(())
     }
 
     /// Run the emulator without terminal UI (headless mode)
     pub fn run_headless(&mut self) -> Result<(), EmulatorError> {
-        self.run_with_renderer(None)
+        self.run_with_renderer(&mut HeadlessRenderer)
     }
 
-    /// Core emulation loop with optional renderer
-    fn run_with_renderer(
-        &mut self,
-        mut renderer: Option<RatatuiRenderer>,
-    ) -> Result<(), EmulatorError> {
+    /// Run the emulation loop, presenting frames through `renderer`
+    ///
+    /// Unlike [`Emulator::run`] this loads no user config and leaves the
+    /// input system as it is. A non-interactive renderer (see
+    /// [`Renderer::is_interactive`]) runs like [`Emulator::run_headless`].
+    pub fn run_with_renderer(&mut self, renderer: &mut dyn Renderer) -> Result<(), EmulatorError> {
+        let interactive = renderer.is_interactive();
         self.is_running.store(true, Ordering::SeqCst);
         self.cycles_executed = 0;
-
-        // Set up Ctrl+C handler
-        let running = self.is_running.clone();
-        ctrlc::set_handler(move || {
-            running.store(false, Ordering::SeqCst);
-        })
-        .expect("Error setting Ctrl+C handler");
+        self.warm_resets = 0;
+        self.run_duration = Duration::ZERO;
+        self.run_cycle_count = 0;
+        self.halt_reason = HaltReason::NotHalted;
+        let run_started = self.clock.now();
+        self.install_ctrlc_handler();
 
         let cycle_delay = Duration::from_millis(self.config.cycle_delay_ms);
+        let cycles_per_frame = self.config.cycles_per_frame;
+        let frame_mode = cycles_per_frame > 0 || self.config.pace_by_cpu_hz;
 
         // Print appropriate startup message
-        if renderer.is_some() {
-            println!("Starting emulation...");
+        if interactive {
+            say!(self, "Starting emulation...");
         } else {
-            println!("Starting emulation in headless mode...");
+            say!(self, "Starting emulation in headless mode...");
         }
 
         if self.config.verbose {
-            println!("Verbose mode enabled - showing CPU state each cycle");
+            say!(self, "Verbose mode enabled - showing CPU state each cycle");
         }
+        let pacing = if cycles_per_frame > 0 {
+            format!("{} cycles per frame", cycles_per_frame)
+        } else if frame_mode {
+            format!(
+                "{}Hz CPU, {}Hz timers",
+                self.config.cpu_hz, self.config.timer_hz
+            )
+        } else {
+            format!("Cycle delay: {}ms", self.config.cycle_delay_ms)
+        };
         if self.config.max_cycles > 0 {
-            println!(
-                "Max cycles: {}, Cycle delay: {}ms",
-                self.config.max_cycles, self.config.cycle_delay_ms
-            );
+            say!(self, "Max cycles: {}, {}", self.config.max_cycles, pacing);
         } else {
-            println!(
-                "Running indefinitely, Cycle delay: {}ms",
-                self.config.cycle_delay_ms
-            );
+            say!(self, "Running indefinitely, {}", pacing);
         }
-        println!("Press Ctrl+C to stop\n");
+        say!(self, "Press Ctrl+C to stop\n");
+
+        // Timers run at timer_hz of clock time, independent of the cycle rate;
+        // in the frame model each frame is one timer tick
+        let frame_interval = self.tick_scheduler.tick_duration();
+        let mut last_timer_update = self.clock.now();
+        let mut frames = FrameLimiter::with_interval(TIMER_TICK);
+        let mut next_frame = last_timer_update + frame_interval;
+        let mut frame_cycles_left = if frame_mode {
+            self.next_frame_cycles()
+        } else {
+            0
+        };
 
         loop {
             // Check if user pressed Ctrl+C
             if !self.is_running.load(Ordering::SeqCst) {
-                println!("\nReceived Ctrl+C, stopping...");
+                say!(self, "\nReceived Ctrl+C, stopping...");
+                self.halt_reason = HaltReason::Interrupted;
                 break;
             }
 
+            // While paused, keep polling input and rendering but freeze the CPU and timers
+            if self.paused {
+                let now = self.clock.now();
+                last_timer_update = now;
+                self.emit_frame(&mut frames, now);
+                self.input.update();
+                let action = renderer.render(&self.display, self.cycles_executed, now, true)?;
+                self.display.clear_dirty_rows();
+                if self.handle_control_action(action, renderer) {
+                    break;
+                }
+                std::thread::sleep(PAUSED_POLL_INTERVAL);
+                continue;
+            }
+
             self.cycles_executed += 1;
+            let frame_done = frame_mode && {
+                frame_cycles_left -= 1;
+                frame_cycles_left == 0
+            };
 
             if self.config.verbose {
-                println!(
+                say!(
+                    self,
                     "Cycle {}: PC=0x{:04X}, I=0x{:04X}",
                     self.cycles_executed,
                     self.cpu.get_pc(),
@@ -207,216 +819,2667 @@ impl Emulator {
                 );
             }
 
-            // Poll input backend (only needed for renderer mode)
-            if renderer.is_some() {
+            // Poll input backend (only needed for interactive renderers)
+            if interactive {
                 self.input.update();
+            } else {
+                self.apply_auto_input(self.cycles_executed - 1)?;
+            }
+            self.replay_and_record_input(self.cycles_executed - 1)?;
+
+            // In the frame model the timers tick once per frame instead
+            let now = self.clock.now();
+            if !frame_mode {
+                self.advance_timers(now - last_timer_update);
+                last_timer_update = now;
             }
 
             // Execute one CPU cycle
+            self.push_rewind();
+            self.record_opcode();
+            let returning = self.about_to_return();
             match self
                 .cpu
                 .execute_cycle(&mut self.memory, &mut self.display, &mut self.input)
             {
                 Ok(()) => {
+                    if returning {
+                        self.check_return_target();
+                    }
+                    self.update_audio();
+                    self.emit_frame(&mut frames, now);
+
+                    // Breakpoints pause the TUI; a headless run has no way to resume
+                    if let Some(addr) = self.cpu.breakpoint_hit() {
+                        if !interactive {
+                            say!(self, "Breakpoint at 0x{:04X}, stopping", addr);
+                            self.halt_reason = HaltReason::Breakpoint { addr };
+                            break;
+                        }
+                        say!(self, "Breakpoint at 0x{:04X}, paused", addr);
+                        self.paused = true;
+                        self.silence_audio();
+                    }
+
                     // Check for max cycles limit (if set)
                     if self.config.max_cycles > 0 && self.cycles_executed >= self.config.max_cycles
                     {
-                        println!(
+                        say!(
+                            self,
                             "Reached maximum cycles ({}), stopping",
                             self.config.max_cycles
                         );
+                        self.halt_reason = HaltReason::MaxCycles;
                         break;
                     }
 
-                    // Handle display rendering and control actions (only if renderer exists)
-                    if let Some(ref mut r) = renderer {
-                        match r.render(&self.display, self.cycles_executed)? {
-                            ControlAction::Quit => {
-                                println!("\nReceived quit command, stopping...");
-                                break;
-                            }
-                            ControlAction::Reset => {
-                                println!("\nResetting emulator...");
-                                self.reset();
-                            }
-                            ControlAction::TogglePause => {
-                                // TODO: Implement pause functionality
-                                println!("\nPause/Resume functionality not yet implemented");
-                            }
-                            ControlAction::None => {
-                                // Continue normal execution
-                            }
+                    // Handle display rendering and control actions
+                    if frame_done || !frame_mode {
+                        let action =
+                            renderer.render(&self.display, self.cycles_executed, now, false)?;
+                        self.display.clear_dirty_rows();
+                        if self.handle_control_action(action, renderer) {
+                            break;
                         }
                     }
                 }
                 Err(e) => {
-                    println!("Execution error at cycle {}: {}", self.cycles_executed, e);
-                    return Err(EmulatorError::Cpu(e));
+                    say!(
+                        self,
+                        "Execution error at cycle {}: {}",
+                        self.cycles_executed,
+                        e
+                    );
+                    self.capture_fault_trace();
+                    if !self.fault_trace.is_empty() {
+                        say!(self, "Recent instructions:");
+                        for entry in &self.fault_trace {
+                            say!(
+                                self,
+                                "  0x{:04X}: {:04X}  {}",
+                                entry.pc,
+                                entry.opcode,
+                                entry.mnemonic
+                            );
+                        }
+                    }
+                    match self.config.on_cpu_error {
+                        ErrorPolicy::Abort => {
+                            self.halt_reason = HaltReason::Error {
+                                message: e.to_string(),
+                            };
+                            self.is_running.store(false, Ordering::SeqCst);
+                            self.silence_audio();
+                            return Err(EmulatorError::Cpu(e));
+                        }
+                        ErrorPolicy::WarmReset => {
+                            say!(self, "Warm-resetting and continuing...");
+                            self.warnings.push(format!(
+                                "Warm reset after CPU error at cycle {}: {}",
+                                self.cycles_executed, e
+                            ));
+                            self.warm_reset();
+                            self.warm_resets += 1;
+                            if self.config.max_cycles > 0
+                                && self.cycles_executed >= self.config.max_cycles
+                            {
+                                self.halt_reason = HaltReason::MaxCycles;
+                                break;
+                            }
+                        }
+                        ErrorPolicy::Stop => {
+                            self.halt_reason = HaltReason::Error {
+                                message: e.to_string(),
+                            };
+                            break;
+                        }
+                    }
                 }
             }
 
-            // Add delay between cycles
-            if self.config.cycle_delay_ms > 0 {
+            // End the frame, or add delay between cycles
+            if frame_done {
+                self.tick_timers();
+                let now = self.clock.now();
+                if next_frame > now {
+                    std::thread::sleep(next_frame - now);
+                }
+                next_frame = next_frame.max(now) + frame_interval;
+                frame_cycles_left = self.next_frame_cycles();
+            } else if !frame_mode && self.config.cycle_delay_ms > 0 {
                 std::thread::sleep(cycle_delay);
             }
         }
 
         self.is_running.store(false, Ordering::SeqCst);
+        self.silence_audio();
+        self.run_duration = self.clock.now().duration_since(run_started);
+        self.run_cycle_count = self.cycles_executed;
 
         // Show final results and statistics
         self.show_final_statistics();
         Ok(())
     }
 
-    /// Execute a single cycle without the full emulation loop
-    pub fn step(&mut self) -> Result<(), EmulatorError> {
-        self.input.update();
-        self.cpu
-            .execute_cycle(&mut self.memory, &mut self.display, &mut self.input)?;
-        self.cycles_executed += 1;
-        Ok(())
-    }
-
-    /// Get current emulator statistics
-    pub fn get_stats(&self) -> EmulatorStats {
-        EmulatorStats {
-            cycles_executed: self.cycles_executed,
-            program_counter: self.cpu.get_pc(),
-            index_register: self.cpu.get_index(),
-            display_stats: self.display.get_stats(),
-            is_running: self.is_running.load(Ordering::SeqCst),
+    /// Cycles the next run loop frame executes, see [`EmulatorConfig::pace_by_cpu_hz`]
+    fn next_frame_cycles(&mut self) -> usize {
+        if self.config.cycles_per_frame > 0 {
+            self.config.cycles_per_frame
+        } else {
+            (self.tick_scheduler.next_tick_cycles() as usize).max(1)
         }
     }
 
-    /// Stop the emulation loop
-    pub fn stop(&self) {
-        self.is_running.store(false, Ordering::SeqCst);
+    /// Point the Ctrl+C handler at this emulator's running flag, installing
+    /// the process-wide handler on first use
+    fn install_ctrlc_handler(&self) {
+        *CTRLC_TARGET.lock().unwrap_or_else(|e| e.into_inner()) = Some(self.is_running.clone());
+        let quiet = self.config.quiet;
+        CTRLC_HANDLER.call_once(|| {
+            let result = ctrlc::set_handler(|| {
+                if let Some(running) = CTRLC_TARGET
+                    .lock()
+                    .unwrap_or_else(|e| e.into_inner())
+                    .as_ref()
+                {
+                    running.store(false, Ordering::SeqCst);
+                }
+            });
+            if let Err(e) = result
+                && !quiet
+            {
+                eprintln!("Warning: Failed to set Ctrl+C handler: {}", e);
+            }
+        });
     }
 
-    /// Get a reference to the display
-    pub fn display(&self) -> &Display {
-        &self.display
+    /// Apply a control action from the renderer, returning true if the loop should stop
+    fn handle_control_action(&mut self, action: ControlAction, renderer: &dyn Renderer) -> bool {
+        match action {
+            ControlAction::Quit => {
+                say!(self, "\nReceived quit command, stopping...");
+                self.halt_reason = HaltReason::UserQuit;
+                return true;
+            }
+            ControlAction::Reset => {
+                say!(self, "\nResetting emulator...");
+                self.reset();
+            }
+            ControlAction::TogglePause => self.toggle_pause(),
+            ControlAction::Reload => {
+                say!(self, "\nReloading ROM...");
+                if let Err(e) = self.reload_rom() {
+                    say!(self, "Reload failed: {}", e);
+                    self.warnings.push(format!("Reload failed: {}", e));
+                }
+            }
+            ControlAction::Screenshot => self.save_screenshot(renderer.pixel_rgb()),
+            ControlAction::QuitAndSave => return self.quit_and_save(),
+            ControlAction::None => {
+                // Continue normal execution
+            }
+        }
+        false
     }
 
-    /// Get a reference to the CPU
-    pub fn cpu(&self) -> &Cpu {
-        &self.cpu
+    /// Set where the quit-and-save key (Ctrl+Q) writes the state
+    ///
+    /// By default [`Emulator::run`] uses the path from the user's config, see
+    /// [`ConfigManager::resume_state_path`](crate::ConfigManager::resume_state_path).
+    pub fn set_resume_state_path(&mut self, path: impl Into<PathBuf>) {
+        self.resume_state_path = Some(path.into());
     }
 
-    /// Get a reference to the memory
-    pub fn memory(&self) -> &Memory {
-        &self.memory
+    /// Write a save state to the resume path, returning true if the loop
+    /// should stop; on failure the run continues so no progress is lost
+    #[cfg(feature = "save-state")]
+    fn quit_and_save(&mut self) -> bool {
+        let Some(path) = self.resume_state_path.clone() else {
+            say!(self, "\nNo save state location available, not quitting");
+            self.warnings
+                .push("Quit and save failed: no save state location".to_string());
+            return false;
+        };
+        match std::fs::write(&path, self.save_state()) {
+            Ok(()) => {
+                say!(self, "\nSaved state to {}, stopping...", path.display());
+                self.halt_reason = HaltReason::UserQuit;
+                true
+            }
+            Err(e) => {
+                say!(self, "\nSaving state failed: {}", e);
+                self.warnings.push(format!("Saving state failed: {}", e));
+                false
+            }
+        }
     }
 
-    /// Get a reference to the input
-    pub fn input(&self) -> &Input {
-        &self.input
+    #[cfg(not(feature = "save-state"))]
+    fn quit_and_save(&mut self) -> bool {
+        say!(
+            self,
+            "\nSave states need joe built with the `save-state` feature"
+        );
+        false
     }
 
-    /// Reset the emulator to initial state
-    pub fn reset(&mut self) {
-        self.cpu = Cpu::new();
-        self.memory = Memory::new(self.config.write_protection);
-        self.display = Display::new();
-        self.input = Input::new();
-        self.cycles_executed = 0;
-        self.is_running.store(false, Ordering::SeqCst);
-        self.last_display_hash = 0;
-        self.last_render_time = Instant::now();
+    /// Write the display to `joe-screenshot-<cycle>.png` in the working directory
+    #[cfg(feature = "image")]
+    fn save_screenshot(&mut self, color: (u8, u8, u8)) {
+        let path = format!("joe-screenshot-{}.png", self.cycles_executed);
+        match self.display.to_png(&path, SCREENSHOT_SCALE, color) {
+            Ok(()) => say!(self, "\nSaved screenshot to {}", path),
+            Err(e) => {
+                say!(self, "\nScreenshot failed: {}", e);
+                self.warnings.push(format!("Screenshot failed: {}", e));
+            }
+        }
     }
 
-    /// Show final statistics and display state
-    fn show_final_statistics(&self) {
-        println!(
-            "\nEmulation completed after {} cycles",
-            self.cycles_executed
+    #[cfg(not(feature = "image"))]
+    fn save_screenshot(&mut self, _color: (u8, u8, u8)) {
+        say!(
+            self,
+            "\nScreenshots need joe built with the `image` feature"
         );
+    }
 
-        // Final display is already visible above from continuous updates
-
-        // Show statistics
-        let stats = self.display.get_stats();
-        println!("\nStatistics:");
-        println!("  Cycles executed: {}", self.cycles_executed);
-        println!(
-            "  Display pixels on: {}/{} ({}%)",
-            stats.pixels_on,
-            stats.pixels_total,
-            if stats.pixels_total > 0 {
-                (stats.pixels_on * 100) / stats.pixels_total
-            } else {
-                0
-            }
-        );
+    /// Pause or resume execution; while paused the CPU and timers are frozen
+    pub fn toggle_pause(&mut self) {
+        self.paused = !self.paused;
+        if self.paused {
+            self.silence_audio();
+        }
+    }
 
-        println!("  Final CPU state:");
-        println!("    PC: 0x{:04X}", self.cpu.get_pc());
-        println!("    I:  0x{:04X}", self.cpu.get_index());
+    /// Check if execution is paused
+    pub fn is_paused(&self) -> bool {
+        self.paused
+    }
 
-        // Show a few registers
-        for i in 0..4 {
-            if let Ok(value) = self.cpu.get_register(i) {
-                if value != 0 {
-                    println!("    V{}: 0x{:02X}", i, value);
-                }
-            }
+    /// Execute a single cycle unless paused, returning whether a cycle ran
+    pub fn step_if_running(&mut self) -> Result<bool, EmulatorError> {
+        if self.paused {
+            return Ok(false);
         }
+        self.step()?;
+        Ok(true)
+    }
 
-        if self.cpu.get_delay_timer() > 0 {
-            println!("    Delay Timer: {}", self.cpu.get_delay_timer());
+    /// Execute a single cycle without the full emulation loop
+    pub fn step(&mut self) -> Result<(), EmulatorError> {
+        self.input.update();
+        self.apply_auto_input(self.cycles_executed)?;
+        self.replay_and_record_input(self.cycles_executed)?;
+        self.push_rewind();
+        self.record_opcode();
+        let returning = self.about_to_return();
+        if let Err(e) = self
+            .cpu
+            .execute_cycle(&mut self.memory, &mut self.display, &mut self.input)
+        {
+            self.capture_fault_trace();
+            return Err(e.into());
         }
-        if self.cpu.get_sound_timer() > 0 {
-            println!("    Sound Timer: {}", self.cpu.get_sound_timer());
+        if returning {
+            self.check_return_target();
         }
+        self.cycles_executed += 1;
+        self.update_audio();
+        Ok(())
+    }
 
-        println!("\nROM execution complete!");
+    /// Stop execution whenever the CPU is about to run the instruction at `addr`
+    pub fn add_breakpoint(&mut self, addr: u16) {
+        self.cpu.add_breakpoint(addr);
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// Remove a breakpoint, returning whether it was set
+    pub fn remove_breakpoint(&mut self, addr: u16) -> bool {
+        self.cpu.remove_breakpoint(addr)
+    }
 
-    #[test]
-    fn test_emulator_creation() {
-        let emulator = Emulator::with_defaults();
-        let stats = emulator.get_stats();
+    /// Step until PC reaches a breakpoint or `max_cycles` cycles have run
+    ///
+    /// Like [`Cpu::step_until_break`], but each cycle goes through
+    /// [`Emulator::step`] so input, replay and statistics stay in sync.
+    pub fn step_until_break(&mut self, max_cycles: usize) -> Result<BreakReason, EmulatorError> {
+        for _ in 0..max_cycles {
+            self.step()?;
+            if let Some(addr) = self.cpu.breakpoint_hit() {
+                return Ok(BreakReason::Breakpoint { addr });
+            }
+        }
+        Ok(BreakReason::CycleLimit)
+    }
 
-        assert_eq!(stats.cycles_executed, 0);
-        assert_eq!(stats.program_counter, 0x200); // Default PC
-        assert!(!stats.is_running);
+    /// Execute exactly `n` CPU cycles, first advancing the timers by the
+    /// clock time since the previous call
+    ///
+    /// Meant for embedders driving their own render loop: call it once per
+    /// frame and draw [`Emulator::frame_buffer`] afterward.
+    pub fn run_cycles(&mut self, n: usize) -> Result<(), EmulatorError> {
+        let now = self.clock.now();
+        let last = self.last_run_cycles.replace(now).unwrap_or(now);
+        self.advance_timers(now - last);
+        for _ in 0..n {
+            self.step()?;
+        }
+        Ok(())
     }
 
-    #[test]
-    fn test_emulator_config() {
-        let config = EmulatorConfig {
+    /// Run one timer tick's worth of CPU cycles at `cpu_hz`, then tick the timers once
+    ///
+    /// Non-integer ratios are carried between calls (500Hz over 60Hz timers
+    /// runs 8, 8, 9, ... cycles), so the cycle count never drifts from
+    /// `cpu_hz` per second of ticks. Driven by call count, not the clock.
+    pub fn run_tick(&mut self) -> Result<(), EmulatorError> {
+        for _ in 0..self.tick_scheduler.next_tick_cycles() {
+            self.step()?;
+        }
+        self.tick_timers();
+        Ok(())
+    }
+
+    /// Run headless for `duration` of wall-clock time, then return the run's statistics
+    ///
+    /// Cycles run at `cpu_hz` in 1/`timer_hz` ticks as with
+    /// [`Emulator::run_tick`], sleeping between ticks to keep pace, and the
+    /// frame callback gets the display once per tick. Time is measured with
+    /// the system clock, not [`Emulator::set_clock`], so a mock clock can't
+    /// stall the deadline. Returns early when the running flag is cleared,
+    /// as [`Emulator::stop`], [`Emulator::stop_handle`] and Ctrl+C do.
+    ///
+    /// Like the run loop, it stops at breakpoints and at
+    /// [`EmulatorConfig::max_cycles`], and handles CPU errors by
+    /// [`EmulatorConfig::on_cpu_error`]: only `Abort` returns the error.
+    pub fn run_for(&mut self, duration: Duration) -> Result<EmulatorStats, EmulatorError> {
+        self.start_checked_run();
+        let started = Instant::now();
+        let deadline = started + duration;
+        let tick = self.tick_scheduler.tick_duration();
+        let mut next_tick = started;
+        let result = loop {
+            if !self.is_running.load(Ordering::SeqCst) {
+                self.halt_reason = HaltReason::Interrupted;
+                break Ok(());
+            }
+            let now = Instant::now();
+            if now >= deadline {
+                break Ok(());
+            }
+            if now < next_tick {
+                std::thread::sleep(next_tick.min(deadline) - now);
+                continue;
+            }
+            if let Some(outcome) = self.run_checked_tick() {
+                break outcome;
+            }
+            if let Some(callback) = self.frame_callback.as_mut() {
+                callback(&self.display);
+            }
+            next_tick += tick;
+        };
+
+        self.finish_checked_run(started.elapsed());
+        result.map(|()| self.get_stats())
+    }
+
+    /// Run headless for `frames` ticks of 1/`timer_hz`, as fast as the host
+    /// allows, then return the run's statistics
+    ///
+    /// Each frame is one [`Emulator::run_tick`] followed by the frame
+    /// callback, as in [`Emulator::run_for`], but nothing sleeps and no clock
+    /// is read, so a ROM always executes the same cycles for the same frame
+    /// count. Stops early and handles CPU errors like `run_for`.
+    pub fn run_frames(&mut self, frames: usize) -> Result<EmulatorStats, EmulatorError> {
+        self.start_checked_run();
+        let started = Instant::now();
+        let mut result = Ok(());
+        for _ in 0..frames {
+            if !self.is_running.load(Ordering::SeqCst) {
+                self.halt_reason = HaltReason::Interrupted;
+                break;
+            }
+            if let Some(outcome) = self.run_checked_tick() {
+                result = outcome;
+                break;
+            }
+            if let Some(callback) = self.frame_callback.as_mut() {
+                callback(&self.display);
+            }
+        }
+
+        self.finish_checked_run(started.elapsed());
+        result.map(|()| self.get_stats())
+    }
+
+    /// Start a [`Emulator::run_for`] or [`Emulator::run_frames`] run
+    fn start_checked_run(&mut self) {
+        self.is_running.store(true, Ordering::SeqCst);
+        self.cycles_executed = 0;
+        self.warm_resets = 0;
+        self.halt_reason = HaltReason::NotHalted;
+        self.install_ctrlc_handler();
+    }
+
+    /// End a [`Emulator::run_for`] or [`Emulator::run_frames`] run that took `duration`
+    fn finish_checked_run(&mut self, duration: Duration) {
+        self.is_running.store(false, Ordering::SeqCst);
+        self.silence_audio();
+        self.run_duration = duration;
+        self.run_cycle_count = self.cycles_executed;
+    }
+
+    /// One [`Emulator::run_tick`] for [`Emulator::run_for`] and
+    /// [`Emulator::run_frames`], returning the run's outcome once a breakpoint, the cycle limit or a CPU error ends it
+    fn run_checked_tick(&mut self) -> Option<Result<(), EmulatorError>> {
+        for _ in 0..self.tick_scheduler.next_tick_cycles() {
+            if let Err(e) = self.step() {
+                match self.config.on_cpu_error {
+                    ErrorPolicy::Abort => {
+                        self.halt_reason = HaltReason::Error {
+                            message: e.to_string(),
+                        };
+                        return Some(Err(e));
+                    }
+                    ErrorPolicy::WarmReset => {
+                        self.warnings.push(format!(
+                            "Warm reset after CPU error at cycle {}: {}",
+                            self.cycles_executed + 1,
+                            e
+                        ));
+                        self.warm_reset();
+                        self.warm_resets += 1;
+                    }
+                    ErrorPolicy::Stop => {
+                        self.halt_reason = HaltReason::Error {
+                            message: e.to_string(),
+                        };
+                        return Some(Ok(()));
+                    }
+                }
+            }
+            if let Some(addr) = self.cpu.breakpoint_hit() {
+                self.halt_reason = HaltReason::Breakpoint { addr };
+                return Some(Ok(()));
+            }
+            if self.config.max_cycles > 0 && self.cycles_executed >= self.config.max_cycles {
+                self.halt_reason = HaltReason::MaxCycles;
+                return Some(Ok(()));
+            }
+        }
+        self.tick_timers();
+        None
+    }
+
+    /// Get the framebuffer: `frame_buffer()[row][col]` is true when lit
+    pub fn frame_buffer(&self) -> &[[bool; DISPLAY_WIDTH]; DISPLAY_HEIGHT] {
+        self.display.pixels()
+    }
+
+    /// Register a callback that receives the display once per 60Hz frame of
+    /// the run loop, in both terminal and headless mode
+    ///
+    /// A lighter alternative to a full renderer when handing frames to a
+    /// windowing library. Replaces any previously registered callback.
+    pub fn on_frame(&mut self, callback: FrameCallback) {
+        self.frame_callback = Some(callback);
+    }
+
+    /// Register a callback run for every instruction, after it is decoded and
+    /// just before it executes
+    ///
+    /// The hook only sees copies of the PC and opcode, so it can build
+    /// coverage or profiling data but cannot change CPU state. Replaces any
+    /// previously registered hook.
+    pub fn set_trace_hook(&mut self, hook: TraceHook) {
+        self.trace_hook = Some(hook);
+    }
+
+    /// Hand the display to the frame callback if a frame is due at `now`
+    fn emit_frame(&mut self, frames: &mut FrameLimiter, now: Instant) {
+        if let Some(callback) = self.frame_callback.as_mut()
+            && frames.should_render(now)
+        {
+            callback(&self.display);
+        }
+    }
+
+    /// Start recording keypad input (and the RND seed) for later replay
+    pub fn attach_recorder(&mut self) {
+        self.recorder = Some(InputRecorder::new(self.cpu.get_seed()));
+    }
+
+    /// Stop recording and return the input log, if a recorder was attached
+    pub fn take_recording(&mut self) -> Option<InputLog> {
+        self.recorder.take().map(InputRecorder::into_log)
+    }
+
+    /// Replay a recorded input log at its cycle counts
+    ///
+    /// Attach before running: the CPU restarts with the log's RND seed so the
+    /// replay matches the recorded run. That holds when both runs go through
+    /// [`Emulator::step`]; see [`crate::replay`] for why clock-driven timers
+    /// can make a looped run diverge.
+    pub fn attach_player(&mut self, log: InputLog) {
+        let player = InputPlayer::new(log);
+        self.cpu = self.fresh_cpu(Some(player.seed()));
+        self.player = Some(player);
+    }
+
+    /// Send status output (startup banner, per-cycle lines, final statistics)
+    /// somewhere other than stdout
+    pub fn set_output(&mut self, output: Box<dyn Write>) {
+        self.output = output;
+    }
+
+    /// Replace the clock that drives the 60Hz timers and render pacing
+    pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+        self.clock = clock;
+    }
+
+    /// Replace the audio output driven by the sound timer
+    pub fn set_audio_output(&mut self, audio: Box<dyn AudioOutput>) {
+        self.silence_audio();
+        self.audio = audio;
+    }
+
+    /// Start the beep when the sound timer becomes active, and stop it once
+    /// the timer has run out and the beep has lasted its effective duration
+    ///
+    /// The duration is counted in timer ticks, so the hold follows the
+    /// timers however they are driven.
+    fn update_audio(&mut self) {
+        let should_beep = self.cpu.should_beep();
+        if should_beep && !self.beeping {
+            let min_duration = Duration::from_millis(self.config.min_beep_ms);
+            let duration = effective_beep_duration(self.cpu.get_sound_timer(), min_duration);
+            let tick = self.tick_scheduler.tick_duration();
+            self.beep_hold_ticks = duration.as_nanos().div_ceil(tick.as_nanos()) as u32;
+            self.audio.start_beep();
+            self.beeping = true;
+        } else if !should_beep && self.beeping && self.beep_hold_ticks == 0 {
+            self.audio.stop_beep();
+            self.beeping = false;
+        }
+    }
+
+    /// Stop any beep in progress
+    fn silence_audio(&mut self) {
+        if self.beeping {
+            self.audio.stop_beep();
+            self.beeping = false;
+        }
+    }
+
+    /// Advance the delay and sound timers by `elapsed` time, ticking once per 1/60s
+    pub fn advance_timers(&mut self, elapsed: Duration) {
+        let tick = self.tick_scheduler.tick_duration();
+        self.timer_accumulator += elapsed;
+        while self.timer_accumulator >= tick {
+            self.cpu.update_timers();
+            self.beep_hold_ticks = self.beep_hold_ticks.saturating_sub(1);
+            self.timer_accumulator -= tick;
+        }
+        self.update_audio();
+    }
+
+    /// Tick the delay and sound timers once (one 1/60s step) without running any cycles
+    ///
+    /// Leaves the partial-tick carry of `advance_timers` alone; the beeper
+    /// follows the new sound timer value.
+    pub fn tick_timers(&mut self) {
+        self.cpu.update_timers();
+        self.beep_hold_ticks = self.beep_hold_ticks.saturating_sub(1);
+        self.update_audio();
+    }
+
+    /// Get current emulator statistics
+    pub fn get_stats(&self) -> EmulatorStats {
+        EmulatorStats {
+            cycles_executed: self.cycles_executed,
+            program_counter: self.cpu.get_pc(),
+            index_register: self.cpu.get_index(),
+            display_stats: self.display.get_stats(),
+            is_running: self.is_running.load(Ordering::SeqCst),
+            effective_ips: effective_ips(self.run_cycle_count, self.run_duration),
+            run_duration: self.run_duration,
+        }
+    }
+
+    /// Build a machine-readable report of the current run
+    pub fn run_report(&self) -> RunReport {
+        let mut warnings = self.warnings.clone();
+        if let CpuState::WaitingForKey { vx } | CpuState::WaitingForRelease { vx, .. } =
+            self.cpu.get_state()
+        {
+            warnings.push(format!(
+                "CPU halted while waiting for a key press (V{:X})",
+                vx
+            ));
+        }
+        warnings.extend(self.stack_balance_warning());
+
+        RunReport {
+            cycles_executed: self.cycles_executed,
+            halt_reason: self.halt_reason.clone(),
+            program_counter: self.cpu.get_pc(),
+            index_register: self.cpu.get_index(),
+            pixels_on: self.display.get_stats().pixels_on,
+            opcode_histogram: self
+                .opcode_histogram
+                .iter()
+                .map(|(&pattern, &count)| (pattern.to_string(), count))
+                .collect(),
+            warnings,
+            warm_resets: self.warm_resets,
+            rng_seed: self.cpu.get_seed(),
+            fault_trace: self.fault_trace.clone(),
+        }
+    }
+
+    /// Get the seed used for the RND instruction
+    pub fn get_seed(&self) -> u64 {
+        self.cpu.get_seed()
+    }
+
+    /// Replace the source of RND bytes, e.g. with a scripted [`FileSequence`]
+    ///
+    /// The source outlives resets and reloads, each of which restarts it
+    /// from where it was when handed in, just as a reset replays a seeded
+    /// generator's stream. Loading a save state keeps its current position.
+    ///
+    /// [`FileSequence`]: crate::random::FileSequence
+    pub fn set_random_source(&mut self, source: Box<dyn RandomSource>) {
+        self.cpu.set_random_source(source.clone_box());
+        self.random_source = Some(source);
+    }
+
+    /// Build a CPU from the config, seeded with `seed` unless a random source was injected
+    fn fresh_cpu(&self, seed: Option<u64>) -> Cpu {
+        let mut cpu = new_cpu(&self.config, seed);
+        if let Some(source) = &self.random_source {
+            cpu.set_random_source(source.clone_box());
+        }
+        cpu
+    }
+
+    /// Release keys tapped on the previous cycle and press those scheduled for `cycle`
+    fn apply_auto_input(&mut self, cycle: usize) -> Result<(), EmulatorError> {
+        for key in self.auto_input_pressed.drain(..) {
+            self.input.release_key(key)?;
+        }
+
+        for &(at_cycle, key) in &self.config.auto_input {
+            if at_cycle == cycle {
+                self.input.press_key(key)?;
+                self.auto_input_pressed.push(key);
+            }
+        }
+        Ok(())
+    }
+
+    /// Apply played-back key events due before `cycle`, then log keypad changes
+    fn replay_and_record_input(&mut self, cycle: usize) -> Result<(), EmulatorError> {
+        if let Some(player) = self.player.as_mut() {
+            for (key, pressed) in player.events_for(cycle) {
+                if pressed {
+                    self.input.press_key(key)?;
+                } else {
+                    self.input.release_key(key)?;
+                }
+            }
+        }
+        if let Some(recorder) = self.recorder.as_mut() {
+            recorder.observe(cycle, &self.input);
+        }
+        Ok(())
+    }
+
+    /// Whether stack balance is tracked and the instruction about to execute is a RET
+    fn about_to_return(&self) -> bool {
+        self.config.track_stack_balance
+            && *self.cpu.get_state() == CpuState::Running
+            && !self.cpu.will_break()
+            && self
+                .memory
+                .read_word(self.cpu.get_pc())
+                .is_ok_and(|opcode| opcode == 0x00EE)
+    }
+
+    /// Warn (once per address) when a RET just landed outside the loaded ROM
+    fn check_return_target(&mut self) {
+        let pc = self.cpu.get_pc();
+        let rom = PROGRAM_START_ADDR..PROGRAM_START_ADDR + self.memory.rom_len() as u16;
+        if rom.contains(&pc) {
+            return;
+        }
+        let warning = format!(
+            "RET returned to {:#06x}, outside the ROM ({:#06x}-{:#06x})",
+            pc,
+            rom.start,
+            rom.end.saturating_sub(1)
+        );
+        if !self.warnings.contains(&warning) {
+            self.warnings.push(warning);
+        }
+    }
+
+    /// Warning for CALLs left unreturned, when stack balance is tracked
+    fn stack_balance_warning(&self) -> Option<String> {
+        let sp = self.cpu.get_sp();
+        (self.config.track_stack_balance && sp != 0).then(|| {
+            format!(
+                "Run ended with {} unreturned CALL{} on the stack (SP={})",
+                sp,
+                if sp == 1 { "" } else { "s" },
+                sp
+            )
+        })
+    }
+
+    /// Count the instruction about to execute in the opcode histogram and
+    /// remember it in the recent-instruction trace
+    fn record_opcode(&mut self) {
+        if *self.cpu.get_state() != CpuState::Running || self.cpu.will_break() {
+            return;
+        }
+
+        let pc = self.cpu.get_pc();
+        let Ok(opcode) = self.memory.read_word(pc) else {
+            return;
+        };
+        let instruction = match decode_opcode(opcode) {
+            Ok(instruction) => instruction,
+            Err(_) if self.config.lenient_decode => {
+                let Ok(instruction) = decode_opcode_lenient(opcode) else {
+                    self.remember_instruction(pc, opcode, None);
+                    return;
+                };
+                let warning = format!(
+                    "Lenient decode: {:#06x} at {:#06x} executed as {}",
+                    opcode,
+                    pc,
+                    instruction.mnemonic()
+                );
+                if !self.warnings.contains(&warning) {
+                    self.warnings.push(warning);
+                }
+                instruction
+            }
+            Err(_) => {
+                self.remember_instruction(pc, opcode, None);
+                return;
+            }
+        };
+        self.remember_instruction(pc, opcode, Some(instruction.clone()));
+        if let Some(hook) = self.trace_hook.as_mut() {
+            hook(pc, opcode, &instruction);
+        }
+        *self
+            .opcode_histogram
+            .entry(instruction.pattern())
+            .or_insert(0) += 1;
+    }
+
+    /// Push an instruction onto the crash-diagnostics ring buffer
+    fn remember_instruction(&mut self, pc: u16, opcode: u16, instruction: Option<Instruction>) {
+        if self.config.trace_depth == 0 {
+            return;
+        }
+        if self.recent_instructions.len() >= self.config.trace_depth {
+            self.recent_instructions.pop_front();
+        }
+        self.recent_instructions
+            .push_back((pc, opcode, instruction));
+    }
+
+    /// Freeze the recent instructions as the trace of the fault that just happened
+    fn capture_fault_trace(&mut self) {
+        self.fault_trace = self
+            .recent_instructions
+            .iter()
+            .map(|(pc, opcode, instruction)| TraceEntry {
+                pc: *pc,
+                opcode: *opcode,
+                mnemonic: instruction
+                    .as_ref()
+                    .map_or_else(|| format!("DW {:04X}", opcode), Instruction::mnemonic),
+            })
+            .collect();
+    }
+
+    /// Stop the emulation loop
+    pub fn stop(&self) {
+        self.is_running.store(false, Ordering::SeqCst);
+    }
+
+    /// The running flag, for stopping a run from another thread
+    ///
+    /// Storing false has the same effect as [`Emulator::stop`]; the loop
+    /// notices it before the next cycle.
+    pub fn stop_handle(&self) -> Arc<AtomicBool> {
+        self.is_running.clone()
+    }
+
+    /// Get a reference to the display
+    pub fn display(&self) -> &Display {
+        &self.display
+    }
+
+    /// Get a reference to the CPU
+    pub fn cpu(&self) -> &Cpu {
+        &self.cpu
+    }
+
+    /// Get a reference to the memory
+    pub fn memory(&self) -> &Memory {
+        &self.memory
+    }
+
+    /// Get a reference to the input
+    pub fn input(&self) -> &Input {
+        &self.input
+    }
+
+    /// Attach a button input source (like a gamepad) with its button mappings
+    ///
+    /// [`Emulator::run`] keeps the source but maps its buttons by the
+    /// `[input.button_mappings]` table of the user config instead.
+    pub fn set_input_source(
+        &mut self,
+        input_source: Box<dyn InputSource>,
+        button_mappings: ButtonMappings,
+    ) {
+        self.input.set_input_source(input_source, button_mappings);
+    }
+
+    /// Snapshot the machine: CPU registers, timers and stack, all 4KB of RAM
+    /// and the framebuffer
+    #[cfg(feature = "save-state")]
+    pub fn save_state(&self) -> Vec<u8> {
+        let state = (SAVE_STATE_VERSION, &self.cpu, &self.memory, &self.display);
+        bincode::serde::encode_to_vec(state, bincode::config::standard())
+            .expect("machine state always serializes")
+    }
+
+    /// Restore a snapshot taken with [`Emulator::save_state`]
+    ///
+    /// Input, audio and run statistics are left alone. The random source is
+    /// not part of the snapshot, so RND keeps drawing from the current one.
+    /// Quirks, sprite clipping and write protection follow this emulator's
+    /// config, not the one the snapshot was taken under.
+    #[cfg(feature = "save-state")]
+    pub fn load_state(&mut self, data: &[u8]) -> Result<(), EmulatorError> {
+        let ((version, cpu, memory, display), _): ((u32, Cpu, Memory, Display), usize) =
+            bincode::serde::decode_from_slice(data, bincode::config::standard()).map_err(|e| {
+                EmulatorError::InvalidSaveState {
+                    message: e.to_string(),
+                }
+            })?;
+        if version != SAVE_STATE_VERSION {
+            return Err(EmulatorError::InvalidSaveState {
+                message: format!(
+                    "unsupported version {} (expected {})",
+                    version, SAVE_STATE_VERSION
+                ),
+            });
+        }
+
+        check_restored_cpu(&cpu)?;
+
+        // Breakpoints, write tracking, the random source and the configured
+        // quirks belong to the session, not the snapshot
+        let mut cpu = cpu;
+        configure_cpu(&mut cpu, &self.config);
+        cpu.set_random_source(self.cpu.clone_random_source());
+        for &addr in self.cpu.breakpoints() {
+            cpu.add_breakpoint(addr);
+        }
+        let mut memory = memory;
+        memory.set_write_protection(self.config.write_protection);
+        memory.set_write_tracking(self.config.track_ram_writes);
+        let mut display = display;
+        display.set_clip_sprites(self.config.clip_sprites);
+
+        self.cpu = cpu;
+        self.memory = memory;
+        self.display = display;
+        self.timer_accumulator = Duration::ZERO;
+        self.last_display_hash = 0;
+        self.update_audio();
+        Ok(())
+    }
+
+    /// Undo the last cycle by restoring the newest rewind snapshot
+    ///
+    /// Needs [`EmulatorConfig::rewind_depth`] above 0. Returns false once the
+    /// buffer is exhausted. Restores like [`Emulator::load_state`], except
+    /// that the cycle count and the random source step back too.
+    #[cfg(feature = "save-state")]
+    pub fn step_back(&mut self) -> Result<bool, EmulatorError> {
+        let Some((state, random_source)) = self.rewind.pop_back() else {
+            return Ok(false);
+        };
+        self.load_state(&state)?;
+        // Replaying the undone cycles draws the same RND bytes again
+        self.cpu.set_random_source(random_source);
+        self.cycles_executed = self.cycles_executed.saturating_sub(1);
+        Ok(true)
+    }
+
+    /// Snapshot the machine into the rewind buffer before a cycle runs
+    fn push_rewind(&mut self) {
+        #[cfg(feature = "save-state")]
+        if self.config.rewind_depth > 0 {
+            if self.rewind.len() >= self.config.rewind_depth {
+                self.rewind.pop_front();
+            }
+            self.rewind
+                .push_back((self.save_state(), self.cpu.clone_random_source()));
+        }
+    }
+
+    /// Replace the CPU with a fresh one, carrying over what survives a reset
+    fn reset_cpu(&mut self) {
+        // Keep the seed so a reset replays the same RND stream
+        let rpl_flags = self.cpu.rpl_flags();
+        let mut cpu = self.fresh_cpu(Some(self.cpu.get_seed()));
+        for &addr in self.cpu.breakpoints() {
+            cpu.add_breakpoint(addr);
+        }
+        self.cpu = cpu;
+        if self.config.keep_rpl_flags {
+            self.cpu.set_rpl_flags(rpl_flags);
+        }
+    }
+
+    /// Restart the loaded program: reset the CPU and display but keep memory
+    ///
+    /// Unlike [`Emulator::reset`], the ROM stays loaded, input keeps its
+    /// backend and run statistics are kept.
+    pub fn warm_reset(&mut self) {
+        self.reset_cpu();
+        self.display = new_display(&self.config);
+        self.timer_accumulator = Duration::ZERO;
+        self.silence_audio();
+    }
+
+    /// Reset the emulator to initial state
+    pub fn reset(&mut self) {
+        self.reset_cpu();
+        self.memory = new_memory(&self.config);
+        self.display = new_display(&self.config);
+        self.input.reset();
+        self.cycles_executed = 0;
+        self.run_duration = Duration::ZERO;
+        self.run_cycle_count = 0;
+        self.is_running.store(false, Ordering::SeqCst);
+        self.last_display_hash = 0;
+        self.last_render_time = Instant::now();
+        self.halt_reason = HaltReason::NotHalted;
+        self.opcode_histogram.clear();
+        self.warnings.clear();
+        self.warm_resets = 0;
+        self.auto_input_pressed.clear();
+        self.timer_accumulator = Duration::ZERO;
+        self.tick_scheduler = TickScheduler::new(self.config.cpu_hz, self.config.timer_hz);
+        self.last_run_cycles = None;
+        self.silence_audio();
+        self.paused = false;
+        #[cfg(feature = "save-state")]
+        self.rewind.clear();
+        self.recent_instructions.clear();
+        self.fault_trace.clear();
+    }
+
+    /// Show final statistics and display state
+    fn show_final_statistics(&mut self) {
+        say!(
+            self,
+            "\nEmulation completed after {} cycles",
+            self.cycles_executed
+        );
+
+        // Final display is already visible above from continuous updates
+
+        // Show statistics
+        let stats = self.display.get_stats();
+        say!(self, "\nStatistics:");
+        say!(self, "  Cycles executed: {}", self.cycles_executed);
+        say!(
+            self,
+            "  Effective speed: {:.0} instructions/s over {:.2}s",
+            effective_ips(self.run_cycle_count, self.run_duration),
+            self.run_duration.as_secs_f64()
+        );
+        say!(
+            self,
+            "  Display pixels on: {}/{} ({}%)",
+            stats.pixels_on,
+            stats.pixels_total,
+            (stats.pixels_on * 100)
+                .checked_div(stats.pixels_total)
+                .unwrap_or(0)
+        );
+
+        say!(self, "  Final CPU state:");
+        say!(self, "    PC: 0x{:04X}", self.cpu.get_pc());
+        say!(self, "    I:  0x{:04X}", self.cpu.get_index());
+
+        // Show a few registers
+        for i in 0..4 {
+            if let Ok(value) = self.cpu.get_register(i)
+                && value != 0
+            {
+                say!(self, "    V{}: 0x{:02X}", i, value);
+            }
+        }
+
+        if self.cpu.get_delay_timer() > 0 {
+            say!(self, "    Delay Timer: {}", self.cpu.get_delay_timer());
+        }
+        if self.cpu.get_sound_timer() > 0 {
+            say!(self, "    Sound Timer: {}", self.cpu.get_sound_timer());
+        }
+
+        if let Some(warning) = self.stack_balance_warning() {
+            say!(self, "\nWarning: {}", warning);
+        }
+
+        say!(self, "\nROM execution complete!");
+    }
+}
+
+/// Instructions per second achieved by running `cycles` in `duration` (0 for an empty run)
+fn effective_ips(cycles: usize, duration: Duration) -> f64 {
+    if duration.is_zero() {
+        0.0
+    } else {
+        cycles as f64 / duration.as_secs_f64()
+    }
+}
+
+/// Create a CPU with the configured quirks, seeded from `seed` or system entropy
+fn new_cpu(config: &EmulatorConfig, seed: Option<u64>) -> Cpu {
+    let mut cpu = seed.map_or_else(Cpu::new, Cpu::with_seed);
+    configure_cpu(&mut cpu, config);
+    cpu
+}
+
+/// Apply the configured quirks and decode mode to a CPU
+fn configure_cpu(cpu: &mut Cpu, config: &EmulatorConfig) {
+    cpu.set_reset_vf_on_logic(config.reset_vf_on_logic);
+    cpu.set_index_increment(config.index_increment);
+    cpu.set_lenient_decode(config.lenient_decode);
+    cpu.set_wait_key_on_release(config.wait_key_on_release);
+    cpu.set_display_wait(config.display_wait);
+    cpu.set_add_index_sets_vf(config.add_index_sets_vf);
+    cpu.set_shift_uses_vy(config.shift_uses_vy);
+    cpu.set_jump_uses_vx(config.jump_uses_vx);
+    cpu.set_keep_rpl_flags(config.keep_rpl_flags);
+}
+
+/// Reject a decoded save-state CPU whose registers would index out of bounds
+#[cfg(feature = "save-state")]
+fn check_restored_cpu(cpu: &Cpu) -> Result<(), EmulatorError> {
+    let invalid = |message: String| Err(EmulatorError::InvalidSaveState { message });
+    if cpu.get_sp() as usize > crate::constants::STACK_SIZE {
+        return invalid(format!(
+            "stack pointer {} exceeds stack depth",
+            cpu.get_sp()
+        ));
+    }
+    if cpu.get_pc() as usize >= crate::constants::MEMORY_SIZE - 1 {
+        return invalid(format!(
+            "program counter 0x{:04X} out of bounds",
+            cpu.get_pc()
+        ));
+    }
+    if cpu.get_index() as usize >= crate::constants::MEMORY_SIZE {
+        return invalid(format!(
+            "index register 0x{:04X} out of bounds",
+            cpu.get_index()
+        ));
+    }
+    match *cpu.get_state() {
+        CpuState::WaitingForKey { vx } | CpuState::WaitingForRelease { vx, .. } if vx > 0xF => {
+            invalid(format!("key wait targets invalid register V{}", vx))
+        }
+        CpuState::WaitingForRelease { key, .. } if key > 0xF => {
+            invalid(format!("key wait holds invalid key {}", key))
+        }
+        _ => Ok(()),
+    }
+}
+
+/// Create a blank display with the configured sprite edge behavior
+fn new_display(config: &EmulatorConfig) -> Display {
+    let mut display = Display::new();
+    display.set_clip_sprites(config.clip_sprites);
+    display
+}
+
+/// Create memory with the configured write protection and font set
+fn new_memory(config: &EmulatorConfig) -> Memory {
+    let mut memory = Memory::new(config.write_protection);
+    memory.set_font(config.font);
+    memory.set_write_tracking(config.track_ram_writes);
+    memory
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_emulator_creation() {
+        let emulator = Emulator::with_defaults();
+        let stats = emulator.get_stats();
+
+        assert_eq!(stats.cycles_executed, 0);
+        assert_eq!(stats.program_counter, 0x200); // Default PC
+        assert!(!stats.is_running);
+    }
+
+    #[test]
+    fn test_emulator_config() {
+        let config = EmulatorConfig {
+            max_cycles: 100,
+            cycle_delay_ms: 10,
+            cycles_per_frame: 12,
+            cpu_hz: 700,
+            pace_by_cpu_hz: true,
+            timer_hz: 50,
+            verbose: true,
+            write_protection: false,
+            seed: Some(7),
+            auto_input: Vec::new(),
+            font: FontSet::CosmacVip,
+            reset_vf_on_logic: true,
+            index_increment: IndexIncrementMode::X,
+            on_cpu_error: ErrorPolicy::Stop,
+            lenient_decode: true,
+            wait_key_on_release: true,
+            keep_rpl_flags: false,
+            display_wait: true,
+            add_index_sets_vf: true,
+            shift_uses_vy: true,
+            jump_uses_vx: true,
+            clip_sprites: true,
+            min_beep_ms: 50,
+            quiet: true,
+            track_stack_balance: false,
+            track_ram_writes: true,
+            rewind_depth: 0,
+            trace_depth: 4,
+        };
+
+        let emulator = Emulator::new(config.clone());
+        assert_eq!(emulator.get_seed(), 7);
+        assert_eq!(emulator.run_report().rng_seed, 7);
+        assert_eq!(emulator.config.max_cycles, 100);
+        assert_eq!(emulator.config.cycle_delay_ms, 10);
+        assert_eq!(emulator.config.cycles_per_frame, 12);
+        assert_eq!(emulator.tick_scheduler, TickScheduler::new(700, 50));
+        assert!(emulator.config.verbose);
+        assert!(!emulator.config.write_protection);
+        assert_eq!(emulator.memory.get_font(), FontSet::CosmacVip);
+        assert!(emulator.cpu.is_reset_vf_on_logic_enabled());
+        assert!(emulator.cpu.is_wait_key_on_release_enabled());
+        assert!(emulator.cpu.is_display_wait_enabled());
+        assert!(emulator.cpu.is_add_index_sets_vf_enabled());
+        assert!(emulator.cpu.is_shift_uses_vy_enabled());
+        assert!(emulator.cpu.is_jump_uses_vx_enabled());
+        assert!(!emulator.cpu.is_keep_rpl_flags_enabled());
+        assert!(emulator.display.is_clip_sprites_enabled());
+        assert!(emulator.memory.is_write_tracking_enabled());
+    }
+
+    #[test]
+    fn test_display_wait_draws_once_per_tick() {
+        let draws_per_tick = |display_wait| {
+            let mut emulator = Emulator::new(EmulatorConfig {
+                display_wait,
+                ..Default::default()
+            });
+            // loop: DRW V0, V1, 1; JP loop
+            emulator.load_rom(&[0xD0, 0x11, 0x12, 0x00]).unwrap();
+            let draws = std::rc::Rc::new(std::cell::Cell::new(0));
+            let counter = std::rc::Rc::clone(&draws);
+            emulator.set_trace_hook(Box::new(move |_, _, instruction| {
+                if matches!(instruction, Instruction::Draw { .. }) {
+                    counter.set(counter.get() + 1);
+                }
+            }));
+
+            let mut counts = Vec::new();
+            for _ in 0..4 {
+                let before = draws.get();
+                emulator.run_tick().unwrap();
+                counts.push(draws.get() - before);
+            }
+            counts
+        };
+
+        assert_eq!(draws_per_tick(true), vec![1; 4]);
+        assert!(draws_per_tick(false).iter().all(|&count| count > 1));
+    }
+
+    #[test]
+    fn test_rpl_flags_survive_resets() {
+        let rom = [0x60, 0x2A, 0xF0, 0x75]; // LD V0, 2A; LD R, V0
+        let mut emulator = Emulator::with_defaults();
+        emulator.load_rom(&rom).unwrap();
+        emulator.step().unwrap();
+        emulator.step().unwrap();
+
+        emulator.warm_reset();
+        assert_eq!(emulator.cpu.rpl_flags()[0], 0x2A);
+        emulator.reset();
+        assert_eq!(emulator.cpu.rpl_flags()[0], 0x2A);
+
+        let mut emulator = Emulator::new(EmulatorConfig {
+            keep_rpl_flags: false,
+            ..Default::default()
+        });
+        emulator.load_rom(&rom).unwrap();
+        emulator.step().unwrap();
+        emulator.step().unwrap();
+        emulator.warm_reset();
+        assert_eq!(emulator.cpu.rpl_flags()[0], 0);
+    }
+
+    /// Input source that holds one button down from the first poll
+    #[derive(Debug)]
+    struct HeldButton(Option<crate::input::Button>);
+
+    impl InputSource for HeldButton {
+        fn poll(&mut self) -> Vec<crate::input::ButtonEvent> {
+            self.0
+                .take()
+                .map(crate::input::ButtonEvent::Pressed)
+                .into_iter()
+                .collect()
+        }
+    }
+
+    #[test]
+    fn test_input_settings_remap_attached_source() {
+        use crate::input::Button;
+
+        let mut emulator = Emulator::with_defaults();
+        emulator.set_input_source(
+            Box::new(HeldButton(Some(Button::Up))),
+            resolve_button_mappings(None).unwrap(),
+        );
+
+        let mut settings = crate::config::Config::default().input;
+        settings
+            .button_mappings
+            .insert("Up".to_string(), "A".to_string());
+        let (_sender, receiver) = mpsc::channel();
+        emulator.apply_input_settings(&settings, receiver).unwrap();
+
+        emulator.input.update();
+        assert!(emulator.input.is_key_pressed(0xA).unwrap());
+        assert!(!emulator.input.is_key_pressed(0x2).unwrap());
+
+        // A bad mapping is reported instead of silently ignored
+        settings
+            .button_mappings
+            .insert("Up".to_string(), "G".to_string());
+        let (_sender, receiver) = mpsc::channel();
+        assert!(emulator.apply_input_settings(&settings, receiver).is_err());
+    }
+
+    #[test]
+    fn test_reset_keeps_key_mappings() {
+        use crate::input::KeyMappings;
+
+        let (sender, receiver) = mpsc::channel();
+        let mappings = KeyMappings::from_pairs(&[('m', 0x7)]).unwrap();
+        let mut emulator = Emulator::with_defaults();
+        emulator.input = Input::with_mappings(mappings, Some(receiver));
+        emulator.input.press_key(0x2).unwrap();
+
+        emulator.reset();
+        assert!(emulator.input().get_pressed_keys().is_empty());
+        assert_eq!(emulator.input().get_chip8_key('m'), Some(0x7));
+        assert_eq!(emulator.input().get_chip8_key('1'), None);
+
+        // The renderer's key channel is still connected
+        sender.send(KeyEvent::Pressed('m')).unwrap();
+        emulator.input.update();
+        assert_eq!(emulator.input().get_pressed_keys(), vec![0x7]);
+    }
+
+    #[test]
+    fn test_quirks_survive_resets() {
+        let quirks = QuirkSettings {
+            reset_vf_on_logic: true,
+            clip_sprites: true,
+            ..Default::default()
+        };
+        let mut emulator = Emulator::with_config_and_quirks(EmulatorConfig::default(), quirks);
+        assert_eq!(emulator.config.quirks(), quirks);
+
+        // LD VF, 1; OR V0, V1
+        let rom = [0x6F, 0x01, 0x80, 0x11];
+        for reset in [Emulator::reset, Emulator::warm_reset] {
+            reset(&mut emulator);
+            emulator.load_rom(&rom).unwrap();
+            emulator.step().unwrap();
+            emulator.step().unwrap();
+            assert_eq!(emulator.cpu.get_register(0xF).unwrap(), 0);
+            assert!(emulator.display.is_clip_sprites_enabled());
+        }
+    }
+
+    #[test]
+    fn test_quirk_profile_flag_matrix() {
+        use IndexIncrementMode::{Unchanged, XPlusOne};
+
+        // (profile, index increment,
+        //  [vf reset, key release, display wait, clip, shift vy, jump vx])
+        let matrix = [
+            (
+                QuirkProfile::CosmacVip,
+                XPlusOne,
+                [true, true, true, true, true, false],
+            ),
+            (
+                QuirkProfile::SuperChip,
+                Unchanged,
+                [false, false, false, true, false, true],
+            ),
+            (
+                QuirkProfile::XoChip,
+                XPlusOne,
+                [false, false, false, false, false, false],
+            ),
+            (
+                QuirkProfile::Modern,
+                Unchanged,
+                [false, false, false, false, false, false],
+            ),
+        ];
+        for (profile, index_increment, flags) in matrix {
+            let quirks = profile.to_quirks();
+            assert_eq!(quirks.index_increment, index_increment, "{:?}", profile);
+            assert!(!quirks.add_index_sets_vf, "{:?}", profile);
+            assert_eq!(
+                [
+                    quirks.reset_vf_on_logic,
+                    quirks.wait_key_on_release,
+                    quirks.display_wait,
+                    quirks.clip_sprites,
+                    quirks.shift_uses_vy,
+                    quirks.jump_uses_vx,
+                ],
+                flags,
+                "{:?}",
+                profile
+            );
+        }
+        assert_eq!(
+            QuirkProfile::default().to_quirks(),
+            EmulatorConfig::default().quirks()
+        );
+    }
+
+    #[test]
+    fn test_quirk_profile_from_str() {
+        assert_eq!(
+            "cosmac-vip".parse::<QuirkProfile>().unwrap(),
+            QuirkProfile::CosmacVip
+        );
+        assert_eq!(
+            "Super_Chip".parse::<QuirkProfile>().unwrap(),
+            QuirkProfile::SuperChip
+        );
+        assert_eq!(
+            "xo-chip".parse::<QuirkProfile>().unwrap(),
+            QuirkProfile::XoChip
+        );
+        assert_eq!(
+            "modern".parse::<QuirkProfile>().unwrap(),
+            QuirkProfile::Modern
+        );
+        assert!(matches!(
+            "chip48".parse::<QuirkProfile>(),
+            Err(EmulatorError::UnknownQuirkProfile { .. })
+        ));
+    }
+
+    #[test]
+    fn test_cosmac_vip_profile_increments_index() {
+        let config = EmulatorConfig {
+            quiet: true,
+            ..Default::default()
+        };
+        let mut emulator =
+            Emulator::with_config_and_quirks(config, QuirkProfile::CosmacVip.to_quirks());
+        // LD I, 300; LD [I], V2
+        emulator.load_rom(&[0xA3, 0x00, 0xF2, 0x55]).unwrap();
+        emulator.step().unwrap();
+        emulator.step().unwrap();
+        assert_eq!(emulator.cpu.get_index(), 0x303);
+    }
+
+    #[test]
+    fn test_clip_sprites_survives_resets() {
+        let mut emulator = Emulator::new(EmulatorConfig {
+            clip_sprites: true,
+            ..Default::default()
+        });
+        // LD V0, 3C (x = 60); LD I, 300; DRW V0, V1, 1
+        emulator
+            .load_rom(&[0x60, 0x3C, 0xA3, 0x00, 0xD0, 0x11])
+            .unwrap();
+        emulator.memory.write_byte(0x300, 0xFF).unwrap();
+        for _ in 0..3 {
+            emulator.step().unwrap();
+        }
+        assert!(emulator.display.pixels()[0][63]);
+        assert!(!emulator.display.pixels()[0][0]);
+
+        emulator.warm_reset();
+        assert!(emulator.display.is_clip_sprites_enabled());
+        emulator.reset();
+        assert!(emulator.display.is_clip_sprites_enabled());
+    }
+
+    #[test]
+    fn test_emulator_reset() {
+        let mut emulator = Emulator::with_defaults();
+
+        // Execute a few steps to change state
+        let _ = emulator.step();
+        let _ = emulator.step();
+
+        // Reset should restore initial state
+        emulator.reset();
+        let stats = emulator.get_stats();
+
+        assert_eq!(stats.cycles_executed, 0);
+        assert!(!stats.is_running);
+    }
+
+    #[test]
+    fn test_run_report() {
+        let mut emulator = Emulator::with_defaults();
+
+        // LD V0, 05; LD I, 300; LD V1, 01; DRW V0, V1, 1
+        let rom_data = vec![0x60, 0x05, 0xA3, 0x00, 0x61, 0x01, 0xD0, 0x11];
+        emulator.load_rom(&rom_data).unwrap();
+        emulator.memory.write_byte(0x300, 0b11100000).unwrap();
+
+        for _ in 0..4 {
+            emulator.step().unwrap();
+        }
+
+        let report = emulator.run_report();
+        assert_eq!(report.cycles_executed, 4);
+        assert_eq!(report.halt_reason, HaltReason::NotHalted);
+        assert_eq!(report.program_counter, 0x208);
+        assert_eq!(report.index_register, 0x300);
+        assert_eq!(report.pixels_on, 3);
+        assert_eq!(report.opcode_histogram.get("6XNN"), Some(&2));
+        assert_eq!(report.opcode_histogram.get("ANNN"), Some(&1));
+        assert_eq!(report.opcode_histogram.get("DXYN"), Some(&1));
+        assert!(report.warnings.is_empty());
+
+        let json = report.to_json().unwrap();
+        assert!(json.contains("\"halt_reason\""));
+        assert!(json.contains("\"DXYN\": 1"));
+    }
+
+    #[test]
+    fn test_effective_ips() {
+        assert_eq!(effective_ips(600, Duration::from_secs(2)), 300.0);
+        assert_eq!(effective_ips(50, Duration::from_millis(100)), 500.0);
+        // No run yet: no rate rather than a division by zero
+        assert_eq!(effective_ips(0, Duration::ZERO), 0.0);
+        assert_eq!(Emulator::with_defaults().get_stats().effective_ips, 0.0);
+    }
+
+    #[test]
+    fn test_effective_ips_counts_only_the_last_run() {
+        use crate::clock::MockClock;
+
+        let mut emulator = Emulator::new(EmulatorConfig {
+            max_cycles: 10,
+            cycle_delay_ms: 0,
+            quiet: true,
+            ..Default::default()
+        });
+        emulator.set_clock(Box::new(MockClock::with_auto_advance(
+            Duration::from_millis(1),
+        )));
+        // JP 200
+        emulator.load_rom(&[0x12, 0x00]).unwrap();
+        emulator.run_headless().unwrap();
+        let after_run = emulator.get_stats().effective_ips;
+        assert!(after_run > 0.0);
+
+        // Stepping afterwards adds cycles but no run time
+        for _ in 0..100 {
+            emulator.step().unwrap();
+        }
+        assert_eq!(emulator.get_stats().cycles_executed, 110);
+        assert_eq!(emulator.get_stats().effective_ips, after_run);
+    }
+
+    #[test]
+    fn test_auto_input_fires_at_cycle() {
+        let config = EmulatorConfig {
+            auto_input: vec![(3, 0x7)],
+            ..Default::default()
+        };
+        let mut emulator = Emulator::new(config);
+
+        // LD V0, K; JP 0x202 (wait for a key, then spin)
+        emulator.load_rom(&[0xF0, 0x0A, 0x12, 0x02]).unwrap();
+
+        // Cycles 0-2: still waiting for the key
+        for _ in 0..3 {
+            emulator.step().unwrap();
+        }
+        assert!(matches!(
+            emulator.cpu.get_state(),
+            CpuState::WaitingForKey { vx: 0 }
+        ));
+        assert_eq!(emulator.cpu.get_register(0).unwrap(), 0);
+
+        // Cycle 3: the tapped key is observed by the waiting ROM
+        emulator.step().unwrap();
+        assert_eq!(*emulator.cpu.get_state(), CpuState::Running);
+        assert_eq!(emulator.cpu.get_register(0).unwrap(), 0x7);
+        assert!(emulator.input.is_key_pressed(0x7).unwrap());
+
+        // Cycle 4: the key is released again
+        emulator.step().unwrap();
+        assert!(!emulator.input.is_key_pressed(0x7).unwrap());
+    }
+
+    #[test]
+    fn test_configured_font_addressed_by_load_font() {
+        let config = EmulatorConfig {
+            font: FontSet::SuperChip,
+            ..Default::default()
+        };
+        let mut emulator = Emulator::new(config);
+
+        // LD V0, 0; LD F, V0
+        emulator.load_rom(&[0x60, 0x00, 0xF0, 0x29]).unwrap();
+        emulator.step().unwrap();
+        emulator.step().unwrap();
+
+        let glyph_addr = emulator.cpu.get_index() as usize;
+        assert_eq!(
+            emulator.memory.as_slice()[glyph_addr..glyph_addr + 5],
+            FontSet::SuperChip.glyphs()[..5]
+        );
+
+        // The font is reloaded on reset
+        emulator.reset();
+        assert_eq!(emulator.memory.get_font(), FontSet::SuperChip);
+    }
+
+    #[test]
+    fn test_lenient_decode_rescues_corrupted_key_skip() {
+        // SKP V0 with bit-rot (0x9E -> 0x9F), then ADD V1, 1
+        let rom = [0xE0, 0x9F, 0x71, 0x01];
+
+        let mut strict = Emulator::with_defaults();
+        strict.load_rom(&rom).unwrap();
+        assert!(strict.step().is_err());
+
+        let mut lenient = Emulator::new(EmulatorConfig {
+            lenient_decode: true,
+            ..Default::default()
+        });
+        lenient.load_rom(&rom).unwrap();
+        lenient.step().unwrap();
+        lenient.step().unwrap();
+
+        // No key pressed, so SKP falls through to the ADD
+        assert_eq!(lenient.cpu.get_register(1).unwrap(), 1);
+        assert_eq!(
+            lenient.run_report().warnings,
+            vec!["Lenient decode: 0xe09f at 0x0200 executed as SKP V0".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_trace_hook_sees_each_instruction() {
+        let traced = std::rc::Rc::new(std::cell::RefCell::new(Vec::new()));
+        let sink = traced.clone();
+
+        let mut emulator = Emulator::with_defaults();
+        // LD V0, 05; ADD V0, 01; JP 204
+        emulator
+            .load_rom(&[0x60, 0x05, 0x70, 0x01, 0x12, 0x04])
+            .unwrap();
+        emulator.set_trace_hook(Box::new(move |pc, opcode, instruction| {
+            sink.borrow_mut().push((pc, opcode, instruction.pattern()));
+        }));
+        for _ in 0..3 {
+            emulator.step().unwrap();
+        }
+
+        assert_eq!(
+            *traced.borrow(),
+            vec![
+                (0x200, 0x6005, "6XNN"),
+                (0x202, 0x7001, "7XNN"),
+                (0x204, 0x1204, "1NNN"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_breakpoints_stop_emulator() {
+        // LD V0, 05; ADD V0, 01; JP 202
+        let rom = [0x60, 0x05, 0x70, 0x01, 0x12, 0x02];
+
+        let mut emulator = Emulator::with_defaults();
+        emulator.load_rom(&rom).unwrap();
+        emulator.add_breakpoint(0x202);
+        assert_eq!(
+            emulator.step_until_break(10).unwrap(),
+            BreakReason::Breakpoint { addr: 0x202 }
+        );
+        assert_eq!(
+            emulator.step_until_break(10).unwrap(),
+            BreakReason::Breakpoint { addr: 0x202 }
+        );
+        assert_eq!(emulator.cpu.get_register(0).unwrap(), 0x06);
+
+        // Breakpoints belong to the session and survive a reset
+        emulator.warm_reset();
+        assert_eq!(
+            emulator.step_until_break(10).unwrap(),
+            BreakReason::Breakpoint { addr: 0x202 }
+        );
+
+        // Stopping in front of an instruction does not count it as executed
+        let histogram = emulator.run_report().opcode_histogram;
+        assert_eq!(histogram.get("7XNN"), Some(&1));
+        assert_eq!(histogram.get("1NNN"), Some(&1));
+
+        // A headless run halts at the breakpoint instead of spinning
+        let mut headless = Emulator::new(EmulatorConfig {
             max_cycles: 100,
-            cycle_delay_ms: 10,
+            cycle_delay_ms: 0,
+            ..Default::default()
+        });
+        headless.load_rom(&rom).unwrap();
+        headless.add_breakpoint(0x204);
+        headless.run_headless().unwrap();
+        assert_eq!(headless.halt_reason, HaltReason::Breakpoint { addr: 0x204 });
+        assert_eq!(headless.cpu.get_pc(), 0x204);
+    }
+
+    #[test]
+    fn test_fault_trace_lists_preceding_instructions() {
+        let mut emulator = Emulator::new(EmulatorConfig {
+            trace_depth: 3,
+            ..Default::default()
+        });
+        // LD V0, 01; ADD V0, 02; LD I, 300; RET with an empty stack
+        emulator
+            .load_rom(&[0x60, 0x01, 0x70, 0x02, 0xA3, 0x00, 0x00, 0xEE])
+            .unwrap();
+        for _ in 0..3 {
+            emulator.step().unwrap();
+        }
+        assert!(emulator.run_report().fault_trace.is_empty());
+        assert!(emulator.step().is_err());
+
+        let trace = emulator.run_report().fault_trace;
+        let entries: Vec<_> = trace
+            .iter()
+            .map(|entry| (entry.pc, entry.opcode, entry.mnemonic.as_str()))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (0x202, 0x7002, "ADD V0, 02"),
+                (0x204, 0xA300, "LD I, 300"),
+                (0x206, 0x00EE, "RET"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_fault_trace_in_headless_run() {
+        let (emulator, result) = run_faulting_rom(ErrorPolicy::Stop);
+        result.unwrap();
+        let mnemonics: Vec<_> = emulator
+            .run_report()
+            .fault_trace
+            .into_iter()
+            .map(|entry| entry.mnemonic)
+            .collect();
+        assert_eq!(mnemonics, vec!["ADD V0, 01", "DW FFFF"]);
+    }
+
+    #[test]
+    fn test_run_with_renderer() {
+        /// Asks to quit after a fixed number of frames
+        struct QuitAfter {
+            frames: usize,
+            seen_cycles: Vec<usize>,
+        }
+
+        impl Renderer for QuitAfter {
+            fn render(
+                &mut self,
+                _display: &Display,
+                cycles_executed: usize,
+                _now: Instant,
+                _paused: bool,
+            ) -> Result<ControlAction, crate::display::RendererError> {
+                self.seen_cycles.push(cycles_executed);
+                if self.seen_cycles.len() == self.frames {
+                    Ok(ControlAction::Quit)
+                } else {
+                    Ok(ControlAction::None)
+                }
+            }
+
+            fn is_interactive(&self) -> bool {
+                false
+            }
+        }
+
+        let config = EmulatorConfig {
+            max_cycles: 100,
+            cycle_delay_ms: 0,
+            quiet: true,
+            ..Default::default()
+        };
+        // JP 200
+        let mut headless = Emulator::new(config.clone());
+        headless.load_rom(&[0x12, 0x00]).unwrap();
+        headless.run_with_renderer(&mut HeadlessRenderer).unwrap();
+        assert_eq!(headless.halt_reason, HaltReason::MaxCycles);
+        assert_eq!(headless.get_stats().cycles_executed, 100);
+
+        let mut emulator = Emulator::new(config);
+        emulator.load_rom(&[0x12, 0x00]).unwrap();
+        let mut renderer = QuitAfter {
+            frames: 3,
+            seen_cycles: Vec::new(),
+        };
+        emulator.run_with_renderer(&mut renderer).unwrap();
+        assert_eq!(emulator.halt_reason, HaltReason::UserQuit);
+        assert_eq!(renderer.seen_cycles, vec![1, 2, 3]);
+    }
+
+    /// Run `ADD V0, 1` followed by an unknown opcode under the given error policy
+    fn run_faulting_rom(policy: ErrorPolicy) -> (Emulator, Result<(), EmulatorError>) {
+        let config = EmulatorConfig {
+            max_cycles: 10,
+            cycle_delay_ms: 0,
+            on_cpu_error: policy,
+            ..Default::default()
+        };
+        let mut emulator = Emulator::new(config);
+        emulator.load_rom(&[0x70, 0x01, 0xFF, 0xFF]).unwrap();
+        let result = emulator.run_headless();
+        (emulator, result)
+    }
+
+    #[test]
+    fn test_error_policy_abort_returns_error() {
+        let (emulator, result) = run_faulting_rom(ErrorPolicy::Abort);
+        assert!(matches!(result, Err(EmulatorError::Cpu(_))));
+        assert!(matches!(emulator.halt_reason, HaltReason::Error { .. }));
+        assert_eq!(emulator.cycles_executed, 2);
+    }
+
+    #[test]
+    fn test_error_policy_stop_halts_cleanly() {
+        let (emulator, result) = run_faulting_rom(ErrorPolicy::Stop);
+        assert!(result.is_ok());
+        assert!(matches!(emulator.halt_reason, HaltReason::Error { .. }));
+        assert_eq!(emulator.cycles_executed, 2);
+        assert_eq!(emulator.cpu.get_register(0).unwrap(), 1);
+    }
+
+    #[test]
+    fn test_error_policy_warm_reset_continues() {
+        let (emulator, result) = run_faulting_rom(ErrorPolicy::WarmReset);
+        assert!(result.is_ok());
+        assert_eq!(emulator.halt_reason, HaltReason::MaxCycles);
+        assert_eq!(emulator.cycles_executed, 10);
+
+        // Every second cycle faults and restarts the program, which stays loaded
+        assert_eq!(emulator.warnings.len(), 5);
+        assert_eq!(emulator.cpu.get_register(0).unwrap(), 0);
+        assert_eq!(emulator.memory.read_word(0x200).unwrap(), 0x7001);
+    }
+
+    #[test]
+    fn test_error_policy_from_str() {
+        assert_eq!("abort".parse::<ErrorPolicy>().unwrap(), ErrorPolicy::Abort);
+        assert_eq!(
+            "Warm-Reset".parse::<ErrorPolicy>().unwrap(),
+            ErrorPolicy::WarmReset
+        );
+        assert_eq!("stop".parse::<ErrorPolicy>().unwrap(), ErrorPolicy::Stop);
+        assert!("retry".parse::<ErrorPolicy>().is_err());
+    }
+
+    #[derive(Clone, Default)]
+    struct RecordingAudio {
+        events: std::rc::Rc<std::cell::RefCell<Vec<&'static str>>>,
+    }
+
+    impl AudioOutput for RecordingAudio {
+        fn start_beep(&mut self) {
+            self.events.borrow_mut().push("start");
+        }
+
+        fn stop_beep(&mut self) {
+            self.events.borrow_mut().push("stop");
+        }
+
+        fn set_frequency(&mut self, _hz: f32) {}
+    }
+
+    #[test]
+    fn test_beep_follows_sound_timer() {
+        let audio = RecordingAudio::default();
+        let mut emulator = Emulator::new(EmulatorConfig {
+            min_beep_ms: 0,
+            ..Default::default()
+        });
+        emulator.set_audio_output(Box::new(audio.clone()));
+
+        // LD V0, 2; LD ST, V0; JP 0x204 (spin)
+        emulator
+            .load_rom(&[0x60, 0x02, 0xF0, 0x18, 0x12, 0x04])
+            .unwrap();
+        emulator.step().unwrap();
+        assert!(audio.events.borrow().is_empty());
+
+        // The beep starts once, when the sound timer becomes active
+        emulator.step().unwrap();
+        emulator.step().unwrap();
+        assert_eq!(*audio.events.borrow(), vec!["start"]);
+
+        // ...and stops when the timer runs out
+        emulator.advance_timers(TIMER_TICK);
+        assert_eq!(*audio.events.borrow(), vec!["start"]);
+        emulator.advance_timers(TIMER_TICK);
+        assert_eq!(*audio.events.borrow(), vec!["start", "stop"]);
+    }
+
+    #[test]
+    fn test_short_beep_lasts_min_beep_ms() {
+        let audio = RecordingAudio::default();
+        let mut emulator = Emulator::new(EmulatorConfig {
+            min_beep_ms: 40,
+            ..Default::default()
+        });
+        emulator.set_audio_output(Box::new(audio.clone()));
+
+        // LD V0, 1; LD ST, V0; JP 0x204 (spin)
+        emulator
+            .load_rom(&[0x60, 0x01, 0xF0, 0x18, 0x12, 0x04])
+            .unwrap();
+        emulator.step().unwrap();
+        emulator.step().unwrap();
+        assert_eq!(*audio.events.borrow(), vec!["start"]);
+
+        // The 1-tick timer runs out at once, but 40ms rounds up to a 3-tick beep
+        emulator.tick_timers();
+        assert_eq!(emulator.cpu().get_sound_timer(), 0);
+        emulator.tick_timers();
+        emulator.step().unwrap();
+        assert_eq!(*audio.events.borrow(), vec!["start"]);
+
+        emulator.tick_timers();
+        assert_eq!(*audio.events.borrow(), vec!["start", "stop"]);
+    }
+
+    /// Output sink that keeps what was written for the test to inspect
+    #[derive(Clone, Default)]
+    struct CapturedOutput(std::rc::Rc<std::cell::RefCell<Vec<u8>>>);
+
+    impl Write for CapturedOutput {
+        fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+            self.0.borrow_mut().extend_from_slice(buf);
+            Ok(buf.len())
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    /// Run a short headless program, returning everything it printed
+    fn run_and_capture(quiet: bool) -> String {
+        let config = EmulatorConfig {
+            max_cycles: 5,
+            cycle_delay_ms: 0,
             verbose: true,
-            write_protection: false,
+            quiet,
+            ..Default::default()
         };
+        let mut emulator = Emulator::new(config);
+        let output = CapturedOutput::default();
+        emulator.set_output(Box::new(output.clone()));
+        emulator.load_rom(&[0x70, 0x01, 0x12, 0x00]).unwrap();
+        emulator.run_headless().unwrap();
+        String::from_utf8(output.0.take()).unwrap()
+    }
 
-        let emulator = Emulator::new(config.clone());
-        assert_eq!(emulator.config.max_cycles, 100);
-        assert_eq!(emulator.config.cycle_delay_ms, 10);
-        assert!(emulator.config.verbose);
-        assert!(!emulator.config.write_protection);
+    #[test]
+    fn test_quiet_suppresses_status_output() {
+        let output = run_and_capture(false);
+        assert!(output.contains("Starting emulation in headless mode..."));
+        assert!(output.contains("Cycle 5: PC=0x"));
+        assert!(output.contains("Emulation completed after 5 cycles"));
+
+        assert_eq!(run_and_capture(true), "");
     }
 
     #[test]
-    fn test_emulator_reset() {
+    fn test_step_if_running_respects_pause() {
         let mut emulator = Emulator::with_defaults();
+        // ADD V0, 1; JP 0x200
+        emulator.load_rom(&[0x70, 0x01, 0x12, 0x00]).unwrap();
+
+        assert!(emulator.step_if_running().unwrap());
+        assert_eq!(emulator.cycles_executed, 1);
+
+        // Paused: nothing executes and the cycle counter holds
+        emulator.toggle_pause();
+        assert!(emulator.is_paused());
+        assert!(!emulator.step_if_running().unwrap());
+        assert!(!emulator.step_if_running().unwrap());
+        assert_eq!(emulator.cycles_executed, 1);
+        assert_eq!(emulator.cpu.get_pc(), 0x202);
+
+        // Resumed: execution continues where it left off
+        emulator.toggle_pause();
+        assert!(emulator.step_if_running().unwrap());
+        assert_eq!(emulator.cycles_executed, 2);
+        assert_eq!(emulator.cpu.get_pc(), 0x200);
+        assert_eq!(emulator.cpu.get_register(0).unwrap(), 1);
+    }
 
-        // Execute a few steps to change state
-        let _ = emulator.step();
-        let _ = emulator.step();
+    #[cfg(feature = "save-state")]
+    #[test]
+    fn test_save_state_round_trip() {
+        let mut emulator = Emulator::with_defaults();
+        emulator
+            .load_rom(&[
+                0x70, 0x01, // ADD V0, 1
+                0x71, 0x02, // ADD V1, 2
+                0xA3, 0x00, // LD I, 0x300
+                0xF0, 0x55, // LD [I], V0
+                0xD0, 0x15, // DRW V0, V1, 5
+                0xF0, 0x15, // LD DT, V0
+                0x12, 0x00, // JP 0x200
+            ])
+            .unwrap();
+        for _ in 0..500 {
+            emulator.step().unwrap();
+        }
+        let snapshot = emulator.save_state();
+        let pc = emulator.cpu().get_pc();
+        let registers: Vec<u8> = (0..16)
+            .map(|r| emulator.cpu().get_register(r).unwrap())
+            .collect();
+        let ram = emulator.memory().as_slice().to_vec();
+        let pixels = emulator.display().to_bitplane();
+
+        for _ in 0..500 {
+            emulator.step().unwrap();
+        }
+        assert_ne!(emulator.save_state(), snapshot);
 
-        // Reset should restore initial state
+        emulator.load_state(&snapshot).unwrap();
+        assert_eq!(emulator.save_state(), snapshot);
+        assert_eq!(emulator.cpu().get_pc(), pc);
+        for (r, &value) in registers.iter().enumerate() {
+            assert_eq!(emulator.cpu().get_register(r).unwrap(), value);
+        }
+        assert_eq!(emulator.memory().as_slice(), ram.as_slice());
+        assert_eq!(emulator.display().to_bitplane(), pixels);
+
+        // Garbage and truncated snapshots are rejected without touching the machine
+        assert!(matches!(
+            emulator.load_state(&snapshot[..snapshot.len() / 2]),
+            Err(EmulatorError::InvalidSaveState { .. })
+        ));
+        assert!(matches!(
+            emulator.load_state(&[0xFF; 8]),
+            Err(EmulatorError::InvalidSaveState { .. })
+        ));
+        assert_eq!(emulator.save_state(), snapshot);
+    }
+
+    #[test]
+    fn test_random_source_survives_resets() {
+        use crate::random::{FileSequence, SequenceEnd};
+
+        let mut emulator = Emulator::with_defaults();
+        // RND V0, FF; JP 200
+        emulator.load_rom(&[0xC0, 0xFF, 0x12, 0x00]).unwrap();
+        let sequence = FileSequence::new(vec![0x11, 0x22, 0x33], SequenceEnd::Error).unwrap();
+        emulator.set_random_source(Box::new(sequence));
+
+        let mut draws = Vec::new();
+        for _ in 0..2 {
+            emulator.step().unwrap(); // RND
+            draws.push(emulator.cpu.get_register(0).unwrap());
+            emulator.step().unwrap(); // JP
+        }
+        // A warm reset restarts the script rather than falling back to the PRNG
+        emulator.warm_reset();
+        emulator.step().unwrap();
+        draws.push(emulator.cpu.get_register(0).unwrap());
         emulator.reset();
-        let stats = emulator.get_stats();
+        emulator.load_rom(&[0xC0, 0xFF]).unwrap();
+        emulator.step().unwrap();
+        draws.push(emulator.cpu.get_register(0).unwrap());
+        assert_eq!(draws, vec![0x11, 0x22, 0x11, 0x11]);
+    }
 
-        assert_eq!(stats.cycles_executed, 0);
+    #[cfg(feature = "save-state")]
+    #[test]
+    fn test_load_state_rejects_corrupt_cpu_and_keeps_config() {
+        let mut saved = Emulator::new(EmulatorConfig {
+            index_increment: IndexIncrementMode::XPlusOne,
+            write_protection: false,
+            ..Default::default()
+        });
+        saved.load_rom(&[0x12, 0x00]).unwrap();
+        let snapshot = saved.save_state();
+
+        let mut emulator = Emulator::with_defaults();
+        emulator.load_rom(&[0x12, 0x00]).unwrap();
+        emulator.load_state(&snapshot).unwrap();
+        assert_eq!(
+            emulator.cpu().index_increment(),
+            IndexIncrementMode::Unchanged
+        );
+        assert!(emulator.memory.write_byte(0x100, 1).is_err());
+
+        // Re-encode the snapshot with one CPU field overwritten
+        let tampered = |field: &str, value: serde_json::Value| {
+            let mut cpu = serde_json::to_value(&saved.cpu).unwrap();
+            cpu[field] = value;
+            let cpu: Cpu = serde_json::from_value(cpu).unwrap();
+            let state = (SAVE_STATE_VERSION, &cpu, &saved.memory, &saved.display);
+            bincode::serde::encode_to_vec(state, bincode::config::standard()).unwrap()
+        };
+        for bad in [
+            tampered("sp", 17.into()),
+            tampered("pc", 0xFFF.into()),
+            tampered("i", 0x1000.into()),
+            tampered(
+                "state",
+                serde_json::json!({ "WaitingForKey": { "vx": 16 } }),
+            ),
+        ] {
+            assert!(matches!(
+                emulator.load_state(&bad),
+                Err(EmulatorError::InvalidSaveState { .. })
+            ));
+        }
+        assert!(emulator.load_state(&tampered("sp", 16.into())).is_ok());
+    }
+
+    #[cfg(feature = "save-state")]
+    #[test]
+    fn test_load_state_keeps_random_source() {
+        use crate::random::{FileSequence, SequenceEnd};
+
+        let mut emulator = Emulator::with_defaults();
+        // RND V0, FF; JP 200
+        emulator.load_rom(&[0xC0, 0xFF, 0x12, 0x00]).unwrap();
+        let sequence = FileSequence::new(vec![0x11, 0x22], SequenceEnd::Error).unwrap();
+        emulator.set_random_source(Box::new(sequence));
+        let snapshot = emulator.save_state();
+        emulator.step().unwrap();
+
+        emulator.load_state(&snapshot).unwrap();
+        emulator.step().unwrap();
+        assert_eq!(emulator.cpu.get_register(0).unwrap(), 0x22);
+    }
+
+    #[cfg(feature = "save-state")]
+    #[test]
+    fn test_quit_and_save_writes_loadable_state() {
+        let mut emulator = Emulator::new(EmulatorConfig {
+            quiet: true,
+            ..Default::default()
+        });
+        // LD V3, 2A; JP 202
+        emulator.load_rom(&[0x63, 0x2A, 0x12, 0x02]).unwrap();
+        emulator.step().unwrap();
+
+        // Without a location the run keeps going
+        assert!(!emulator.quit_and_save());
+        assert_eq!(emulator.halt_reason, HaltReason::NotHalted);
+
+        let path = std::env::temp_dir().join(format!("joe-resume-{}.state", std::process::id()));
+        emulator.set_resume_state_path(&path);
+        assert!(emulator.quit_and_save());
+        assert_eq!(emulator.halt_reason, HaltReason::UserQuit);
+
+        let state = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        let mut resumed = Emulator::with_defaults();
+        resumed.load_state(&state).unwrap();
+        assert_eq!(resumed.cpu().get_register(3).unwrap(), 0x2A);
+        assert_eq!(resumed.cpu().get_pc(), 0x202);
+    }
+
+    #[cfg(feature = "save-state")]
+    #[test]
+    fn test_step_back_restores_earlier_state() {
+        let mut emulator = Emulator::new(EmulatorConfig {
+            rewind_depth: 5,
+            ..Default::default()
+        });
+        emulator
+            .load_rom(&[
+                0x70, 0x01, // ADD V0, 1
+                0x71, 0x02, // ADD V1, 2
+                0xA3, 0x00, // LD I, 0x300
+                0xF1, 0x55, // LD [I], V1
+                0x12, 0x00, // JP 0x200
+            ])
+            .unwrap();
+        emulator.add_breakpoint(0x280);
+
+        for _ in 0..7 {
+            emulator.step().unwrap();
+        }
+        let earlier = emulator.cpu().snapshot();
+        let earlier_ram = emulator.memory().as_slice().to_vec();
+        for _ in 0..3 {
+            emulator.step().unwrap();
+        }
+        assert_ne!(emulator.cpu().snapshot(), earlier);
+
+        for _ in 0..3 {
+            assert!(emulator.step_back().unwrap());
+        }
+        assert_eq!(emulator.cpu().snapshot(), earlier);
+        assert_eq!(emulator.memory().as_slice(), earlier_ram.as_slice());
+        assert_eq!(emulator.get_stats().cycles_executed, 7);
+        assert!(emulator.cpu().breakpoints().contains(&0x280));
+
+        // Only `rewind_depth` states are kept
+        assert!(emulator.step_back().unwrap());
+        assert!(emulator.step_back().unwrap());
+        assert!(!emulator.step_back().unwrap());
+    }
+
+    #[cfg(feature = "save-state")]
+    #[test]
+    fn test_step_back_replays_same_random_bytes() {
+        let mut emulator = Emulator::new(EmulatorConfig {
+            rewind_depth: 4,
+            ..Default::default()
+        });
+        // RND V0, FF; RND V1, FF; JP 200
+        emulator
+            .load_rom(&[0xC0, 0xFF, 0xC1, 0xFF, 0x12, 0x00])
+            .unwrap();
+        let draws = |emulator: &mut Emulator| {
+            emulator.step().unwrap();
+            emulator.step().unwrap();
+            (
+                emulator.cpu().get_register(0).unwrap(),
+                emulator.cpu().get_register(1).unwrap(),
+            )
+        };
+
+        let first = draws(&mut emulator);
+        assert!(emulator.step_back().unwrap());
+        assert!(emulator.step_back().unwrap());
+        assert_eq!(draws(&mut emulator), first);
+    }
+
+    #[test]
+    fn test_cycles_per_frame_ticks_timers_once_per_frame() {
+        use crate::clock::MockClock;
+
+        let mut emulator = Emulator::new(EmulatorConfig {
+            max_cycles: 52,
+            cycles_per_frame: 10,
+            quiet: true,
+            ..Default::default()
+        });
+        // A clock racing ahead would tick wall-clock timers every cycle
+        emulator.set_clock(Box::new(MockClock::with_auto_advance(TIMER_TICK)));
+        emulator
+            .load_rom(&[
+                0x60, 0xFF, // LD V0, FF
+                0xF0, 0x15, // LD DT, V0
+                0x12, 0x04, // JP 0x204 (spin)
+            ])
+            .unwrap();
+        emulator.run_headless().unwrap();
+
+        // Frames end at cycles 10, 20, 30, 40 and 50: five timer ticks
+        assert_eq!(emulator.cycles_executed, 52);
+        assert_eq!(emulator.cpu.get_delay_timer(), 0xFF - 5);
+    }
+
+    #[test]
+    fn test_pace_by_cpu_hz_spreads_cycles_over_ticks() {
+        use crate::clock::MockClock;
+
+        let mut emulator = Emulator::new(EmulatorConfig {
+            max_cycles: 26,
+            pace_by_cpu_hz: true,
+            quiet: true,
+            ..Default::default()
+        });
+        emulator.set_clock(Box::new(MockClock::with_auto_advance(TIMER_TICK)));
+        emulator
+            .load_rom(&[
+                0x60, 0xFF, // LD V0, FF
+                0xF0, 0x15, // LD DT, V0
+                0x12, 0x04, // JP 0x204 (spin)
+            ])
+            .unwrap();
+        emulator.run_headless().unwrap();
+
+        // 500Hz over 60Hz: frames end at cycles 8, 16 and 25
+        assert_eq!(emulator.cycles_executed, 26);
+        assert_eq!(emulator.cpu.get_delay_timer(), 0xFF - 3);
+    }
+
+    #[test]
+    fn test_run_cycles_steps_and_ticks_timers() {
+        use crate::clock::MockClock;
+
+        let clock = MockClock::new();
+        let mut emulator = Emulator::with_defaults();
+        emulator.set_clock(Box::new(clock.clone()));
+        emulator
+            .load_rom(&[
+                0x60, 0x1E, // LD V0, 30
+                0xF0, 0x15, // LD DT, V0
+                0xF1, 0x29, // LD F, V1 (glyph for 0)
+                0xD1, 0x15, // DRW V1, V1, 5
+                0x12, 0x08, // JP 0x208 (spin)
+            ])
+            .unwrap();
+
+        // The first call only starts the clock
+        emulator.run_cycles(4).unwrap();
+        assert_eq!(emulator.cycles_executed, 4);
+        assert_eq!(emulator.cpu.get_delay_timer(), 30);
+        // "0" glyph: top row 0xF0
+        assert_eq!(
+            emulator.frame_buffer()[0][..5],
+            [true, true, true, true, false]
+        );
+
+        clock.advance(TIMER_TICK);
+        emulator.run_cycles(10).unwrap();
+        assert_eq!(emulator.cycles_executed, 14);
+        assert_eq!(emulator.cpu.get_delay_timer(), 29);
+
+        clock.advance(TIMER_TICK * 3);
+        emulator.run_cycles(0).unwrap();
+        assert_eq!(emulator.cycles_executed, 14);
+        assert_eq!(emulator.cpu.get_delay_timer(), 26);
+    }
+
+    #[test]
+    fn test_run_for_wall_clock_duration() {
+        let mut emulator = Emulator::new(EmulatorConfig {
+            quiet: true,
+            ..Default::default()
+        });
+        // JP 200
+        emulator.load_rom(&[0x12, 0x00]).unwrap();
+        let ticks = std::rc::Rc::new(std::cell::Cell::new(0));
+        let counter = ticks.clone();
+        emulator.on_frame(Box::new(move |_| counter.set(counter.get() + 1)));
+
+        let stats = emulator.run_for(Duration::from_millis(50)).unwrap();
+        // 500Hz in 60Hz ticks: 8, 8, 9 cycles at 0, 16.7 and 33.3ms
+        assert!(
+            (8..=34).contains(&stats.cycles_executed),
+            "{} cycles",
+            stats.cycles_executed
+        );
+        let ticks = ticks.get();
+        assert!((8 * ticks..=9 * ticks).contains(&stats.cycles_executed));
+        assert!(stats.run_duration >= Duration::from_millis(50));
         assert!(!stats.is_running);
+        assert_eq!(stats.program_counter, 0x200);
+        assert_eq!(emulator.halt_reason, HaltReason::NotHalted);
+    }
+
+    #[test]
+    fn test_run_for_stops_from_another_thread() {
+        let mut emulator = Emulator::new(EmulatorConfig {
+            quiet: true,
+            ..Default::default()
+        });
+        // JP 200
+        emulator.load_rom(&[0x12, 0x00]).unwrap();
+
+        // Wait for the run to start, then clear the flag
+        let handle = emulator.stop_handle();
+        let stopper = std::thread::spawn(move || {
+            while !handle.load(Ordering::SeqCst) {
+                std::thread::yield_now();
+            }
+            handle.store(false, Ordering::SeqCst);
+        });
+        let stats = emulator.run_for(Duration::from_secs(30)).unwrap();
+        stopper.join().unwrap();
+
+        assert!(stats.run_duration < Duration::from_secs(30));
+        assert_eq!(emulator.halt_reason, HaltReason::Interrupted);
+    }
+
+    #[test]
+    fn test_run_for_honors_limits_and_error_policy() {
+        // LD V0, 05; ADD V0, 01; JP 202
+        let rom = [0x60, 0x05, 0x70, 0x01, 0x12, 0x02];
+        let quiet = |config: EmulatorConfig| {
+            Emulator::new(EmulatorConfig {
+                quiet: true,
+                ..config
+            })
+        };
+
+        let mut emulator = quiet(EmulatorConfig {
+            max_cycles: 5,
+            ..Default::default()
+        });
+        emulator.load_rom(&rom).unwrap();
+        let stats = emulator.run_for(Duration::from_secs(30)).unwrap();
+        assert_eq!(stats.cycles_executed, 5);
+        assert_eq!(emulator.halt_reason, HaltReason::MaxCycles);
+
+        let mut emulator = quiet(EmulatorConfig::default());
+        emulator.load_rom(&rom).unwrap();
+        emulator.add_breakpoint(0x204);
+        emulator.run_for(Duration::from_secs(30)).unwrap();
+        assert_eq!(emulator.halt_reason, HaltReason::Breakpoint { addr: 0x204 });
+        assert_eq!(emulator.cpu.get_pc(), 0x204);
+
+        // ADD V0, 01, then FFFF is not an instruction
+        let bad_rom = [0x70, 0x01, 0xFF, 0xFF];
+        let mut emulator = quiet(EmulatorConfig {
+            on_cpu_error: ErrorPolicy::Stop,
+            ..Default::default()
+        });
+        emulator.load_rom(&bad_rom).unwrap();
+        emulator.run_for(Duration::from_secs(30)).unwrap();
+        assert!(matches!(emulator.halt_reason, HaltReason::Error { .. }));
+
+        let mut emulator = quiet(EmulatorConfig {
+            on_cpu_error: ErrorPolicy::WarmReset,
+            max_cycles: 3,
+            ..Default::default()
+        });
+        emulator.load_rom(&bad_rom).unwrap();
+        emulator.run_for(Duration::from_secs(30)).unwrap();
+        assert_eq!(emulator.halt_reason, HaltReason::MaxCycles);
+        assert!(!emulator.warnings.is_empty());
+
+        let mut emulator = quiet(EmulatorConfig::default());
+        emulator.load_rom(&bad_rom).unwrap();
+        assert!(emulator.run_for(Duration::from_secs(30)).is_err());
+    }
+
+    #[test]
+    fn test_run_frames_runs_a_fixed_number_of_ticks() {
+        // 600Hz over 60Hz timers: 10 cycles per frame
+        let config = EmulatorConfig {
+            cpu_hz: 600,
+            quiet: true,
+            ..Default::default()
+        };
+
+        // JP 200 spins forever
+        let mut emulator = Emulator::new(config.clone());
+        emulator.load_rom(&[0x12, 0x00]).unwrap();
+        emulator.cpu.set_timers(10, 0);
+        let stats = emulator.run_frames(3).unwrap();
+        assert_eq!(stats.cycles_executed, 30);
+        assert_eq!(emulator.cpu.timers(), (7, 0));
+        assert_eq!(emulator.halt_reason, HaltReason::NotHalted);
+
+        // RET with an empty stack faults on every cycle
+        let mut emulator = Emulator::new(EmulatorConfig {
+            on_cpu_error: ErrorPolicy::WarmReset,
+            ..config.clone()
+        });
+        emulator.load_rom(&[0x00, 0xEE]).unwrap();
+        emulator.run_frames(3).unwrap();
+        assert_eq!(emulator.run_report().warm_resets, 30);
+
+        let mut emulator = Emulator::new(config);
+        emulator.load_rom(&[0x00, 0xEE]).unwrap();
+        assert!(emulator.run_frames(3).is_err());
+        assert_eq!(emulator.run_report().warm_resets, 0);
+    }
+
+    #[test]
+    fn test_run_tick_follows_cpu_and_timer_rates() {
+        let mut emulator = Emulator::new(EmulatorConfig {
+            cpu_hz: 700,
+            ..Default::default()
+        });
+        // JP 200 spins forever
+        emulator.load_rom(&[0x12, 0x00]).unwrap();
+        emulator.cpu.set_timers(255, 0);
+
+        // One second of ticks: exactly cpu_hz cycles and timer_hz timer ticks
+        for _ in 0..60 {
+            emulator.run_tick().unwrap();
+        }
+        assert_eq!(emulator.cycles_executed, 700);
+        assert_eq!(emulator.cpu.timers(), (255 - 60, 0));
+
+        // timer_hz also sets how much clock time a timer tick takes
+        let mut emulator = Emulator::new(EmulatorConfig {
+            timer_hz: 50,
+            ..Default::default()
+        });
+        emulator.cpu.set_timers(10, 0);
+        emulator.advance_timers(Duration::from_millis(100));
+        assert_eq!(emulator.cpu.timers(), (5, 0));
+    }
+
+    #[test]
+    fn test_advance_timers_carries_partial_ticks() {
+        let mut emulator = Emulator::with_defaults();
+        emulator.cpu.set_timers(10, 10);
+
+        // Two half ticks add up to one full tick
+        emulator.advance_timers(TIMER_TICK / 2);
+        assert_eq!(emulator.cpu.timers(), (10, 10));
+        emulator.advance_timers(TIMER_TICK / 2);
+        assert_eq!(emulator.cpu.timers(), (9, 9));
+
+        emulator.advance_timers(TIMER_TICK * 3);
+        assert_eq!(emulator.cpu.timers(), (6, 6));
+    }
+
+    #[test]
+    fn test_tick_timers_decrements_without_cycles() {
+        let mut emulator = Emulator::with_defaults();
+        emulator.load_rom(&[0x12, 0x00]).unwrap();
+        emulator.cpu.set_timers(5, 2);
+
+        for _ in 0..3 {
+            emulator.tick_timers();
+        }
+        // The sound timer stops at zero, and no instruction ran
+        assert_eq!(emulator.cpu.timers(), (2, 0));
+        assert_eq!(emulator.cycles_executed, 0);
+        assert_eq!(emulator.cpu.get_pc(), 0x200);
+    }
+
+    #[test]
+    fn test_recorded_input_replays_to_same_display() {
+        // Wait for a key and draw its digit, then draw a random digit beside it
+        let rom = [
+            0x61, 0x00, 0x62, 0x00, 0xF0, 0x0A, 0xF0, 0x29, 0xD1, 0x25, 0xC3, 0x0F, 0xF3, 0x29,
+            0x61, 0x08, 0xD1, 0x25, 0x12, 0x12,
+        ];
+
+        let mut recorded = Emulator::new(EmulatorConfig {
+            seed: Some(99),
+            auto_input: vec![(5, 0x7)],
+            ..Default::default()
+        });
+        recorded.load_rom(&rom).unwrap();
+        recorded.attach_recorder();
+        for _ in 0..20 {
+            recorded.step().unwrap();
+        }
+        let log = recorded.take_recording().unwrap();
+        assert_eq!(
+            log.events,
+            vec![
+                (5, crate::replay::KeypadEvent::Pressed(0x7)),
+                (6, crate::replay::KeypadEvent::Released(0x7)),
+            ]
+        );
+
+        // A differently seeded emulator replays the run exactly
+        let mut replayed = Emulator::new(EmulatorConfig {
+            seed: Some(1),
+            ..Default::default()
+        });
+        replayed.load_rom(&rom).unwrap();
+        replayed.attach_player(InputLog::from_json(&log.to_json().unwrap()).unwrap());
+        for _ in 0..20 {
+            replayed.step().unwrap();
+        }
+        assert_eq!(replayed.get_seed(), 99);
+        assert!(recorded.display.get_stats().pixels_on > 0);
+        assert_eq!(replayed.display.pixels(), recorded.display.pixels());
+    }
+
+    fn stack_tracking_emulator(rom: &[u8]) -> Emulator {
+        let mut emulator = Emulator::new(EmulatorConfig {
+            track_stack_balance: true,
+            ..Default::default()
+        });
+        emulator.load_rom(rom).unwrap();
+        emulator
+    }
+
+    #[test]
+    fn test_balanced_stack_has_no_warning() {
+        // CALL 206; JP 202 (spin); (pad); RET
+        let mut emulator =
+            stack_tracking_emulator(&[0x22, 0x06, 0x12, 0x02, 0x00, 0x00, 0x00, 0xEE]);
+        for _ in 0..4 {
+            emulator.step().unwrap();
+        }
+        assert_eq!(emulator.cpu.get_sp(), 0);
+        assert!(emulator.run_report().warnings.is_empty());
+    }
+
+    #[test]
+    fn test_unreturned_call_warns() {
+        // CALL 202; JP 202 (spin without returning)
+        let mut emulator = stack_tracking_emulator(&[0x22, 0x02, 0x12, 0x02]);
+        for _ in 0..3 {
+            emulator.step().unwrap();
+        }
+        assert_eq!(emulator.cpu.get_sp(), 1);
+        assert_eq!(
+            emulator.run_report().warnings,
+            vec!["Run ended with 1 unreturned CALL on the stack (SP=1)"]
+        );
+
+        // Off by default
+        let mut untracked = Emulator::with_defaults();
+        untracked.load_rom(&[0x22, 0x02, 0x12, 0x02]).unwrap();
+        untracked.step().unwrap();
+        assert!(untracked.run_report().warnings.is_empty());
+    }
+
+    #[test]
+    fn test_ret_outside_rom_warns() {
+        // JP 300; (unused); RET - with CALL 204 at 0x300, past the end of the ROM
+        let mut emulator = stack_tracking_emulator(&[0x13, 0x00, 0x00, 0x00, 0x00, 0xEE]);
+        emulator.memory.write_word(0x300, 0x2204).unwrap();
+        for _ in 0..3 {
+            emulator.step().unwrap();
+        }
+        assert_eq!(emulator.cpu.get_pc(), 0x302);
+        assert_eq!(
+            emulator.run_report().warnings,
+            vec!["RET returned to 0x0302, outside the ROM (0x0200-0x0205)"]
+        );
+    }
+
+    #[test]
+    fn test_reload_rom_picks_up_new_bytes() {
+        let path = std::env::temp_dir().join(format!("joe-reload-{}.ch8", std::process::id()));
+        // LD V0, 01; JP 202
+        std::fs::write(&path, [0x60, 0x01, 0x12, 0x02]).unwrap();
+
+        let mut emulator = Emulator::with_defaults();
+        emulator
+            .load_rom_from_source(path.to_str().unwrap())
+            .unwrap();
+        emulator.step().unwrap();
+        emulator.step().unwrap();
+        assert_eq!(emulator.cpu.get_register(0).unwrap(), 0x01);
+
+        // Edit the ROM on disk: LD V0, 2A
+        std::fs::write(&path, [0x60, 0x2A]).unwrap();
+        let result = emulator.reload_rom();
+        std::fs::remove_file(&path).unwrap();
+        result.unwrap();
+
+        // Restarted from 0x200 on the new bytes, with the old tail gone
+        assert_eq!(emulator.cpu.get_pc(), 0x200);
+        assert_eq!(emulator.memory.read_word(0x202).unwrap(), 0x0000);
+        emulator.step().unwrap();
+        assert_eq!(emulator.cpu.get_register(0).unwrap(), 0x2A);
+
+        // The original loader settings apply to the reload too
+        std::fs::write(&path, [0x60, 0x01]).unwrap();
+        let config = RomLoaderConfig {
+            max_rom_size: 2,
+            ..Default::default()
+        };
+        emulator
+            .load_rom_from_source_with_progress(path.to_str().unwrap(), &config, &mut |_, _| {})
+            .unwrap();
+        std::fs::write(&path, [0x60, 0x01, 0x12, 0x02]).unwrap();
+        let result = emulator.reload_rom();
+        std::fs::remove_file(&path).unwrap();
+        assert!(matches!(result, Err(EmulatorError::RomLoad { .. })));
+        assert_eq!(emulator.memory.read_word(0x202).unwrap(), 0x0000);
+
+        // Raw bytes have no source to reload from
+        emulator.load_rom(&[0x00, 0xE0]).unwrap();
+        assert!(matches!(
+            emulator.reload_rom(),
+            Err(EmulatorError::NoRomSource)
+        ));
     }
 
     #[test]
@@ -426,4 +3489,25 @@ mod tests {
 
         assert!(emulator.load_rom(&rom_data).is_ok());
     }
+
+    #[test]
+    fn test_load_rom_from_source() {
+        let mut emulator = Emulator::with_defaults();
+        let path = concat!(env!("CARGO_MANIFEST_DIR"), "/test.ch8");
+
+        let source = emulator.load_rom_from_source(path).unwrap();
+        assert!(source.is_file());
+        assert_eq!(source.description(), path);
+
+        let expected = std::fs::read(path).unwrap();
+        assert_eq!(
+            &emulator.memory.as_slice()[0x200..0x200 + expected.len()],
+            &expected[..]
+        );
+
+        assert!(matches!(
+            emulator.load_rom_from_source("missing_rom.ch8"),
+            Err(EmulatorError::RomLoad { .. })
+        ));
+    }
 }
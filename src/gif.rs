@@ -0,0 +1,268 @@
+//! GIF - Animated recordings of the display
+//!
+//! A [`GifRecorder`] collects frames, typically from [`Emulator::on_frame`],
+//! and encodes them as a looping two-color GIF. Like the PNG writer behind
+//! the `image` feature, the encoder is self-contained: frames come from
+//! [`Display::to_indexed`] and are LZW-compressed here.
+//!
+//! [`Emulator::on_frame`]: crate::Emulator::on_frame
+
+use crate::Display;
+use crate::constants::{DISPLAY_HEIGHT, DISPLAY_WIDTH};
+use std::collections::HashMap;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+/// Smallest LZW code size GIF allows; enough for a two-color palette
+const MIN_CODE_SIZE: u8 = 2;
+
+/// GIF LZW codes are at most 12 bits wide
+const MAX_CODES: u16 = 1 << 12;
+
+/// Records display frames and writes them out as an animated GIF
+#[derive(Debug, Clone)]
+pub struct GifRecorder {
+    scale: usize,
+    delay_cs: u16,
+    color: (u8, u8, u8),
+    max_frames: usize,
+    frames: Vec<Vec<u8>>,
+}
+
+impl GifRecorder {
+    /// Create a recorder keeping up to `max_frames` frames, each CHIP-8 pixel
+    /// a `scale`×`scale` block drawn in `color` on black, shown for `delay`
+    ///
+    /// GIF delays have centisecond resolution, so `delay` is rounded down to
+    /// a multiple of 10ms.
+    pub fn new(
+        max_frames: usize,
+        scale: u32,
+        delay: Duration,
+        color: (u8, u8, u8),
+    ) -> io::Result<Self> {
+        if scale == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "GIF scale must be at least 1",
+            ));
+        }
+        if DISPLAY_WIDTH * scale as usize > u16::MAX as usize {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("GIF scale {} is too large", scale),
+            ));
+        }
+        Ok(Self {
+            scale: scale as usize,
+            delay_cs: (delay.as_millis() / 10).min(u16::MAX as u128) as u16,
+            color,
+            max_frames,
+            frames: Vec::new(),
+        })
+    }
+
+    /// Capture the display as the next frame, unless the recorder is full
+    pub fn capture(&mut self, display: &Display) {
+        if !self.is_full() {
+            self.frames.push(display.to_indexed(self.scale));
+        }
+    }
+
+    /// Check whether `max_frames` frames have been captured
+    pub fn is_full(&self) -> bool {
+        self.frames.len() >= self.max_frames
+    }
+
+    /// Get the captured frames, one palette index (0 unlit, 1 lit) per GIF pixel
+    pub fn frames(&self) -> &[Vec<u8>] {
+        &self.frames
+    }
+
+    /// Get the `(width, height)` of the GIF in pixels
+    pub fn frame_size(&self) -> (u16, u16) {
+        (
+            (DISPLAY_WIDTH * self.scale) as u16,
+            (DISPLAY_HEIGHT * self.scale) as u16,
+        )
+    }
+
+    /// Encode the captured frames as a looping animated GIF
+    pub fn encode(&self) -> Vec<u8> {
+        let (width, height) = self.frame_size();
+        let mut gif = b"GIF89a".to_vec();
+
+        // Logical screen with a 2-entry global color table, background index 0
+        gif.extend_from_slice(&width.to_le_bytes());
+        gif.extend_from_slice(&height.to_le_bytes());
+        gif.extend_from_slice(&[0xF0, 0, 0]);
+        let (r, g, b) = self.color;
+        gif.extend_from_slice(&[0, 0, 0, r, g, b]);
+
+        // NETSCAPE2.0 application extension: loop forever
+        gif.extend_from_slice(&[0x21, 0xFF, 0x0B]);
+        gif.extend_from_slice(b"NETSCAPE2.0");
+        gif.extend_from_slice(&[0x03, 0x01, 0x00, 0x00, 0x00]);
+
+        for frame in &self.frames {
+            // Graphic control extension carrying the frame delay
+            gif.extend_from_slice(&[0x21, 0xF9, 0x04, 0x00]);
+            gif.extend_from_slice(&self.delay_cs.to_le_bytes());
+            gif.extend_from_slice(&[0x00, 0x00]);
+
+            // Full-screen image descriptor, no local color table
+            gif.extend_from_slice(&[0x2C, 0, 0, 0, 0]);
+            gif.extend_from_slice(&width.to_le_bytes());
+            gif.extend_from_slice(&height.to_le_bytes());
+            gif.push(0);
+
+            gif.push(MIN_CODE_SIZE);
+            for block in lzw_encode(frame).chunks(255) {
+                gif.push(block.len() as u8);
+                gif.extend_from_slice(block);
+            }
+            gif.push(0);
+        }
+
+        gif.push(0x3B);
+        gif
+    }
+
+    /// Encode the captured frames and write them to `path`
+    pub fn save(&self, path: impl AsRef<Path>) -> io::Result<()> {
+        std::fs::write(path, self.encode())
+    }
+}
+
+/// Packs variable-width codes least significant bit first, as GIF expects
+#[derive(Default)]
+struct BitWriter {
+    bytes: Vec<u8>,
+    pending: u32,
+    pending_bits: u32,
+}
+
+impl BitWriter {
+    fn write(&mut self, code: u16, width: u32) {
+        self.pending |= (code as u32) << self.pending_bits;
+        self.pending_bits += width;
+        while self.pending_bits >= 8 {
+            self.bytes.push(self.pending as u8);
+            self.pending >>= 8;
+            self.pending_bits -= 8;
+        }
+    }
+
+    fn finish(mut self) -> Vec<u8> {
+        if self.pending_bits > 0 {
+            self.bytes.push(self.pending as u8);
+        }
+        self.bytes
+    }
+}
+
+/// LZW-compress palette indices into a GIF image data stream (without the
+/// leading code size byte or the sub-block framing)
+fn lzw_encode(indices: &[u8]) -> Vec<u8> {
+    let clear = 1u16 << MIN_CODE_SIZE;
+    let end = clear + 1;
+    let initial_width = MIN_CODE_SIZE as u32 + 1;
+
+    let mut table: HashMap<(u16, u8), u16> = HashMap::new();
+    let mut next_code = end + 1;
+    let mut width = initial_width;
+    let mut bits = BitWriter::default();
+    bits.write(clear, width);
+
+    let mut pixels = indices.iter();
+    let Some(&first) = pixels.next() else {
+        bits.write(end, width);
+        return bits.finish();
+    };
+    let mut prefix = first as u16;
+    for &pixel in pixels {
+        if let Some(&code) = table.get(&(prefix, pixel)) {
+            prefix = code;
+            continue;
+        }
+        bits.write(prefix, width);
+        if next_code < MAX_CODES {
+            // The decoder widens its codes as soon as its table reaches the next power of two
+            if next_code == 1 << width {
+                width += 1;
+            }
+            table.insert((prefix, pixel), next_code);
+            next_code += 1;
+        } else {
+            bits.write(clear, width);
+            table.clear();
+            next_code = end + 1;
+            width = initial_width;
+        }
+        prefix = pixel as u16;
+    }
+    bits.write(prefix, width);
+    if next_code < MAX_CODES && next_code == 1 << width {
+        width += 1;
+    }
+    bits.write(end, width);
+    bits.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::DisplayBus;
+
+    #[test]
+    fn test_captured_frames_are_scaled() {
+        let mut display = Display::new();
+        display.draw_sprite(0, 0, &[0x80]).unwrap();
+        let mut recorder = GifRecorder::new(2, 3, Duration::from_millis(20), (0, 255, 0)).unwrap();
+        assert_eq!(recorder.frame_size(), (192, 96));
+
+        for _ in 0..3 {
+            recorder.capture(&display);
+        }
+        assert!(recorder.is_full());
+        assert_eq!(recorder.frames().len(), 2);
+        for frame in recorder.frames() {
+            assert_eq!(frame.len(), 192 * 96);
+            assert_eq!(frame.iter().filter(|&&p| p == 1).count(), 9);
+        }
+
+        assert!(GifRecorder::new(1, 0, Duration::ZERO, (0, 0, 0)).is_err());
+    }
+
+    #[test]
+    fn test_encode_two_frames() {
+        let mut display = Display::new();
+        let mut recorder =
+            GifRecorder::new(2, 2, Duration::from_millis(50), (255, 255, 255)).unwrap();
+        recorder.capture(&display);
+        display.draw_sprite(10, 5, &[0xF0, 0x90, 0xF0]).unwrap();
+        recorder.capture(&display);
+        let gif = recorder.encode();
+
+        assert_eq!(&gif[..6], b"GIF89a");
+        assert_eq!(u16::from_le_bytes([gif[6], gif[7]]), 128);
+        assert_eq!(u16::from_le_bytes([gif[8], gif[9]]), 64);
+        assert_eq!(&gif[13..19], &[0, 0, 0, 255, 255, 255]);
+        assert_eq!(&gif[22..33], b"NETSCAPE2.0");
+        // First graphic control extension follows the 19-byte loop extension
+        assert_eq!(&gif[38..42], &[0x21, 0xF9, 0x04, 0x00]);
+        assert_eq!(u16::from_le_bytes([gif[42], gif[43]]), 5);
+        assert_eq!(gif[46], 0x2C);
+        assert_eq!(gif.last(), Some(&0x3B));
+    }
+
+    #[test]
+    fn test_lzw_encode_small_input() {
+        // Clear (4), 1, 1, 1, 1 collapses to codes 4 1 6 1 then end (5), 3 bits each
+        // until the table reaches 8 entries
+        assert_eq!(lzw_encode(&[1, 1, 1, 1]), vec![0x8C, 0x53]);
+        // An empty frame is just clear and end
+        assert_eq!(lzw_encode(&[]), vec![0x2C]);
+    }
+}
@@ -16,10 +16,19 @@
 //! └─┴─┴─┴─┘          └─┴─┴─┴─┘
 //! ```
 
+use serde::{Deserialize, Serialize};
 use std::collections::{HashMap, VecDeque};
 use std::sync::mpsc::Receiver;
 use thiserror::Error;
 
+/// CHIP-8 keypad layout, row by row as on the COSMAC VIP
+pub const KEYPAD_LAYOUT: [[u8; 4]; 4] = [
+    [0x1, 0x2, 0x3, 0xC],
+    [0x4, 0x5, 0x6, 0xD],
+    [0x7, 0x8, 0x9, 0xE],
+    [0xA, 0x0, 0xB, 0xF],
+];
+
 /// Resolved key mappings for CHIP-8 input
 #[derive(Debug, Clone)]
 pub struct KeyMappings {
@@ -31,7 +40,18 @@ pub struct KeyMappings {
 
 impl KeyMappings {
     /// Create key mappings from raw mapping pairs
+    ///
+    /// Letters also map in uppercase, so Shift doesn't change the key hit.
     pub fn from_pairs(mappings: &[(char, u8)]) -> Result<Self, InputError> {
+        Self::from_pairs_with_case(mappings, false)
+    }
+
+    /// Create key mappings from raw mapping pairs, mapping only the exact
+    /// characters given when `case_sensitive` is true
+    pub fn from_pairs_with_case(
+        mappings: &[(char, u8)],
+        case_sensitive: bool,
+    ) -> Result<Self, InputError> {
         let mut key_map = HashMap::new();
         let mut reverse_key_map = HashMap::new();
 
@@ -42,7 +62,7 @@ impl KeyMappings {
 
             key_map.insert(keyboard_key, chip8_key);
             let upper = keyboard_key.to_ascii_uppercase();
-            if upper != keyboard_key {
+            if !case_sensitive && upper != keyboard_key {
                 key_map.insert(upper, chip8_key);
             }
             reverse_key_map.insert(chip8_key, keyboard_key);
@@ -65,9 +85,31 @@ impl KeyMappings {
     }
 }
 
+/// Parse a configured keyboard key: exactly one printable, non-whitespace character
+fn parse_keyboard_key(value: &str) -> Result<char, InputError> {
+    let mut chars = value.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if !c.is_whitespace() && !c.is_control() => Ok(c),
+        _ => Err(InputError::InvalidKeyboardKey {
+            value: value.to_string(),
+        }),
+    }
+}
+
 /// Resolve key mappings from config or use defaults
 pub fn resolve_key_mappings(
     config_mappings: Option<&HashMap<String, String>>,
+) -> Result<KeyMappings, InputError> {
+    resolve_key_mappings_with_case(config_mappings, false)
+}
+
+/// Resolve key mappings from config or use defaults, keeping configured
+/// keyboard keys in their exact case when `case_sensitive` is true
+///
+/// See [`KeyMappings::from_pairs_with_case`].
+pub fn resolve_key_mappings_with_case(
+    config_mappings: Option<&HashMap<String, String>>,
+    case_sensitive: bool,
 ) -> Result<KeyMappings, InputError> {
     match config_mappings {
         Some(mappings) => {
@@ -82,13 +124,14 @@ pub fn resolve_key_mappings(
                     return Err(InputError::InvalidKey { key: chip8_key });
                 }
 
-                // Get first character from keyboard key string
-                if let Some(keyboard_char) = keyboard_key_str.chars().next() {
-                    converted_mappings.push((keyboard_char.to_ascii_lowercase(), chip8_key));
+                let mut keyboard_char = parse_keyboard_key(keyboard_key_str)?;
+                if !case_sensitive {
+                    keyboard_char = keyboard_char.to_ascii_lowercase();
                 }
+                converted_mappings.push((keyboard_char, chip8_key));
             }
 
-            KeyMappings::from_pairs(&converted_mappings)
+            KeyMappings::from_pairs_with_case(&converted_mappings, case_sensitive)
         }
         None => {
             // Use default mappings
@@ -110,7 +153,156 @@ pub fn resolve_key_mappings(
                 ('c', 0xB),
                 ('v', 0xF),
             ];
-            KeyMappings::from_pairs(&default_mappings)
+            KeyMappings::from_pairs_with_case(&default_mappings, case_sensitive)
+        }
+    }
+}
+
+/// Abstract buttons an external controller can report
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Button {
+    Up,
+    Down,
+    Left,
+    Right,
+    A,
+    B,
+    X,
+    Y,
+    Start,
+    Select,
+}
+
+impl Button {
+    /// Parse a button from its config name (case-insensitive)
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name.to_ascii_lowercase().as_str() {
+            "up" => Some(Self::Up),
+            "down" => Some(Self::Down),
+            "left" => Some(Self::Left),
+            "right" => Some(Self::Right),
+            "a" => Some(Self::A),
+            "b" => Some(Self::B),
+            "x" => Some(Self::X),
+            "y" => Some(Self::Y),
+            "start" => Some(Self::Start),
+            "select" => Some(Self::Select),
+            _ => None,
+        }
+    }
+}
+
+/// Button events reported by an input source
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ButtonEvent {
+    /// A button was pressed
+    Pressed(Button),
+    /// A button was released
+    Released(Button),
+}
+
+/// External source of button events (e.g. a gamepad backend)
+pub trait InputSource: std::fmt::Debug {
+    /// Return all button events since the last poll
+    fn poll(&mut self) -> Vec<ButtonEvent>;
+}
+
+/// Resolved button mappings for CHIP-8 input
+#[derive(Debug, Clone, Default)]
+pub struct ButtonMappings {
+    /// Mapping from abstract button to CHIP-8 key value (0-15)
+    button_map: HashMap<Button, u8>,
+}
+
+impl ButtonMappings {
+    /// Create button mappings from raw mapping pairs
+    pub fn from_pairs(mappings: &[(Button, u8)]) -> Result<Self, InputError> {
+        let mut button_map = HashMap::new();
+
+        for &(button, chip8_key) in mappings {
+            if !is_valid_key(chip8_key) {
+                return Err(InputError::InvalidKey { key: chip8_key });
+            }
+            button_map.insert(button, chip8_key);
+        }
+
+        Ok(Self { button_map })
+    }
+
+    /// Get the CHIP-8 key for a button
+    pub fn get_chip8_key(&self, button: Button) -> Option<u8> {
+        self.button_map.get(&button).copied()
+    }
+}
+
+/// Resolve button mappings from config or use defaults
+///
+/// Config entries map a button name (e.g. "Up") to a CHIP-8 key hex string.
+pub fn resolve_button_mappings(
+    config_mappings: Option<&HashMap<String, String>>,
+) -> Result<ButtonMappings, InputError> {
+    match config_mappings {
+        Some(mappings) => {
+            let mut converted_mappings = Vec::new();
+
+            for (button_name, chip8_key_str) in mappings {
+                let button =
+                    Button::from_name(button_name).ok_or_else(|| InputError::UnknownButton {
+                        name: button_name.clone(),
+                    })?;
+                let chip8_key = u8::from_str_radix(chip8_key_str, 16)
+                    .map_err(|_| InputError::InvalidKey { key: 255 })?;
+
+                converted_mappings.push((button, chip8_key));
+            }
+
+            ButtonMappings::from_pairs(&converted_mappings)
+        }
+        None => {
+            // Directions on the 2/4/6/8 cross, actions on the center and bottom keys
+            let default_mappings = [
+                (Button::Up, 0x2),
+                (Button::Down, 0x8),
+                (Button::Left, 0x4),
+                (Button::Right, 0x6),
+                (Button::A, 0x5),
+                (Button::B, 0x0),
+            ];
+            ButtonMappings::from_pairs(&default_mappings)
+        }
+    }
+}
+
+/// Policy for choosing which key `try_get_key_press` reports
+///
+/// Both `Input` and `MockInput` apply the same policy to the same press
+/// sequence, so tests written against one behave identically on the other.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum KeySelection {
+    /// Report the lowest-index held key, falling back to the lowest queued press
+    /// (a key reported while held doesn't come back from the queue once released)
+    #[default]
+    LowestIndex,
+    /// Report queued presses in the order they happened, ignoring held state
+    FifoOrder,
+}
+
+impl KeySelection {
+    /// Select a key from the held keys (ascending) and the queue of presses
+    fn select(self, pressed_keys: &[u8], key_queue: &mut VecDeque<u8>) -> Option<u8> {
+        match self {
+            Self::LowestIndex => {
+                if let Some(&lowest) = pressed_keys.first() {
+                    // Reported while held, so its queued press is used up
+                    if let Some(index) = key_queue.iter().position(|&key| key == lowest) {
+                        key_queue.remove(index);
+                    }
+                    return Some(lowest);
+                }
+                let (index, _) = key_queue.iter().enumerate().min_by_key(|&(_, &key)| key)?;
+                key_queue.remove(index)
+            }
+            Self::FifoOrder => key_queue.pop_front(),
         }
     }
 }
@@ -121,7 +313,7 @@ fn is_valid_key(key: u8) -> bool {
 }
 
 /// Key events that can be sent through channels
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum KeyEvent {
     /// A key was pressed
     Pressed(char),
@@ -134,6 +326,14 @@ pub enum KeyEvent {
 pub enum InputError {
     #[error("Invalid key value: {key} (must be 0-15)")]
     InvalidKey { key: u8 },
+
+    #[error("Unknown button: {name}")]
+    UnknownButton { name: String },
+
+    #[error(
+        "Invalid keyboard key: {value:?} (must be a single printable, non-whitespace character)"
+    )]
+    InvalidKeyboardKey { value: String },
 }
 
 /// Trait for input handling - allows for different input backends
@@ -141,6 +341,11 @@ pub trait InputBus {
     /// Check if a specific key is currently pressed
     fn is_key_pressed(&self, key: u8) -> Result<bool, InputError>;
 
+    /// Check if a specific key is up, e.g. to finish a release-edge FX0A
+    fn is_key_released(&self, key: u8) -> Result<bool, InputError> {
+        self.is_key_pressed(key).map(|pressed| !pressed)
+    }
+
     /// Try to get a key press without blocking - returns None if no key available
     fn try_get_key_press(&mut self) -> Option<u8>;
 
@@ -160,14 +365,23 @@ pub struct Input {
     /// Key mappings resolver
     key_mappings: KeyMappings,
 
-    /// Buffer for input events
-    input_buffer: Vec<char>,
+    /// Queue of CHIP-8 keys pressed since they were last reported
+    input_buffer: VecDeque<u8>,
+
+    /// Policy for choosing which key `try_get_key_press` reports
+    key_selection: KeySelection,
 
     /// Whether the system is currently waiting for any key press
     waiting_for_key: bool,
 
     /// Optional receiver for key events from external sources (like display renderer)
     key_receiver: Option<Receiver<KeyEvent>>,
+
+    /// Optional source of abstract button events (like a gamepad)
+    input_source: Option<Box<dyn InputSource>>,
+
+    /// Button to CHIP-8 key mappings for the input source
+    button_mappings: ButtonMappings,
 }
 
 impl Input {
@@ -186,9 +400,63 @@ impl Input {
         Self {
             key_states: [false; 16],
             key_mappings,
-            input_buffer: Vec::new(),
+            input_buffer: VecDeque::new(),
+            key_selection: KeySelection::default(),
             waiting_for_key: false,
             key_receiver,
+            input_source: None,
+            button_mappings: ButtonMappings::default(),
+        }
+    }
+
+    /// Attach a button input source with its button mappings
+    pub fn set_input_source(
+        &mut self,
+        input_source: Box<dyn InputSource>,
+        button_mappings: ButtonMappings,
+    ) {
+        self.input_source = Some(input_source);
+        self.button_mappings = button_mappings;
+    }
+
+    /// Detach the button input source, if any
+    pub fn take_input_source(&mut self) -> Option<Box<dyn InputSource>> {
+        self.input_source.take()
+    }
+
+    /// Press a CHIP-8 key directly (for scripted input)
+    pub fn press_key(&mut self, key: u8) -> Result<(), InputError> {
+        if !is_valid_key(key) {
+            return Err(InputError::InvalidKey { key });
+        }
+        self.key_states[key as usize] = true;
+        self.input_buffer.push_back(key);
+        Ok(())
+    }
+
+    /// Release a CHIP-8 key directly (for scripted input)
+    pub fn release_key(&mut self, key: u8) -> Result<(), InputError> {
+        if !is_valid_key(key) {
+            return Err(InputError::InvalidKey { key });
+        }
+        self.key_states[key as usize] = false;
+        Ok(())
+    }
+
+    /// Process a button event, ignoring buttons without a mapping
+    pub fn process_button_event(&mut self, event: ButtonEvent) {
+        match event {
+            ButtonEvent::Pressed(button) => {
+                if let Some(chip8_key_value) = self.button_mappings.get_chip8_key(button) {
+                    self.key_states[chip8_key_value as usize] = true;
+                    self.input_buffer.push_back(chip8_key_value);
+                }
+            }
+            ButtonEvent::Released(button) => {
+                if let Some(chip8_key_value) = self.button_mappings.get_chip8_key(button) {
+                    self.key_states[chip8_key_value as usize] = false;
+                }
+            }
         }
     }
 
@@ -207,7 +475,7 @@ impl Input {
         if let Some(chip8_key_value) = self.get_chip8_key(ch) {
             self.key_states[chip8_key_value as usize] = true;
             // Add to buffer for key waiting (only when mapped)
-            self.input_buffer.push(ch);
+            self.input_buffer.push_back(chip8_key_value);
         }
     }
 
@@ -218,11 +486,31 @@ impl Input {
         }
     }
 
+    /// Release every key and forget queued presses
+    ///
+    /// Key and button mappings, the key event receiver and any input source
+    /// stay wired up, so custom bindings survive an emulator reset.
+    pub fn reset(&mut self) {
+        self.key_states = [false; 16];
+        self.input_buffer.clear();
+        self.waiting_for_key = false;
+    }
+
     /// Clear the input buffer (for testing)
     pub fn clear_input_buffer(&mut self) {
         self.input_buffer.clear();
     }
 
+    /// Set the policy for choosing which key `try_get_key_press` reports
+    pub fn set_key_selection(&mut self, key_selection: KeySelection) {
+        self.key_selection = key_selection;
+    }
+
+    /// Get the policy for choosing which key `try_get_key_press` reports
+    pub fn get_key_selection(&self) -> KeySelection {
+        self.key_selection
+    }
+
     /// Get input statistics
     pub fn get_stats(&self) -> InputStats {
         let pressed_count = self.key_states.iter().filter(|&&pressed| pressed).count();
@@ -242,29 +530,9 @@ impl Input {
     pub fn print_keypad_state(&self) {
         println!("CHIP-8 Keypad State:");
         println!("┌─────┬─────┬─────┬─────┐");
-        for row in 0..4 {
+        for (row, keys) in KEYPAD_LAYOUT.iter().enumerate() {
             print!("│");
-            for col in 0..4 {
-                let key_value = match (row, col) {
-                    (0, 0) => 0x1,
-                    (0, 1) => 0x2,
-                    (0, 2) => 0x3,
-                    (0, 3) => 0xC,
-                    (1, 0) => 0x4,
-                    (1, 1) => 0x5,
-                    (1, 2) => 0x6,
-                    (1, 3) => 0xD,
-                    (2, 0) => 0x7,
-                    (2, 1) => 0x8,
-                    (2, 2) => 0x9,
-                    (2, 3) => 0xE,
-                    (3, 0) => 0xA,
-                    (3, 1) => 0x0,
-                    (3, 2) => 0xB,
-                    (3, 3) => 0xF,
-                    _ => unreachable!(),
-                };
-
+            for &key_value in keys {
                 let state = if self.key_states[key_value as usize] {
                     "█"
                 } else {
@@ -302,21 +570,10 @@ impl InputBus for Input {
     }
 
     fn try_get_key_press(&mut self) -> Option<u8> {
-        // Check if any key is currently pressed
+        // No key available is a perfectly normal condition
         let pressed_keys = self.get_pressed_keys();
-        if let Some(&first_key) = pressed_keys.first() {
-            return Some(first_key);
-        }
-
-        // Check input buffer for recent key presses
-        while let Some(ch) = self.input_buffer.pop() {
-            if let Some(chip8_key_value) = self.get_chip8_key(ch) {
-                return Some(chip8_key_value);
-            }
-        }
-
-        // No key available - perfectly normal condition
-        None
+        self.key_selection
+            .select(&pressed_keys, &mut self.input_buffer)
     }
 
     fn update(&mut self) {
@@ -335,6 +592,15 @@ impl InputBus for Input {
                 KeyEvent::Released(ch) => self.process_char_release(ch),
             }
         }
+
+        // Pull button events from the attached input source
+        let button_events = match &mut self.input_source {
+            Some(source) => source.poll(),
+            None => Vec::new(),
+        };
+        for event in button_events {
+            self.process_button_event(event);
+        }
     }
 
     fn get_pressed_keys(&self) -> Vec<u8> {
@@ -360,10 +626,17 @@ pub struct InputStats {
 }
 
 /// Mock input for testing - allows programmatic control of key states
+///
+/// `press_key`/`release_key` take effect at once; `queue_press`/`queue_release`
+/// queue events that `update()` applies, like the real `Input` draining its
+/// channel, so a test can hold a key across cycles and let go later.
 #[derive(Debug, Clone, PartialEq)]
 pub struct MockInput {
     key_states: [bool; 16],
     key_queue: VecDeque<u8>,
+    key_selection: KeySelection,
+    /// Events for the next `update()`, naming keys by hex digit
+    pending_events: VecDeque<KeyEvent>,
 }
 
 impl MockInput {
@@ -371,9 +644,32 @@ impl MockInput {
         Self {
             key_states: [false; 16],
             key_queue: VecDeque::new(),
+            key_selection: KeySelection::default(),
+            pending_events: VecDeque::new(),
         }
     }
 
+    /// Queue a press of `key` for the next `update()`
+    pub fn queue_press(&mut self, key: u8) -> Result<(), InputError> {
+        self.queue_event(key, KeyEvent::Pressed)
+    }
+
+    /// Queue a release of `key` for the next `update()`
+    pub fn queue_release(&mut self, key: u8) -> Result<(), InputError> {
+        self.queue_event(key, KeyEvent::Released)
+    }
+
+    fn queue_event(&mut self, key: u8, event: fn(char) -> KeyEvent) -> Result<(), InputError> {
+        let digit = std::char::from_digit(key as u32, 16).ok_or(InputError::InvalidKey { key })?;
+        self.pending_events.push_back(event(digit));
+        Ok(())
+    }
+
+    /// Set the policy for choosing which key `try_get_key_press` reports
+    pub fn set_key_selection(&mut self, key_selection: KeySelection) {
+        self.key_selection = key_selection;
+    }
+
     pub fn press_key(&mut self, key: u8) -> Result<(), InputError> {
         if !is_valid_key(key) {
             return Err(InputError::InvalidKey { key });
@@ -394,6 +690,7 @@ impl MockInput {
     pub fn clear_all_keys(&mut self) {
         self.key_states = [false; 16];
         self.key_queue.clear();
+        self.pending_events.clear();
     }
 }
 
@@ -412,11 +709,21 @@ impl InputBus for MockInput {
     }
 
     fn try_get_key_press(&mut self) -> Option<u8> {
-        self.key_queue.pop_front()
+        let pressed_keys = self.get_pressed_keys();
+        self.key_selection
+            .select(&pressed_keys, &mut self.key_queue)
     }
 
     fn update(&mut self) {
-        // No-op for mock
+        let hex_key = |digit: char| digit.to_digit(16).map_or(u8::MAX, |key| key as u8);
+        for event in std::mem::take(&mut self.pending_events) {
+            let result = match event {
+                KeyEvent::Pressed(digit) => self.press_key(hex_key(digit)),
+                KeyEvent::Released(digit) => self.release_key(hex_key(digit)),
+            };
+            // Keys are validated when queued
+            debug_assert!(result.is_ok());
+        }
     }
 
     fn get_pressed_keys(&self) -> Vec<u8> {
@@ -480,6 +787,58 @@ mod tests {
         assert_eq!(input.get_chip8_key('q'), None);
     }
 
+    #[test]
+    fn test_config_keyboard_keys_are_validated() {
+        let resolve = |value: &str| {
+            let config = HashMap::from([("5".to_string(), value.to_string())]);
+            resolve_key_mappings(Some(&config))
+        };
+
+        let mappings = resolve("k").unwrap();
+        assert_eq!(mappings.get_chip8_key('k'), Some(0x5));
+        for value in [" ", "", "\t", "kl"] {
+            assert_eq!(
+                resolve(value).unwrap_err(),
+                InputError::InvalidKeyboardKey {
+                    value: value.to_string()
+                }
+            );
+        }
+    }
+
+    #[test]
+    fn test_case_sensitive_key_mapping() {
+        let config = HashMap::from([
+            ("4".to_string(), "q".to_string()),
+            ("5".to_string(), "Q".to_string()),
+        ]);
+        let mappings = resolve_key_mappings_with_case(Some(&config), true).unwrap();
+        assert_eq!(mappings.get_chip8_key('q'), Some(0x4));
+        assert_eq!(mappings.get_chip8_key('Q'), Some(0x5));
+
+        let config = HashMap::from([("4".to_string(), "Q".to_string())]);
+        let mappings = resolve_key_mappings_with_case(Some(&config), true).unwrap();
+        assert_eq!(mappings.get_chip8_key('Q'), Some(0x4));
+        assert_eq!(mappings.get_chip8_key('q'), None);
+
+        let mappings = resolve_key_mappings_with_case(Some(&config), false).unwrap();
+        assert_eq!(mappings.get_chip8_key('Q'), Some(0x4));
+        assert_eq!(mappings.get_chip8_key('q'), Some(0x4));
+    }
+
+    #[test]
+    fn test_reset_keeps_mappings() {
+        let key_mappings = KeyMappings::from_pairs(&[('a', 0x5)]).unwrap();
+        let mut input = Input::with_mappings(key_mappings, None);
+        input.process_char_input('a');
+        assert!(input.is_key_pressed(0x5).unwrap());
+
+        input.reset();
+        assert!(!input.is_key_pressed(0x5).unwrap());
+        assert_eq!(input.try_get_key_press(), None);
+        assert_eq!(input.get_chip8_key('a'), Some(0x5));
+    }
+
     #[test]
     fn test_key_validation() {
         // Test valid keys
@@ -609,4 +968,168 @@ mod tests {
         input.clear_input_buffer();
         assert!(input.try_get_key_press().is_none());
     }
+
+    #[test]
+    fn test_key_selection_consistent_across_backends() {
+        for selection in [KeySelection::LowestIndex, KeySelection::FifoOrder] {
+            let mut input = Input::new();
+            let mut mock = MockInput::new();
+            input.set_key_selection(selection);
+            mock.set_key_selection(selection);
+
+            // Press 0x9 ('d') then 0x3 ('3'), then release 0x9
+            input.process_char_input('d');
+            mock.press_key(0x9).unwrap();
+            input.process_char_input('3');
+            mock.press_key(0x3).unwrap();
+            input.process_char_release('d');
+            mock.release_key(0x9).unwrap();
+
+            let from_input: Vec<_> = (0..3).map(|_| input.try_get_key_press()).collect();
+            let from_mock: Vec<_> = (0..3).map(|_| mock.try_get_key_press()).collect();
+            assert_eq!(from_input, from_mock, "{:?}", selection);
+
+            let expected = match selection {
+                // 0x3 stays held, so it is reported every time
+                KeySelection::LowestIndex => vec![Some(0x3), Some(0x3), Some(0x3)],
+                KeySelection::FifoOrder => vec![Some(0x9), Some(0x3), None],
+            };
+            assert_eq!(from_input, expected);
+        }
+    }
+
+    #[test]
+    fn test_lowest_index_falls_back_to_queued_presses() {
+        let mut mock = MockInput::new();
+        mock.press_key(0xB).unwrap();
+        mock.press_key(0x2).unwrap();
+        mock.release_key(0xB).unwrap();
+        mock.release_key(0x2).unwrap();
+
+        assert_eq!(mock.try_get_key_press(), Some(0x2));
+        assert_eq!(mock.try_get_key_press(), Some(0xB));
+        assert_eq!(mock.try_get_key_press(), None);
+    }
+
+    #[test]
+    fn test_lowest_index_does_not_repeat_reported_press() {
+        // Held and reported once: releasing it must not replay the queued press
+        let mut input = Input::new();
+        input.process_char_input('e'); // Key6
+        assert_eq!(input.try_get_key_press(), Some(0x6));
+        input.process_char_release('e');
+        assert_eq!(input.try_get_key_press(), None);
+
+        let mut mock = MockInput::new();
+        mock.press_key(0x6).unwrap();
+        assert_eq!(mock.try_get_key_press(), Some(0x6));
+        mock.release_key(0x6).unwrap();
+        assert_eq!(mock.try_get_key_press(), None);
+
+        // Presses that were never reported while held still come back
+        mock.press_key(0x1).unwrap();
+        mock.press_key(0x8).unwrap();
+        assert_eq!(mock.try_get_key_press(), Some(0x1));
+        mock.release_key(0x1).unwrap();
+        mock.release_key(0x8).unwrap();
+        assert_eq!(mock.try_get_key_press(), Some(0x8));
+        assert_eq!(mock.try_get_key_press(), None);
+    }
+
+    #[test]
+    fn test_mock_queued_press_hold_release() {
+        let mut mock = MockInput::new();
+        mock.queue_press(0x7).unwrap();
+        assert_eq!(
+            mock.queue_release(0x10),
+            Err(InputError::InvalidKey { key: 0x10 })
+        );
+
+        // Nothing happens until update() applies the queue
+        assert!(!mock.is_key_pressed(0x7).unwrap());
+        assert_eq!(mock.try_get_key_press(), None);
+
+        mock.update();
+        assert!(mock.is_key_pressed(0x7).unwrap());
+        assert_eq!(mock.try_get_key_press(), Some(0x7));
+
+        // Held across updates
+        mock.update();
+        assert!(mock.is_key_pressed(0x7).unwrap());
+        assert_eq!(mock.try_get_key_press(), Some(0x7));
+
+        mock.queue_release(0x7).unwrap();
+        assert!(mock.is_key_pressed(0x7).unwrap());
+        mock.update();
+        assert!(mock.is_key_released(0x7).unwrap());
+        // The press was already reported while held, so it doesn't linger
+        assert_eq!(mock.try_get_key_press(), None);
+    }
+
+    #[test]
+    fn test_mock_queued_tap_within_one_update() {
+        let mut mock = MockInput::new();
+        mock.queue_press(0x4).unwrap();
+        mock.queue_release(0x4).unwrap();
+        mock.update();
+
+        // Released by the time anyone looks, but the tap is still reported once
+        assert!(!mock.is_key_pressed(0x4).unwrap());
+        assert_eq!(mock.try_get_key_press(), Some(0x4));
+        assert_eq!(mock.try_get_key_press(), None);
+    }
+
+    /// Input source replaying a fixed list of button events
+    #[derive(Debug)]
+    struct ScriptedSource {
+        events: Vec<ButtonEvent>,
+    }
+
+    impl InputSource for ScriptedSource {
+        fn poll(&mut self) -> Vec<ButtonEvent> {
+            std::mem::take(&mut self.events)
+        }
+    }
+
+    #[test]
+    fn test_button_mapping() {
+        let mappings = resolve_button_mappings(None).unwrap();
+        assert_eq!(mappings.get_chip8_key(Button::Up), Some(0x2));
+        assert_eq!(mappings.get_chip8_key(Button::Right), Some(0x6));
+        assert_eq!(mappings.get_chip8_key(Button::A), Some(0x5));
+        assert_eq!(mappings.get_chip8_key(Button::Start), None);
+
+        let mut config = HashMap::new();
+        config.insert("start".to_string(), "F".to_string());
+        let mappings = resolve_button_mappings(Some(&config)).unwrap();
+        assert_eq!(mappings.get_chip8_key(Button::Start), Some(0xF));
+        assert_eq!(mappings.get_chip8_key(Button::Up), None);
+
+        config.insert("Turbo".to_string(), "1".to_string());
+        assert_eq!(
+            resolve_button_mappings(Some(&config)).unwrap_err(),
+            InputError::UnknownButton {
+                name: "Turbo".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_input_source_drives_keys() {
+        let mut input = Input::new();
+        let source = ScriptedSource {
+            events: vec![
+                ButtonEvent::Pressed(Button::Left),
+                ButtonEvent::Pressed(Button::Select), // Unmapped
+            ],
+        };
+        input.set_input_source(Box::new(source), resolve_button_mappings(None).unwrap());
+
+        input.update();
+        assert_eq!(input.get_pressed_keys(), vec![0x4]);
+
+        input.process_button_event(ButtonEvent::Released(Button::Left));
+        input.process_button_event(ButtonEvent::Released(Button::Select));
+        assert!(input.get_pressed_keys().is_empty());
+    }
 }
@@ -4,8 +4,16 @@
 //! and decoding logic. This ensures consistency between CPU execution and
 //! disassembly, following the DRY principle.
 
+use crate::constants::NUM_RPL_FLAGS;
+use serde::Serialize;
 use thiserror::Error;
 
+/// Fixed part of the DXYN cost in microseconds (operand setup and VF reset)
+const DRAW_SETUP_US: u32 = 170;
+
+/// DXYN cost per sprite row in microseconds (fetch, shift, XOR and collision check)
+const DRAW_ROW_US: u32 = 155;
+
 /// CHIP-8 instruction decode errors
 #[derive(Debug, Error)]
 pub enum DecodeError {
@@ -17,7 +25,7 @@ pub enum DecodeError {
 }
 
 /// All CHIP-8 instructions with their operands
-#[derive(Debug, Clone, PartialEq, Eq)]
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
 pub enum Instruction {
     // System instructions
     /// CLS - Clear the display
@@ -29,6 +37,16 @@ pub enum Instruction {
     /// SYS addr - Jump to a machine code routine at addr (rarely used)
     Sys { addr: u16 },
 
+    // SUPER-CHIP scrolling
+    /// SCD n - Scroll the display down n rows
+    ScrollDown { n: u8 },
+
+    /// SCR - Scroll the display right 4 pixels
+    ScrollRight,
+
+    /// SCL - Scroll the display left 4 pixels
+    ScrollLeft,
+
     // Flow control
     /// JP addr - Jump to location addr
     Jump { addr: u16 },
@@ -132,6 +150,13 @@ pub enum Instruction {
 
     /// LD Vx, [I] - Read registers V0 through Vx from memory starting at location I
     LoadRegisters { vx: usize },
+
+    // SUPER-CHIP user flags
+    /// LD R, Vx - Store registers V0 through Vx in the RPL flags (x <= 7)
+    StoreFlags { vx: usize },
+
+    /// LD Vx, R - Read registers V0 through Vx from the RPL flags (x <= 7)
+    LoadFlags { vx: usize },
 }
 
 impl Instruction {
@@ -141,6 +166,9 @@ impl Instruction {
             Instruction::Cls => "CLS".to_string(),
             Instruction::Ret => "RET".to_string(),
             Instruction::Sys { addr } => format!("SYS {:03X}", addr),
+            Instruction::ScrollDown { n } => format!("SCD {:X}", n),
+            Instruction::ScrollRight => "SCR".to_string(),
+            Instruction::ScrollLeft => "SCL".to_string(),
             Instruction::Jump { addr } => format!("JP {:03X}", addr),
             Instruction::Call { addr } => format!("CALL {:03X}", addr),
             Instruction::JumpV0 { addr } => format!("JP V0, {:03X}", addr),
@@ -173,6 +201,54 @@ impl Instruction {
             Instruction::StoreBcd { vx } => format!("LD B, V{:X}", vx),
             Instruction::StoreRegisters { vx } => format!("LD [I], V{:X}", vx),
             Instruction::LoadRegisters { vx } => format!("LD V{:X}, [I]", vx),
+            Instruction::StoreFlags { vx } => format!("LD R, V{:X}", vx),
+            Instruction::LoadFlags { vx } => format!("LD V{:X}, R", vx),
+        }
+    }
+
+    /// Get the canonical opcode pattern for this instruction (e.g. "DXYN")
+    pub fn pattern(&self) -> &'static str {
+        match self {
+            Instruction::Cls => "00E0",
+            Instruction::Ret => "00EE",
+            Instruction::Sys { .. } => "0NNN",
+            Instruction::ScrollDown { .. } => "00CN",
+            Instruction::ScrollRight => "00FB",
+            Instruction::ScrollLeft => "00FC",
+            Instruction::Jump { .. } => "1NNN",
+            Instruction::Call { .. } => "2NNN",
+            Instruction::SkipEqImm { .. } => "3XNN",
+            Instruction::SkipNeImm { .. } => "4XNN",
+            Instruction::SkipEqReg { .. } => "5XY0",
+            Instruction::LoadImm { .. } => "6XNN",
+            Instruction::AddImm { .. } => "7XNN",
+            Instruction::LoadReg { .. } => "8XY0",
+            Instruction::OrReg { .. } => "8XY1",
+            Instruction::AndReg { .. } => "8XY2",
+            Instruction::XorReg { .. } => "8XY3",
+            Instruction::AddReg { .. } => "8XY4",
+            Instruction::SubReg { .. } => "8XY5",
+            Instruction::ShrReg { .. } => "8XY6",
+            Instruction::SubnReg { .. } => "8XY7",
+            Instruction::ShlReg { .. } => "8XYE",
+            Instruction::SkipNeReg { .. } => "9XY0",
+            Instruction::SetIndex { .. } => "ANNN",
+            Instruction::JumpV0 { .. } => "BNNN",
+            Instruction::Random { .. } => "CXNN",
+            Instruction::Draw { .. } => "DXYN",
+            Instruction::SkipKeyPressed { .. } => "EX9E",
+            Instruction::SkipKeyNotPressed { .. } => "EXA1",
+            Instruction::LoadDelayTimer { .. } => "FX07",
+            Instruction::WaitKey { .. } => "FX0A",
+            Instruction::SetDelayTimer { .. } => "FX15",
+            Instruction::SetSoundTimer { .. } => "FX18",
+            Instruction::AddIndex { .. } => "FX1E",
+            Instruction::LoadFont { .. } => "FX29",
+            Instruction::StoreBcd { .. } => "FX33",
+            Instruction::StoreRegisters { .. } => "FX55",
+            Instruction::LoadRegisters { .. } => "FX65",
+            Instruction::StoreFlags { .. } => "FX75",
+            Instruction::LoadFlags { .. } => "FX85",
         }
     }
 
@@ -192,6 +268,105 @@ impl Instruction {
                 | Instruction::SkipKeyNotPressed { .. }
         )
     }
+
+    /// Approximate execution time on the COSMAC VIP, in microseconds
+    ///
+    /// Figures follow Jackson Sommerich's measured VIP instruction timings;
+    /// skips report the not-taken cost and FX0A excludes the wait for a key.
+    /// That table gives DXYN as a single vblank-inclusive average, so the cost
+    /// here is modelled as a setup cost plus a cost per sprite row instead;
+    /// the wait for vertical blank is not modelled. The SUPER-CHIP scrolls
+    /// have no VIP timing and are costed like CLS, another full-screen pass;
+    /// FX75/FX85 are costed like the FX55/FX65 register transfers.
+    pub fn cycle_cost(&self) -> u32 {
+        match self {
+            Instruction::Cls => 109,
+            Instruction::ScrollDown { .. } | Instruction::ScrollRight | Instruction::ScrollLeft => {
+                109
+            }
+            Instruction::Ret | Instruction::Jump { .. } | Instruction::Call { .. } => 105,
+            Instruction::Sys { .. } | Instruction::JumpV0 { .. } => 105,
+            Instruction::SkipEqImm { .. } | Instruction::SkipNeImm { .. } => 55,
+            Instruction::SkipEqReg { .. } | Instruction::SkipNeReg { .. } => 73,
+            Instruction::SkipKeyPressed { .. } | Instruction::SkipKeyNotPressed { .. } => 73,
+            Instruction::LoadImm { .. } => 27,
+            Instruction::AddImm { .. } => 45,
+            Instruction::LoadReg { .. }
+            | Instruction::OrReg { .. }
+            | Instruction::AndReg { .. }
+            | Instruction::XorReg { .. }
+            | Instruction::AddReg { .. }
+            | Instruction::SubReg { .. }
+            | Instruction::ShrReg { .. }
+            | Instruction::SubnReg { .. }
+            | Instruction::ShlReg { .. } => 200,
+            Instruction::SetIndex { .. } => 55,
+            Instruction::Random { .. } => 164,
+            Instruction::Draw { n, .. } => DRAW_SETUP_US + DRAW_ROW_US * *n as u32,
+            Instruction::LoadDelayTimer { .. }
+            | Instruction::WaitKey { .. }
+            | Instruction::SetDelayTimer { .. }
+            | Instruction::SetSoundTimer { .. } => 45,
+            Instruction::AddIndex { .. } => 86,
+            Instruction::LoadFont { .. } => 91,
+            Instruction::StoreBcd { .. } => 927,
+            Instruction::StoreRegisters { .. } | Instruction::LoadRegisters { .. } => 605,
+            Instruction::StoreFlags { .. } | Instruction::LoadFlags { .. } => 605,
+        }
+    }
+
+    /// Encode this instruction back into its 16-bit opcode
+    ///
+    /// The inverse of [`decode_opcode`], except that SHR/SHL don't keep the
+    /// ignored Y operand and always encode it as 0.
+    pub fn opcode(&self) -> u16 {
+        let xy = |vx: usize, vy: usize| ((vx as u16) << 8) | ((vy as u16) << 4);
+        let xkk = |vx: usize, byte: u8| ((vx as u16) << 8) | byte as u16;
+        let x = |vx: usize| (vx as u16) << 8;
+
+        match *self {
+            Instruction::Cls => 0x00E0,
+            Instruction::Ret => 0x00EE,
+            Instruction::Sys { addr } => addr,
+            Instruction::ScrollDown { n } => 0x00C0 | n as u16,
+            Instruction::ScrollRight => 0x00FB,
+            Instruction::ScrollLeft => 0x00FC,
+            Instruction::Jump { addr } => 0x1000 | addr,
+            Instruction::Call { addr } => 0x2000 | addr,
+            Instruction::SkipEqImm { vx, value } => 0x3000 | xkk(vx, value),
+            Instruction::SkipNeImm { vx, value } => 0x4000 | xkk(vx, value),
+            Instruction::SkipEqReg { vx, vy } => 0x5000 | xy(vx, vy),
+            Instruction::LoadImm { vx, value } => 0x6000 | xkk(vx, value),
+            Instruction::AddImm { vx, value } => 0x7000 | xkk(vx, value),
+            Instruction::LoadReg { vx, vy } => 0x8000 | xy(vx, vy),
+            Instruction::OrReg { vx, vy } => 0x8001 | xy(vx, vy),
+            Instruction::AndReg { vx, vy } => 0x8002 | xy(vx, vy),
+            Instruction::XorReg { vx, vy } => 0x8003 | xy(vx, vy),
+            Instruction::AddReg { vx, vy } => 0x8004 | xy(vx, vy),
+            Instruction::SubReg { vx, vy } => 0x8005 | xy(vx, vy),
+            Instruction::ShrReg { vx } => 0x8006 | x(vx),
+            Instruction::SubnReg { vx, vy } => 0x8007 | xy(vx, vy),
+            Instruction::ShlReg { vx } => 0x800E | x(vx),
+            Instruction::SkipNeReg { vx, vy } => 0x9000 | xy(vx, vy),
+            Instruction::SetIndex { addr } => 0xA000 | addr,
+            Instruction::JumpV0 { addr } => 0xB000 | addr,
+            Instruction::Random { vx, mask } => 0xC000 | xkk(vx, mask),
+            Instruction::Draw { vx, vy, n } => 0xD000 | xy(vx, vy) | n as u16,
+            Instruction::SkipKeyPressed { vx } => 0xE09E | x(vx),
+            Instruction::SkipKeyNotPressed { vx } => 0xE0A1 | x(vx),
+            Instruction::LoadDelayTimer { vx } => 0xF007 | x(vx),
+            Instruction::WaitKey { vx } => 0xF00A | x(vx),
+            Instruction::SetDelayTimer { vx } => 0xF015 | x(vx),
+            Instruction::SetSoundTimer { vx } => 0xF018 | x(vx),
+            Instruction::AddIndex { vx } => 0xF01E | x(vx),
+            Instruction::LoadFont { vx } => 0xF029 | x(vx),
+            Instruction::StoreBcd { vx } => 0xF033 | x(vx),
+            Instruction::StoreRegisters { vx } => 0xF055 | x(vx),
+            Instruction::LoadRegisters { vx } => 0xF065 | x(vx),
+            Instruction::StoreFlags { vx } => 0xF075 | x(vx),
+            Instruction::LoadFlags { vx } => 0xF085 | x(vx),
+        }
+    }
 }
 
 /// Decode a 16-bit opcode into an Instruction
@@ -229,6 +404,9 @@ pub fn decode_opcode(opcode: u16) -> Result<Instruction, DecodeError> {
         0x0000 => match opcode {
             0x00E0 => Ok(Instruction::Cls),
             0x00EE => Ok(Instruction::Ret),
+            0x00C0..=0x00CF => Ok(Instruction::ScrollDown { n: nibble }),
+            0x00FB => Ok(Instruction::ScrollRight),
+            0x00FC => Ok(Instruction::ScrollLeft),
             _ => Ok(Instruction::Sys { addr }),
         },
         0x1000 => Ok(Instruction::Jump { addr }),
@@ -276,12 +454,56 @@ pub fn decode_opcode(opcode: u16) -> Result<Instruction, DecodeError> {
             0x33 => Ok(Instruction::StoreBcd { vx }),
             0x55 => Ok(Instruction::StoreRegisters { vx }),
             0x65 => Ok(Instruction::LoadRegisters { vx }),
+            0x75 if vx < NUM_RPL_FLAGS => Ok(Instruction::StoreFlags { vx }),
+            0x85 if vx < NUM_RPL_FLAGS => Ok(Instruction::LoadFlags { vx }),
             _ => Err(DecodeError::UnknownInstruction { opcode }),
         },
         _ => Err(DecodeError::UnknownInstruction { opcode }),
     }
 }
 
+/// Decode an opcode, repairing bit-rot in the low byte of key-skip opcodes
+///
+/// An `EXxx` with an unknown low byte decodes as whichever of `EX9E`/`EXA1`
+/// is fewer flipped bits away (`EX9E` on a tie). Everything else decodes
+/// exactly as [`decode_opcode`] does.
+pub fn decode_opcode_lenient(opcode: u16) -> Result<Instruction, DecodeError> {
+    match decode_opcode(opcode) {
+        Err(DecodeError::UnknownInstruction { .. }) if opcode & 0xF000 == 0xE000 => {
+            decode_opcode((opcode & 0xFF00) | nearest_key_skip_byte(opcode as u8) as u16)
+        }
+        result => result,
+    }
+}
+
+/// Get the mnemonic for an opcode, or `.db 0xNNNN` if it doesn't decode
+///
+/// Never fails, so memory views can label every word without checking
+/// whether it is code or data first.
+pub fn opcode_mnemonic(opcode: u16) -> String {
+    match decode_opcode(opcode) {
+        Ok(instruction) => instruction.mnemonic(),
+        Err(_) => format!(".db 0x{:04X}", opcode),
+    }
+}
+
+/// Pick the key-skip low byte (0x9E or 0xA1) with the smallest Hamming distance to `byte`
+fn nearest_key_skip_byte(byte: u8) -> u8 {
+    if (byte ^ 0x9E).count_ones() <= (byte ^ 0xA1).count_ones() {
+        0x9E
+    } else {
+        0xA1
+    }
+}
+
+/// Decode every possible 16-bit opcode, in ascending order
+///
+/// Useful for cross-checking the decoder against the spec: the full table
+/// shows exactly which opcodes decode and which are rejected.
+pub fn decode_all() -> impl Iterator<Item = (u16, Result<Instruction, DecodeError>)> {
+    (0..=u16::MAX).map(|opcode| (opcode, decode_opcode(opcode)))
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -309,6 +531,63 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_decode_all_golden_counts() {
+        // Successfully decoded opcodes per high nibble. Each nibble covers
+        // 4096 opcodes; the counts only change when the decoder gains or
+        // loses an opcode.
+        const EXPECTED: [usize; 16] = [
+            4096,           // 0NNN: CLS, RET, SCD, SCR, SCL, everything else SYS
+            4096,           // 1NNN: JP
+            4096,           // 2NNN: CALL
+            4096,           // 3XKK: SE Vx, byte
+            4096,           // 4XKK: SNE Vx, byte
+            256,            // 5XY0 only
+            4096,           // 6XKK: LD Vx, byte
+            4096,           // 7XKK: ADD Vx, byte
+            9 * 256,        // 8XY0-8XY7 and 8XYE
+            256,            // 9XY0 only
+            4096,           // ANNN: LD I
+            4096,           // BNNN: JP V0
+            4096,           // CXKK: RND
+            4096,           // DXYN: DRW
+            2 * 16,         // EX9E and EXA1
+            9 * 16 + 2 * 8, // FX07, FX0A, FX15, FX18, FX1E, FX29, FX33, FX55, FX65; FX75/FX85 for X <= 7
+        ];
+
+        let mut decoded = [0usize; 16];
+        let mut total = 0;
+        for (opcode, result) in decode_all() {
+            total += 1;
+            match result {
+                Ok(_) => decoded[(opcode >> 12) as usize] += 1,
+                Err(e) => assert!(
+                    matches!(e, DecodeError::UnknownInstruction { opcode: o } if o == opcode),
+                    "unexpected error for {:#06x}: {}",
+                    opcode,
+                    e
+                ),
+            }
+        }
+
+        assert_eq!(total, 0x10000);
+        assert_eq!(decoded, EXPECTED);
+        assert_eq!(decoded.iter().sum::<usize>(), 48064);
+    }
+
+    #[test]
+    fn test_opcode_inverts_decode() {
+        for (opcode, result) in decode_all() {
+            let Ok(instruction) = result else { continue };
+            let expected = match instruction {
+                // SHR/SHL drop the unused Y nibble
+                Instruction::ShrReg { .. } | Instruction::ShlReg { .. } => opcode & 0xFF0F,
+                _ => opcode,
+            };
+            assert_eq!(instruction.opcode(), expected, "{:#06x}", opcode);
+        }
+    }
+
     #[test]
     fn test_decode_arithmetic() {
         assert_eq!(
@@ -333,6 +612,45 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_decode_super_chip_scrolls() {
+        assert_eq!(
+            decode_opcode(0x00C3).unwrap(),
+            Instruction::ScrollDown { n: 3 }
+        );
+        assert_eq!(decode_opcode(0x00FB).unwrap(), Instruction::ScrollRight);
+        assert_eq!(decode_opcode(0x00FC).unwrap(), Instruction::ScrollLeft);
+        assert_eq!(Instruction::ScrollDown { n: 0xA }.mnemonic(), "SCD A");
+        assert_eq!(Instruction::ScrollRight.pattern(), "00FB");
+
+        // Neighbouring opcodes are still SYS calls
+        assert_eq!(
+            decode_opcode(0x00D0).unwrap(),
+            Instruction::Sys { addr: 0x0D0 }
+        );
+    }
+
+    #[test]
+    fn test_decode_super_chip_flags() {
+        assert_eq!(
+            decode_opcode(0xF375).unwrap(),
+            Instruction::StoreFlags { vx: 3 }
+        );
+        assert_eq!(
+            decode_opcode(0xF785).unwrap(),
+            Instruction::LoadFlags { vx: 7 }
+        );
+        assert_eq!(Instruction::StoreFlags { vx: 3 }.mnemonic(), "LD R, V3");
+        assert_eq!(Instruction::LoadFlags { vx: 7 }.mnemonic(), "LD V7, R");
+
+        // Only eight flags exist
+        assert!(matches!(
+            decode_opcode(0xF875),
+            Err(DecodeError::UnknownInstruction { opcode: 0xF875 })
+        ));
+        assert!(decode_opcode(0xFF85).is_err());
+    }
+
     #[test]
     fn test_decode_unknown_instruction() {
         assert!(matches!(
@@ -341,6 +659,45 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_nearest_key_skip_byte() {
+        assert_eq!(nearest_key_skip_byte(0x9E), 0x9E);
+        assert_eq!(nearest_key_skip_byte(0xA1), 0xA1);
+        assert_eq!(nearest_key_skip_byte(0x9F), 0x9E); // 1 bit from 9E, 5 from A1
+        assert_eq!(nearest_key_skip_byte(0xA0), 0xA1); // 1 bit from A1
+        assert_eq!(nearest_key_skip_byte(0x00), 0xA1); // 5 vs 3 bits
+        assert_eq!(nearest_key_skip_byte(0x99), 0x9E); // 3 bits from each: tie goes to 9E
+    }
+
+    #[test]
+    fn test_lenient_decode() {
+        // Strict decode rejects the near misses
+        assert!(matches!(
+            decode_opcode(0xE39F),
+            Err(DecodeError::UnknownInstruction { opcode: 0xE39F })
+        ));
+
+        assert_eq!(
+            decode_opcode_lenient(0xE39F).unwrap(),
+            Instruction::SkipKeyPressed { vx: 3 }
+        );
+        assert_eq!(
+            decode_opcode_lenient(0xE4A0).unwrap(),
+            Instruction::SkipKeyNotPressed { vx: 4 }
+        );
+
+        // Valid opcodes and non-E unknowns are untouched
+        assert_eq!(
+            decode_opcode_lenient(0xE5A1).unwrap(),
+            Instruction::SkipKeyNotPressed { vx: 5 }
+        );
+        assert!(matches!(
+            decode_opcode_lenient(0xFF00),
+            Err(DecodeError::UnknownInstruction { opcode: 0xFF00 })
+        ));
+        assert!(decode_opcode_lenient(0x5001).is_err());
+    }
+
     #[test]
     fn test_mnemonic_generation() {
         assert_eq!(Instruction::Cls.mnemonic(), "CLS");
@@ -355,6 +712,22 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_opcode_mnemonic() {
+        assert_eq!(opcode_mnemonic(0xD125), "DRW V1, V2, 5");
+        assert_eq!(opcode_mnemonic(0x00E0), "CLS");
+        assert_eq!(opcode_mnemonic(0xFFFF), ".db 0xFFFF");
+        assert_eq!(opcode_mnemonic(0x5121), ".db 0x5121");
+    }
+
+    #[test]
+    fn test_pattern_generation() {
+        assert_eq!(decode_opcode(0x00E0).unwrap().pattern(), "00E0");
+        assert_eq!(decode_opcode(0xD125).unwrap().pattern(), "DXYN");
+        assert_eq!(decode_opcode(0x812E).unwrap().pattern(), "8XYE");
+        assert_eq!(decode_opcode(0xF365).unwrap().pattern(), "FX65");
+    }
+
     #[test]
     fn test_skip_instruction_detection() {
         assert!(Instruction::SkipEqImm { vx: 0, value: 42 }.is_skip_instruction());
@@ -363,4 +736,20 @@ mod tests {
         assert!(!Instruction::Jump { addr: 0x200 }.is_skip_instruction());
         assert!(!Instruction::LoadImm { vx: 0, value: 42 }.is_skip_instruction());
     }
+
+    #[test]
+    fn test_draw_cost_scales_with_sprite_height() {
+        let short = Instruction::Draw { vx: 0, vy: 1, n: 1 }.cycle_cost();
+        let tall = Instruction::Draw {
+            vx: 0,
+            vy: 1,
+            n: 15,
+        }
+        .cycle_cost();
+        assert!(tall > short);
+        assert_eq!(tall - short, DRAW_ROW_US * 14);
+
+        // Even a one-row draw costs more than a register load
+        assert!(short > Instruction::LoadImm { vx: 0, value: 1 }.cycle_cost());
+    }
 }
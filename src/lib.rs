@@ -20,10 +20,16 @@
 //! - [`Cpu`] - Instruction execution and register management ✅
 //! - [`Display`] - 64x32 framebuffer with sprite operations ✅
 //! - [`RatatuiRenderer`] - Rich terminal UI with interactive display ✅
+//! - [`Renderer`] - Pluggable rendering backends, with [`AsciiRenderer`] and [`HeadlessRenderer`] ✅
 //! - [`Input`] - 16-key keypad handling ✅
-//! - [`Emulator`] - Main coordination and timing ✅
+//! - [`Emulator`] - Main coordination and timing ✅ (save states behind the `save-state` feature)
 //! - [`Config`] - Configuration management and persistence ✅
-//! - [`Audio`] - Sound timer and beep generation (TODO)
+//! - [`audio`] - Sound timer beeping ✅ (speaker output behind the `audio` feature)
+//! - [`clock`] - Pluggable time source for deterministic timing tests ✅
+//! - [`assembler`] - Mnemonic source back to ROM bytes ✅
+//! - [`replay`] - Input recording and playback for reproducible runs ✅
+//! - [`random`] - RND byte sources, including scripted sequences ✅
+//! - `gif` - Animated GIF recording of the display ✅ (behind the `gif` feature)
 //!
 //! # Quick Start
 //!
@@ -51,6 +57,7 @@
 //!     cycle_delay_ms: 10,
 //!     verbose: true,
 //!     write_protection: true,
+//!     ..Default::default()
 //! };
 //!
 //! let mut emulator = Emulator::new(config);
@@ -80,39 +87,71 @@
 //! - ✅ Trait-based architecture for extensible rendering backends
 //! - ✅ Comprehensive error handling with rich context
 //! - ✅ 16-key hexadecimal keypad input with customizable key mapping
-//! - 🚧 Sound timer with beep generation (TODO)
+//! - ✅ Sound timer with beep generation
 //! - 🚧 Complete instruction set (remaining opcodes)
 
+pub mod assembler;
+pub mod audio;
+pub mod clock;
 pub mod config;
 pub mod cpu;
 pub mod disassembler;
 pub mod display;
 pub mod emulator;
+#[cfg(feature = "gif")]
+pub mod gif;
 pub mod input;
 pub mod instruction;
 pub mod memory;
+pub mod random;
+pub mod replay;
 pub mod rom_loader;
-// pub mod audio;
 
 // Re-export main types for convenience
+pub use assembler::{AssembleError, assemble};
+#[cfg(feature = "audio")]
+pub use audio::Audio;
+pub use audio::{AudioError, AudioOutput, NullAudio, effective_beep_duration};
+pub use clock::{Clock, MockClock, SystemClock, TickScheduler};
 pub use config::{
-    Config, ConfigError, ConfigManager, DisplaySettings, EmulatorSettings, InputSettings,
+    AudioSettings, Config, ConfigError, ConfigManager, DisplaySettings, EmulatorSettings,
+    InputSettings,
+};
+pub use cpu::{
+    BreakReason, Cpu, CpuError, CpuSnapshot, CpuState, IndexIncrementMode, LastInstruction,
 };
-pub use cpu::{Cpu, CpuError, CpuState};
 pub use disassembler::{
-    InstructionAnalysis, analyze_instruction_usage, disassemble_rom, print_disassembly,
+    DataKind, DataRegion, DisassembledInstruction, InstructionAnalysis, InstructionFamily,
+    analyze_instruction_usage, branch_targets, classify_as_sprite, disassemble_rom,
+    disassembly_to_json, emit_asm, find_data_regions, print_disassembly, sprite_preview,
+    trace_disassemble,
 };
 pub use display::{
-    ControlAction, Display, DisplayBus, DisplayError, DisplayStats, RatatuiConfig, RatatuiRenderer,
-    RendererError,
+    AsciiRenderer, ControlAction, Display, DisplayBus, DisplayError, DisplayStats, DrawMode,
+    FrameLimiter, HeadlessRenderer, RatatuiConfig, RatatuiRenderer, Renderer, RendererError,
+    disassembly_view, instruction_color,
+};
+pub use emulator::{
+    DEFAULT_TRACE_DEPTH, Emulator, EmulatorConfig, EmulatorError, EmulatorStats, ErrorPolicy,
+    FrameCallback, HaltReason, QuirkProfile, QuirkSettings, RunReport, TraceEntry, TraceHook,
 };
-pub use emulator::{Emulator, EmulatorConfig, EmulatorError, EmulatorStats};
+#[cfg(feature = "gif")]
+pub use gif::GifRecorder;
 pub use input::{
-    Input, InputBus, InputError, InputStats, KeyMappings, MockInput, resolve_key_mappings,
+    Button, ButtonEvent, ButtonMappings, Input, InputBus, InputError, InputSource, InputStats,
+    KEYPAD_LAYOUT, KeyMappings, KeySelection, MockInput, resolve_button_mappings,
+    resolve_key_mappings, resolve_key_mappings_with_case,
+};
+pub use instruction::{
+    DecodeError, Instruction, decode_all, decode_opcode, decode_opcode_lenient, opcode_mnemonic,
+};
+pub use memory::{FontSet, Memory, MemoryBus, MemoryError, MemoryStats};
+pub use random::{FileSequence, RandomError, RandomSource, SequenceEnd};
+pub use replay::{InputLog, InputPlayer, InputRecorder, KeypadEvent};
+pub use rom_loader::{
+    BUILTIN_LOGO_ROM, METADATA_MARKER, RomLoaderConfig, RomMetadata, RomSource, load_rom_data,
+    load_rom_data_with_config, load_rom_data_with_progress, split_metadata,
 };
-pub use instruction::{DecodeError, Instruction, decode_opcode};
-pub use memory::{Memory, MemoryBus, MemoryError, MemoryStats};
-pub use rom_loader::{RomLoaderConfig, RomSource, load_rom_data, load_rom_data_with_config};
 
 /// Result type alias using anyhow for convenience
 pub type Result<T> = anyhow::Result<T>;
@@ -140,6 +179,9 @@ pub mod constants {
     /// Stack depth (16 levels)
     pub const STACK_SIZE: usize = 16;
 
+    /// Number of SUPER-CHIP RPL user flags (FX75/FX85)
+    pub const NUM_RPL_FLAGS: usize = 8;
+
     /// Number of keys on keypad
     pub const NUM_KEYS: usize = 16;
 
@@ -4,8 +4,13 @@
 //! Provides write protection for the interpreter area with optional override.
 
 use crate::constants::*;
+use serde::{Deserialize, Serialize};
+use std::path::Path;
 use thiserror::Error;
 
+/// Bytes per [`Memory::hexdump`] line
+const HEXDUMP_WIDTH: usize = 16;
+
 /// Memory bus trait for CPU to interact with memory system
 pub trait MemoryBus {
     /// Read a single byte from memory
@@ -29,7 +34,7 @@ pub const MAX_ROM_SIZE: usize = MEMORY_SIZE - PROGRAM_START_ADDR as usize;
 
 /// Built-in hexadecimal font set (0-F)
 /// Each character is 4×5 pixels, represented as 5 bytes
-const FONT_SET: [u8; FONT_SET_SIZE] = [
+const STANDARD_FONT: [u8; FONT_SET_SIZE] = [
     // 0
     0xF0, 0x90, 0x90, 0x90, 0xF0, // 1
     0x20, 0x60, 0x20, 0x20, 0x70, // 2
@@ -49,6 +54,70 @@ const FONT_SET: [u8; FONT_SET_SIZE] = [
     0xF0, 0x80, 0xF0, 0x80, 0x80,
 ];
 
+/// Font set of the original COSMAC VIP interpreter
+const COSMAC_VIP_FONT: [u8; FONT_SET_SIZE] = [
+    0xF0, 0x90, 0x90, 0x90, 0xF0, // 0
+    0x60, 0x20, 0x20, 0x20, 0x70, // 1
+    0xF0, 0x10, 0xF0, 0x80, 0xF0, // 2
+    0xF0, 0x10, 0xF0, 0x10, 0xF0, // 3
+    0xA0, 0xA0, 0xF0, 0x20, 0x20, // 4
+    0xF0, 0x80, 0xF0, 0x10, 0xF0, // 5
+    0xF0, 0x80, 0xF0, 0x90, 0xF0, // 6
+    0xF0, 0x10, 0x10, 0x10, 0x10, // 7
+    0xF0, 0x90, 0xF0, 0x90, 0xF0, // 8
+    0xF0, 0x90, 0xF0, 0x10, 0xF0, // 9
+    0xF0, 0x90, 0xF0, 0x90, 0x90, // A
+    0xF0, 0x50, 0x70, 0x50, 0xF0, // B
+    0xF0, 0x80, 0x80, 0x80, 0xF0, // C
+    0xF0, 0x50, 0x50, 0x50, 0xF0, // D
+    0xF0, 0x80, 0xF0, 0x80, 0xF0, // E
+    0xF0, 0x80, 0xF0, 0x80, 0x80, // F
+];
+
+/// Small (3×5 glyph) font set of the SUPER-CHIP interpreter
+const SUPER_CHIP_FONT: [u8; FONT_SET_SIZE] = [
+    0x60, 0xA0, 0xA0, 0xA0, 0xC0, // 0
+    0x40, 0xC0, 0x40, 0x40, 0xE0, // 1
+    0xC0, 0x20, 0x40, 0x80, 0xE0, // 2
+    0xC0, 0x20, 0x40, 0x20, 0xC0, // 3
+    0x20, 0xA0, 0xE0, 0x20, 0x20, // 4
+    0xE0, 0x80, 0xC0, 0x20, 0xC0, // 5
+    0x40, 0x80, 0xC0, 0xA0, 0x40, // 6
+    0xE0, 0x20, 0x60, 0x40, 0x40, // 7
+    0x40, 0xA0, 0x40, 0xA0, 0x40, // 8
+    0x40, 0xA0, 0x60, 0x20, 0x40, // 9
+    0x40, 0xA0, 0xE0, 0xA0, 0xA0, // A
+    0xC0, 0xA0, 0xC0, 0xA0, 0xC0, // B
+    0x60, 0x80, 0x80, 0x80, 0x60, // C
+    0xC0, 0xA0, 0xA0, 0xA0, 0xC0, // D
+    0xE0, 0x80, 0xC0, 0x80, 0xE0, // E
+    0xE0, 0x80, 0xC0, 0x80, 0x80, // F
+];
+
+/// Selectable hexadecimal font sets, for ROMs that expect a specific glyph shape
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FontSet {
+    /// The common modern font set
+    #[default]
+    Standard,
+    /// The original COSMAC VIP font set
+    CosmacVip,
+    /// The SUPER-CHIP small font set
+    SuperChip,
+}
+
+impl FontSet {
+    /// Get the 80 bytes of glyph data for this font set
+    pub fn glyphs(self) -> &'static [u8; FONT_SET_SIZE] {
+        match self {
+            Self::Standard => &STANDARD_FONT,
+            Self::CosmacVip => &COSMAC_VIP_FONT,
+            Self::SuperChip => &SUPER_CHIP_FONT,
+        }
+    }
+}
+
 /// Memory errors
 #[derive(Debug, Error)]
 pub enum MemoryError {
@@ -72,11 +141,23 @@ pub enum MemoryError {
 }
 
 /// CHIP-8 Memory system
+#[derive(Serialize, Deserialize)]
 pub struct Memory {
     /// 4KB RAM
+    #[serde(with = "ram_bytes")]
     ram: [u8; MEMORY_SIZE],
     /// Write protection for interpreter area (0x000-0x1FF)
     write_protection_enabled: bool,
+    /// Font set loaded at FONT_START_ADDR
+    font: FontSet,
+    /// Size in bytes of the last ROM loaded at PROGRAM_START_ADDR
+    rom_len: usize,
+    /// Record the highest address written (tooling aid, not machine state)
+    #[serde(skip)]
+    track_writes: bool,
+    /// Highest address written since tracking started or the last reset
+    #[serde(skip)]
+    highest_write: Option<u16>,
 }
 
 impl Memory {
@@ -85,6 +166,10 @@ impl Memory {
         let mut memory = Self {
             ram: [0; MEMORY_SIZE],
             write_protection_enabled,
+            font: FontSet::default(),
+            rom_len: 0,
+            track_writes: false,
+            highest_write: None,
         };
 
         // Load font data at the standard location
@@ -92,11 +177,22 @@ impl Memory {
         memory
     }
 
-    /// Load the built-in font set into memory at FONT_START_ADDR
+    /// Load the selected font set into memory at FONT_START_ADDR
     fn load_font_data(&mut self) {
         let start = FONT_START_ADDR as usize;
         let end = start + FONT_SET_SIZE;
-        self.ram[start..end].copy_from_slice(&FONT_SET);
+        self.ram[start..end].copy_from_slice(self.font.glyphs());
+    }
+
+    /// Select the font set and load it into memory (kept across resets)
+    pub fn set_font(&mut self, font: FontSet) {
+        self.font = font;
+        self.load_font_data();
+    }
+
+    /// Get the selected font set
+    pub fn get_font(&self) -> FontSet {
+        self.font
     }
 
     /// Read a single byte from memory
@@ -126,6 +222,9 @@ impl Memory {
         }
 
         self.ram[addr as usize] = value;
+        if self.track_writes {
+            self.highest_write = Some(self.highest_write.map_or(addr, |high| high.max(addr)));
+        }
         Ok(())
     }
 
@@ -163,6 +262,7 @@ impl Memory {
         let start = PROGRAM_START_ADDR as usize;
         let end = start + rom_data.len();
         self.ram[start..end].copy_from_slice(rom_data);
+        self.rom_len = rom_data.len();
 
         Ok(())
     }
@@ -199,20 +299,97 @@ impl Memory {
         self.write_protection_enabled
     }
 
+    /// Enable or disable recording of the highest address written (the RAM
+    /// high-water mark); ROM loading does not count as a write
+    pub fn set_write_tracking(&mut self, enabled: bool) {
+        self.track_writes = enabled;
+    }
+
+    /// Check if the RAM high-water mark is being recorded
+    pub fn is_write_tracking_enabled(&self) -> bool {
+        self.track_writes
+    }
+
+    /// Get the highest address written while tracking, or None if nothing was
+    pub fn highest_write(&self) -> Option<u16> {
+        self.highest_write
+    }
+
+    /// Get how many bytes of work RAM past the loaded ROM the program has
+    /// used, judging by the high-water mark
+    pub fn work_ram_used(&self) -> usize {
+        let rom_end = PROGRAM_START_ADDR as usize + self.rom_len;
+        self.highest_write
+            .map_or(0, |high| (high as usize + 1).saturating_sub(rom_end))
+    }
+
+    /// Run `f` with write protection disabled, restoring the previous setting
+    /// afterward (even if `f` panics)
+    pub fn with_protection_disabled<F, R>(&mut self, f: F) -> R
+    where
+        F: FnOnce(&mut Memory) -> R,
+    {
+        let guard = ProtectionGuard::new(self);
+        f(guard.memory)
+    }
+
     /// Clear all memory (except font data)
     pub fn reset(&mut self) {
         // Clear everything
         self.ram.fill(0);
+        self.rom_len = 0;
+        self.highest_write = None;
         // Reload font data
         self.load_font_data();
     }
 
+    /// Get the size in bytes of the loaded ROM (0 if none is loaded)
+    pub fn rom_len(&self) -> usize {
+        self.rom_len
+    }
+
     /// Get a read-only view of the entire memory
     /// Useful for debugging and testing
     pub fn as_slice(&self) -> &[u8] {
         &self.ram
     }
 
+    /// Write the raw 4KB of memory to a file
+    pub fn dump_to_file(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        std::fs::write(path, self.ram)
+    }
+
+    /// Format `len` bytes from `start` like `xxd`: address, 16 hex bytes and
+    /// an ASCII gutter per line, with non-printable bytes shown as `.`
+    ///
+    /// The range is cut off at the end of memory.
+    pub fn hexdump(&self, start: u16, len: usize) -> String {
+        let start = (start as usize).min(MEMORY_SIZE);
+        let end = start.saturating_add(len).min(MEMORY_SIZE);
+        let mut text = String::new();
+        for (line, chunk) in self.ram[start..end].chunks(HEXDUMP_WIDTH).enumerate() {
+            let hex: Vec<String> = chunk.iter().map(|byte| format!("{:02X}", byte)).collect();
+            let ascii: String = chunk
+                .iter()
+                .map(|&byte| {
+                    if byte.is_ascii_graphic() || byte == b' ' {
+                        byte as char
+                    } else {
+                        '.'
+                    }
+                })
+                .collect();
+            text.push_str(&format!(
+                "{:04X}: {:<width$}  {}\n",
+                start + line * HEXDUMP_WIDTH,
+                hex.join(" "),
+                ascii,
+                width = HEXDUMP_WIDTH * 3 - 1
+            ));
+        }
+        text
+    }
+
     /// Get memory usage statistics
     pub fn get_stats(&self) -> MemoryStats {
         MemoryStats {
@@ -222,10 +399,56 @@ impl Memory {
             program_start: PROGRAM_START_ADDR,
             max_rom_size: MAX_ROM_SIZE,
             write_protection_enabled: self.write_protection_enabled,
+            highest_write: self.highest_write,
+            work_ram_used: self.work_ram_used(),
         }
     }
 }
 
+/// Serde support for the 4KB RAM array (serde only derives arrays up to 32 elements)
+mod ram_bytes {
+    use super::MEMORY_SIZE;
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(
+        ram: &[u8; MEMORY_SIZE],
+        serializer: S,
+    ) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(ram)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> Result<[u8; MEMORY_SIZE], D::Error> {
+        let bytes = Vec::<u8>::deserialize(deserializer)?;
+        let len = bytes.len();
+        bytes
+            .try_into()
+            .map_err(|_| D::Error::invalid_length(len, &"4096 bytes of RAM"))
+    }
+}
+
+/// Restores the saved write protection setting when dropped
+struct ProtectionGuard<'a> {
+    memory: &'a mut Memory,
+    previous: bool,
+}
+
+impl<'a> ProtectionGuard<'a> {
+    fn new(memory: &'a mut Memory) -> Self {
+        let previous = memory.write_protection_enabled;
+        memory.write_protection_enabled = false;
+        Self { memory, previous }
+    }
+}
+
+impl Drop for ProtectionGuard<'_> {
+    fn drop(&mut self) {
+        self.memory.write_protection_enabled = self.previous;
+    }
+}
+
 /// Memory system statistics
 #[derive(Debug, Clone, PartialEq)]
 pub struct MemoryStats {
@@ -235,6 +458,10 @@ pub struct MemoryStats {
     pub program_start: u16,
     pub max_rom_size: usize,
     pub write_protection_enabled: bool,
+    /// RAM high-water mark, see [`Memory::set_write_tracking`]
+    pub highest_write: Option<u16>,
+    /// Bytes of work RAM used past the ROM, see [`Memory::work_ram_used`]
+    pub work_ram_used: usize,
 }
 
 impl Default for Memory {
@@ -269,6 +496,36 @@ mod tests {
         assert_eq!(f_sprite, &[0xF0, 0x80, 0xF0, 0x80, 0x80]);
     }
 
+    #[test]
+    fn test_selected_font_set_is_loaded() {
+        let mut memory = Memory::new(true);
+        assert_eq!(memory.get_font(), FontSet::Standard);
+
+        // The sets differ in the shape of their glyphs
+        memory.set_font(FontSet::CosmacVip);
+        assert_eq!(
+            memory.get_font_sprite(1).unwrap(),
+            &[0x60, 0x20, 0x20, 0x20, 0x70]
+        );
+
+        memory.set_font(FontSet::SuperChip);
+        assert_eq!(
+            memory.get_font_sprite(0).unwrap(),
+            &[0x60, 0xA0, 0xA0, 0xA0, 0xC0]
+        );
+        assert_ne!(
+            FontSet::SuperChip.glyphs()[..FONT_HEIGHT],
+            FontSet::Standard.glyphs()[..FONT_HEIGHT]
+        );
+
+        // The selection survives a reset
+        memory.reset();
+        assert_eq!(
+            memory.get_font_sprite(0).unwrap(),
+            &[0x60, 0xA0, 0xA0, 0xA0, 0xC0]
+        );
+    }
+
     #[test]
     fn test_byte_read_write() {
         let mut memory = Memory::new(false); // Disable write protection
@@ -307,6 +564,35 @@ mod tests {
         assert_eq!(memory.read_byte(0x300).unwrap(), 0x42);
     }
 
+    #[test]
+    fn test_with_protection_disabled() {
+        let mut memory = Memory::new(true);
+
+        memory
+            .with_protection_disabled(|memory| memory.write_byte(0x100, 0x42))
+            .unwrap();
+        assert_eq!(memory.read_byte(0x100).unwrap(), 0x42);
+
+        // Protection is back once the closure returns
+        assert!(memory.is_write_protection_enabled());
+        assert!(matches!(
+            memory.write_byte(0x100, 0x43),
+            Err(MemoryError::WriteProtected { addr: 0x100 })
+        ));
+
+        // ...and also if it panics
+        let result = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+            memory.with_protection_disabled(|_| panic!("setup failed"))
+        }));
+        assert!(result.is_err());
+        assert!(memory.is_write_protection_enabled());
+
+        // An already-unprotected memory stays unprotected
+        let mut memory = Memory::new(false);
+        memory.with_protection_disabled(|_| ());
+        assert!(!memory.is_write_protection_enabled());
+    }
+
     #[test]
     fn test_rom_loading() {
         let mut memory = Memory::new(true);
@@ -319,6 +605,10 @@ mod tests {
         assert_eq!(memory.read_byte(PROGRAM_START_ADDR + 1).unwrap(), 0x34);
         assert_eq!(memory.read_byte(PROGRAM_START_ADDR + 2).unwrap(), 0x56);
         assert_eq!(memory.read_byte(PROGRAM_START_ADDR + 3).unwrap(), 0x78);
+        assert_eq!(memory.rom_len(), 4);
+
+        memory.reset();
+        assert_eq!(memory.rom_len(), 0);
     }
 
     #[test]
@@ -350,6 +640,44 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn test_hexdump_font_region() {
+        let memory = Memory::new(true);
+        assert_eq!(
+            memory.hexdump(FONT_START_ADDR, 20),
+            "0050: F0 90 90 90 F0 20 60 20 20 70 F0 10 F0 80 F0 F0  ..... `  p......\n\
+             0060: 10 F0 10 F0                                      ....\n"
+        );
+    }
+
+    #[test]
+    fn test_hexdump_ascii_gutter_and_bounds() {
+        let mut memory = Memory::new(true);
+        memory.load_rom(b"HI!\x00").unwrap();
+        assert_eq!(
+            memory.hexdump(PROGRAM_START_ADDR, 4),
+            format!("0200: 48 49 21 00{}  HI!.\n", " ".repeat(36))
+        );
+
+        // Cut off at the end of memory
+        let tail = memory.hexdump(0xFF8, 100);
+        assert_eq!(tail.lines().count(), 1);
+        assert!(tail.starts_with("0FF8: 00 00 00 00 00 00 00 00  "));
+        assert_eq!(memory.hexdump(0x200, 0), "");
+    }
+
+    #[test]
+    fn test_dump_to_file() {
+        let mut memory = Memory::new(true);
+        memory.load_rom(&[0xAB, 0xCD]).unwrap();
+        let path = std::env::temp_dir().join(format!("joe-dump-{}.bin", std::process::id()));
+        memory.dump_to_file(&path).unwrap();
+        let dumped = std::fs::read(&path).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(dumped, memory.as_slice());
+        assert_eq!(dumped.len(), MEMORY_SIZE);
+    }
+
     #[test]
     fn test_font_sprite_addresses() {
         let memory = Memory::new(true);
@@ -368,4 +696,29 @@ mod tests {
             Err(MemoryError::InvalidFontDigit { digit: 0x10 })
         ));
     }
+
+    #[test]
+    fn test_ram_high_water_mark() {
+        let mut memory = Memory::new(true);
+        memory.load_rom(&[0x00; 0x100]).unwrap();
+
+        // Writes before tracking starts are not counted
+        memory.write_byte(0xF00, 0xAA).unwrap();
+        assert_eq!(memory.highest_write(), None);
+
+        memory.set_write_tracking(true);
+        memory.write_byte(0x400, 1).unwrap();
+        memory.write_word(0x7FE, 0x1234).unwrap();
+        memory.write_byte(0x500, 2).unwrap();
+        assert!(memory.write_byte(0x1000, 3).is_err());
+
+        let stats = memory.get_stats();
+        assert_eq!(stats.highest_write, Some(0x7FF));
+        // ROM occupies 0x200-0x2FF, so 0x300-0x7FF is work RAM
+        assert_eq!(stats.work_ram_used, 0x500);
+
+        memory.reset();
+        assert_eq!(memory.highest_write(), None);
+        assert_eq!(memory.work_ram_used(), 0);
+    }
 }
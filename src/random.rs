@@ -0,0 +1,171 @@
+//! Random - Byte source for the RND instruction
+//!
+//! The CPU draws RND bytes through the [`RandomSource`] trait. Any `rand`
+//! generator works as one (the seeded `SmallRng` is the default), and a
+//! [`FileSequence`] replays a scripted list of bytes so regression runs of
+//! RNG-heavy games see exactly the values they were recorded with.
+
+use rand::RngCore;
+use std::path::Path;
+use thiserror::Error;
+
+#[derive(Error, Debug)]
+pub enum RandomError {
+    #[error("Random sequence exhausted after {len} bytes")]
+    Exhausted { len: usize },
+
+    #[error("Random sequence is empty")]
+    EmptySequence,
+
+    #[error("Unknown sequence end: {name} (expected cycle or error)")]
+    UnknownSequenceEnd { name: String },
+
+    #[error("IO error: {0}")]
+    Io(#[from] std::io::Error),
+}
+
+/// Source of the bytes consumed by RND
+pub trait RandomSource: Send {
+    /// Get the next random byte
+    fn next_byte(&mut self) -> Result<u8, RandomError>;
+
+    /// Copy the source, including its position, so the copy yields the same bytes
+    fn clone_box(&self) -> Box<dyn RandomSource>;
+}
+
+impl<R: RngCore + Clone + Send + 'static> RandomSource for R {
+    fn next_byte(&mut self) -> Result<u8, RandomError> {
+        Ok(self.next_u32() as u8)
+    }
+
+    fn clone_box(&self) -> Box<dyn RandomSource> {
+        Box::new(self.clone())
+    }
+}
+
+/// What a [`FileSequence`] does once every byte has been read
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SequenceEnd {
+    /// Start over from the first byte
+    #[default]
+    Cycle,
+    /// Fail the RND instruction with [`RandomError::Exhausted`]
+    Error,
+}
+
+impl std::str::FromStr for SequenceEnd {
+    type Err = RandomError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.to_ascii_lowercase().as_str() {
+            "cycle" => Ok(Self::Cycle),
+            "error" => Ok(Self::Error),
+            _ => Err(RandomError::UnknownSequenceEnd {
+                name: s.to_string(),
+            }),
+        }
+    }
+}
+
+/// Fixed sequence of random bytes, yielded in order
+#[derive(Debug, Clone)]
+pub struct FileSequence {
+    bytes: Vec<u8>,
+    position: usize,
+    end: SequenceEnd,
+}
+
+impl FileSequence {
+    /// Create a sequence from the given bytes
+    pub fn new(bytes: Vec<u8>, end: SequenceEnd) -> Result<Self, RandomError> {
+        if bytes.is_empty() {
+            return Err(RandomError::EmptySequence);
+        }
+        Ok(Self {
+            bytes,
+            position: 0,
+            end,
+        })
+    }
+
+    /// Read a sequence from a file, one random byte per file byte
+    pub fn from_file(path: impl AsRef<Path>, end: SequenceEnd) -> Result<Self, RandomError> {
+        Self::new(std::fs::read(path)?, end)
+    }
+
+    /// Get the number of bytes handed out so far in the current pass
+    pub fn position(&self) -> usize {
+        self.position
+    }
+}
+
+impl RandomSource for FileSequence {
+    fn next_byte(&mut self) -> Result<u8, RandomError> {
+        if self.position == self.bytes.len() {
+            match self.end {
+                SequenceEnd::Cycle => self.position = 0,
+                SequenceEnd::Error => {
+                    return Err(RandomError::Exhausted {
+                        len: self.bytes.len(),
+                    });
+                }
+            }
+        }
+        let byte = self.bytes[self.position];
+        self.position += 1;
+        Ok(byte)
+    }
+
+    fn clone_box(&self) -> Box<dyn RandomSource> {
+        Box::new(self.clone())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sequence_cycles() {
+        let mut sequence = FileSequence::new(vec![1, 2, 3], SequenceEnd::Cycle).unwrap();
+        let bytes: Vec<u8> = (0..7).map(|_| sequence.next_byte().unwrap()).collect();
+        assert_eq!(bytes, vec![1, 2, 3, 1, 2, 3, 1]);
+        assert_eq!(sequence.position(), 1);
+    }
+
+    #[test]
+    fn test_sequence_errors_at_end() {
+        let mut sequence = FileSequence::new(vec![0xAA, 0x55], SequenceEnd::Error).unwrap();
+        assert_eq!(sequence.next_byte().unwrap(), 0xAA);
+        assert_eq!(sequence.next_byte().unwrap(), 0x55);
+        assert!(matches!(
+            sequence.next_byte(),
+            Err(RandomError::Exhausted { len: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_sequence_end_from_str() {
+        assert_eq!("cycle".parse::<SequenceEnd>().unwrap(), SequenceEnd::Cycle);
+        assert_eq!("Error".parse::<SequenceEnd>().unwrap(), SequenceEnd::Error);
+        assert!(matches!(
+            "stop".parse::<SequenceEnd>(),
+            Err(RandomError::UnknownSequenceEnd { .. })
+        ));
+    }
+
+    #[test]
+    fn test_sequence_from_file() {
+        let path = std::env::temp_dir().join(format!("joe-random-{}.bin", std::process::id()));
+        std::fs::write(&path, [0x10, 0x20]).unwrap();
+        let mut sequence = FileSequence::from_file(&path, SequenceEnd::Cycle).unwrap();
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(sequence.next_byte().unwrap(), 0x10);
+        assert_eq!(sequence.next_byte().unwrap(), 0x20);
+
+        assert!(matches!(
+            FileSequence::new(Vec::new(), SequenceEnd::Cycle),
+            Err(RandomError::EmptySequence)
+        ));
+    }
+}
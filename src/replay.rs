@@ -0,0 +1,199 @@
+//! Input recording and playback
+//!
+//! An [`InputRecorder`] watches the keypad state of any [`InputBus`] and logs
+//! each press and release with the cycle it happened before. An
+//! [`InputPlayer`] feeds such a log back at the same cycle counts. The log
+//! also stores the RND seed, so replaying it reproduces the whole run.
+//!
+//! Logged events name CHIP-8 keys by index ([`KeypadEvent::Pressed`]`(0xA)`),
+//! not by keyboard character, so a log replays the same way whatever the key
+//! mappings.
+//!
+//! Replays are only exact when the timers follow the cycle count too, as
+//! they do when both runs are driven by [`crate::Emulator::step`] (or
+//! [`crate::Emulator::run_tick`], which ticks them once per fixed batch of
+//! steps). The run loops and [`crate::Emulator::run_cycles`] advance the
+//! timers by clock time, so a program that reads the delay timer can take a
+//! different path on replay.
+
+use crate::input::InputBus;
+use serde::{Deserialize, Serialize};
+
+/// A change of one CHIP-8 key, by key index (0x0-0xF)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub enum KeypadEvent {
+    /// The key went down
+    Pressed(u8),
+    /// The key came back up
+    Released(u8),
+}
+
+/// A recorded run: the RND seed plus keypad events keyed by cycle number
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct InputLog {
+    /// Seed the recorded run's RND instruction started from
+    pub seed: u64,
+    /// Keypad events in order, each applied before the numbered cycle executes
+    pub events: Vec<(usize, KeypadEvent)>,
+}
+
+impl InputLog {
+    /// Serialize the log as pretty-printed JSON
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Parse a log written by [`InputLog::to_json`]
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
+
+/// Records keypad changes by comparing the pressed keys cycle to cycle
+///
+/// A key pressed and released between two observations isn't seen.
+#[derive(Debug, Clone)]
+pub struct InputRecorder {
+    log: InputLog,
+    pressed: [bool; 16],
+}
+
+impl InputRecorder {
+    /// Start an empty recording of a run seeded with `seed`
+    pub fn new(seed: u64) -> Self {
+        Self {
+            log: InputLog {
+                seed,
+                events: Vec::new(),
+            },
+            pressed: [false; 16],
+        }
+    }
+
+    /// Log every key whose state changed since the last observation
+    pub fn observe(&mut self, cycle: usize, input: &impl InputBus) {
+        let mut pressed = [false; 16];
+        for key in input.get_pressed_keys() {
+            pressed[key as usize & 0xF] = true;
+        }
+        for (key, (&now, was)) in (0u8..).zip(pressed.iter().zip(&mut self.pressed)) {
+            if now != *was {
+                let event = if now {
+                    KeypadEvent::Pressed(key)
+                } else {
+                    KeypadEvent::Released(key)
+                };
+                self.log.events.push((cycle, event));
+                *was = now;
+            }
+        }
+    }
+
+    /// The events recorded so far
+    pub fn log(&self) -> &InputLog {
+        &self.log
+    }
+
+    /// Finish recording and return the log
+    pub fn into_log(self) -> InputLog {
+        self.log
+    }
+}
+
+/// Replays an [`InputLog`] one cycle at a time
+#[derive(Debug, Clone)]
+pub struct InputPlayer {
+    log: InputLog,
+    next: usize,
+}
+
+impl InputPlayer {
+    /// Start playback from the beginning of `log`
+    pub fn new(log: InputLog) -> Self {
+        Self { log, next: 0 }
+    }
+
+    /// Seed the recorded run used
+    pub fn seed(&self) -> u64 {
+        self.log.seed
+    }
+
+    /// Take the events due before `cycle` executes, as (CHIP-8 key, pressed)
+    ///
+    /// Events for earlier cycles that were never asked for come out too, so a
+    /// player attached late catches up. Keys above 0xF in a hand-edited log
+    /// are skipped.
+    pub fn events_for(&mut self, cycle: usize) -> Vec<(u8, bool)> {
+        let mut due = Vec::new();
+        while let Some((at_cycle, event)) = self.log.events.get(self.next)
+            && *at_cycle <= cycle
+        {
+            let (key, pressed) = match *event {
+                KeypadEvent::Pressed(key) => (key, true),
+                KeypadEvent::Released(key) => (key, false),
+            };
+            if key <= 0xF {
+                due.push((key, pressed));
+            }
+            self.next += 1;
+        }
+        due
+    }
+
+    /// Whether every event has been played
+    pub fn is_finished(&self) -> bool {
+        self.next >= self.log.events.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::input::MockInput;
+
+    #[test]
+    fn test_recorder_logs_changes_by_key_index() {
+        let mut input = MockInput::new();
+        let mut recorder = InputRecorder::new(7);
+
+        recorder.observe(0, &input);
+        input.press_key(0xA).unwrap();
+        recorder.observe(3, &input);
+        recorder.observe(4, &input);
+        input.release_key(0xA).unwrap();
+        input.press_key(0x1).unwrap();
+        recorder.observe(9, &input);
+
+        assert_eq!(
+            recorder.into_log(),
+            InputLog {
+                seed: 7,
+                events: vec![
+                    (3, KeypadEvent::Pressed(0xA)),
+                    (9, KeypadEvent::Pressed(0x1)),
+                    (9, KeypadEvent::Released(0xA)),
+                ],
+            }
+        );
+    }
+
+    #[test]
+    fn test_player_releases_events_by_cycle() {
+        let log = InputLog {
+            seed: 7,
+            events: vec![
+                (2, KeypadEvent::Pressed(0xF)),
+                (5, KeypadEvent::Released(0xF)),
+                (6, KeypadEvent::Pressed(0x3)),
+                (6, KeypadEvent::Pressed(0x10)),
+            ],
+        };
+        let mut player = InputPlayer::new(InputLog::from_json(&log.to_json().unwrap()).unwrap());
+
+        assert!(player.events_for(1).is_empty());
+        assert_eq!(player.events_for(2), vec![(0xF, true)]);
+        // Skipping ahead catches up on missed cycles; the bad key is dropped
+        assert_eq!(player.events_for(6), vec![(0xF, false), (0x3, true)]);
+        assert!(player.is_finished());
+    }
+}
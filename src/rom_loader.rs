@@ -4,8 +4,47 @@
 //! from either local filesystem paths or HTTP(S) URLs.
 
 use anyhow::{Context, Result};
+use std::collections::BTreeMap;
+use std::io::Read;
 use std::time::Duration;
 
+/// Built-in IBM logo ROM, used when no ROM source is configured
+pub const BUILTIN_LOGO_ROM: &[u8] = include_bytes!("../roms/ibm_logo.ch8");
+
+/// Size of each chunk read from a download stream
+const DOWNLOAD_CHUNK_SIZE: usize = 1024;
+
+/// Magic bytes at the start of a gzip stream
+const GZIP_MAGIC: [u8; 2] = [0x1F, 0x8B];
+
+/// Line that opens a trailing metadata block
+///
+/// A ROM may end with this marker followed by UTF-8 `key: value` lines
+/// (e.g. `title: Pong`, `author: Someone`) running to the end of the file.
+pub const METADATA_MARKER: &[u8] = b"#chip8-meta\n";
+
+/// Longest metadata block (after the marker) that is recognized
+const MAX_METADATA_LEN: usize = 1024;
+
+/// Metadata parsed from a trailing [`METADATA_MARKER`] block
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RomMetadata {
+    /// All fields, keyed by lowercase name
+    pub fields: BTreeMap<String, String>,
+}
+
+impl RomMetadata {
+    /// Get the `title` field
+    pub fn title(&self) -> Option<&str> {
+        self.fields.get("title").map(String::as_str)
+    }
+
+    /// Get the `author` field
+    pub fn author(&self) -> Option<&str> {
+        self.fields.get("author").map(String::as_str)
+    }
+}
+
 /// Configuration for ROM loading operations
 #[derive(Debug, Clone)]
 pub struct RomLoaderConfig {
@@ -13,6 +52,16 @@ pub struct RomLoaderConfig {
     pub http_timeout: Duration,
     /// Maximum ROM size in bytes
     pub max_rom_size: usize,
+    /// Hosts that URL sources may be downloaded from (None = any host)
+    pub allowed_hosts: Option<Vec<String>>,
+    /// Maximum number of HTTP redirects to follow
+    pub max_redirects: usize,
+    /// Extra download attempts after a connection error, timeout or 5xx response
+    pub max_retries: u32,
+    /// Delay before the first retry; doubled before each further retry
+    pub retry_backoff: Duration,
+    /// Drop a trailing metadata block (see [`split_metadata`]) from loaded ROMs
+    pub strip_metadata: bool,
 }
 
 impl Default for RomLoaderConfig {
@@ -20,6 +69,11 @@ impl Default for RomLoaderConfig {
         Self {
             http_timeout: Duration::from_secs(30),
             max_rom_size: 4096 - 512, // CHIP-8 memory minus interpreter area
+            allowed_hosts: None,
+            max_redirects: 10,
+            max_retries: 2,
+            retry_backoff: Duration::from_millis(500),
+            strip_metadata: true,
         }
     }
 }
@@ -35,9 +89,14 @@ pub enum RomSource {
 
 impl RomSource {
     /// Detect the source type from a string input
+    ///
+    /// `file://` URLs (`file:///path` or `file://localhost/path`) become file
+    /// sources with the percent-decoded path.
     pub fn from_string(input: &str) -> Self {
         if input.starts_with("http://") || input.starts_with("https://") {
             Self::Url(input.to_string())
+        } else if let Some(path) = file_url_path(input) {
+            Self::File(path)
         } else {
             Self::File(input.to_string())
         }
@@ -62,6 +121,21 @@ impl RomSource {
     }
 }
 
+/// Get the local path of a `file://` URL, or None if `input` isn't one
+fn file_url_path(input: &str) -> Option<String> {
+    if !input
+        .get(..7)
+        .is_some_and(|scheme| scheme.eq_ignore_ascii_case("file://"))
+    {
+        return None;
+    }
+    match reqwest::Url::parse(input).ok()?.to_file_path() {
+        Ok(path) => Some(path.to_string_lossy().into_owned()),
+        // Not a valid local file URL (e.g. a remote host): keep the text after the scheme
+        Err(()) => Some(input[7..].to_string()),
+    }
+}
+
 /// Load ROM data from either a file or URL
 pub fn load_rom_data(input: &str) -> Result<Vec<u8>> {
     load_rom_data_with_config(input, &RomLoaderConfig::default())
@@ -69,15 +143,39 @@ pub fn load_rom_data(input: &str) -> Result<Vec<u8>> {
 
 /// Load ROM data with custom configuration
 pub fn load_rom_data_with_config(input: &str, config: &RomLoaderConfig) -> Result<Vec<u8>> {
+    load_rom_data_with_progress(input, config, &mut |_, _| {})
+}
+
+/// Load ROM data with custom configuration, reporting URL download progress
+///
+/// `on_progress` is called after each downloaded chunk with the number of bytes
+/// received so far and the total size if the server reported one.
+pub fn load_rom_data_with_progress(
+    input: &str,
+    config: &RomLoaderConfig,
+    on_progress: &mut dyn FnMut(usize, Option<u64>),
+) -> Result<Vec<u8>> {
     let source = RomSource::from_string(input);
 
     let data = match source {
         RomSource::File(path) => load_from_file(&path)
             .with_context(|| format!("Failed to load ROM from file: {}", path))?,
-        RomSource::Url(url) => load_from_url(&url, config)
+        RomSource::Url(url) => load_from_url(&url, config, on_progress)
             .with_context(|| format!("Failed to load ROM from URL: {}", url))?,
     };
 
+    // `.ch8.gz` archives: the size limit applies to the decompressed ROM
+    let data = if data.starts_with(&GZIP_MAGIC) {
+        decompress_gzip(&data, config.max_rom_size)?
+    } else {
+        data
+    };
+
+    let data = match split_metadata(&data) {
+        (program, Some(_)) if config.strip_metadata => program.to_vec(),
+        _ => data,
+    };
+
     // Validate ROM size
     if data.len() > config.max_rom_size {
         anyhow::bail!(
@@ -94,6 +192,69 @@ pub fn load_rom_data_with_config(input: &str, config: &RomLoaderConfig) -> Resul
     Ok(data)
 }
 
+/// Split a ROM into its program bytes and any trailing metadata block
+///
+/// Detection is strict so ordinary ROMs are never cut short: the block must
+/// start with [`METADATA_MARKER`] within the last 1KB, follow some program
+/// bytes, and consist only of printable `key: value` lines. Otherwise the
+/// whole input is returned as the program.
+pub fn split_metadata(data: &[u8]) -> (&[u8], Option<RomMetadata>) {
+    let search_from = data
+        .len()
+        .saturating_sub(MAX_METADATA_LEN + METADATA_MARKER.len());
+    let Some(offset) = data[search_from..]
+        .windows(METADATA_MARKER.len())
+        .rposition(|window| window == METADATA_MARKER)
+    else {
+        return (data, None);
+    };
+    let start = search_from + offset;
+    if start == 0 {
+        return (data, None);
+    }
+
+    match parse_metadata(&data[start + METADATA_MARKER.len()..]) {
+        Some(metadata) => (&data[..start], Some(metadata)),
+        None => (data, None),
+    }
+}
+
+/// Parse `key: value` lines, or None if anything doesn't look like metadata
+fn parse_metadata(block: &[u8]) -> Option<RomMetadata> {
+    let text = std::str::from_utf8(block).ok()?;
+    let mut fields = BTreeMap::new();
+    for line in text.lines().map(str::trim).filter(|line| !line.is_empty()) {
+        let (key, value) = line.split_once(':')?;
+        let key = key.trim();
+        let valid_key = !key.is_empty()
+            && key
+                .bytes()
+                .all(|b| b.is_ascii_alphanumeric() || b == b'_' || b == b'-');
+        if !valid_key || value.chars().any(char::is_control) {
+            return None;
+        }
+        fields.insert(key.to_ascii_lowercase(), value.trim().to_string());
+    }
+    (!fields.is_empty()).then_some(RomMetadata { fields })
+}
+
+/// Decompress a gzip stream, failing as soon as the output exceeds `max_size` bytes
+fn decompress_gzip(data: &[u8], max_size: usize) -> Result<Vec<u8>> {
+    let mut decompressed = Vec::new();
+    flate2::read::GzDecoder::new(data)
+        .take(max_size as u64 + 1)
+        .read_to_end(&mut decompressed)
+        .context("Failed to decompress gzip ROM")?;
+
+    if decompressed.len() > max_size {
+        anyhow::bail!(
+            "ROM too large: decompressed size exceeds {} bytes",
+            max_size
+        );
+    }
+    Ok(decompressed)
+}
+
 /// Load ROM data from a local file
 fn load_from_file(path: &str) -> Result<Vec<u8>> {
     let path = std::path::Path::new(path);
@@ -109,44 +270,220 @@ fn load_from_file(path: &str) -> Result<Vec<u8>> {
     std::fs::read(path).with_context(|| format!("Failed to read ROM file: {}", path.display()))
 }
 
+/// Ensure the URL's host is permitted by the configured allowlist
+fn check_allowed_host(url: &str, config: &RomLoaderConfig) -> Result<()> {
+    let Some(allowed_hosts) = &config.allowed_hosts else {
+        return Ok(());
+    };
+
+    let parsed = reqwest::Url::parse(url).with_context(|| format!("Invalid URL: {}", url))?;
+    let host = parsed
+        .host_str()
+        .ok_or_else(|| anyhow::anyhow!("URL has no host: {}", url))?;
+
+    if !allowed_hosts
+        .iter()
+        .any(|allowed| allowed.eq_ignore_ascii_case(host))
+    {
+        anyhow::bail!(
+            "Host '{}' is not in the allowed hosts list ({})",
+            host,
+            allowed_hosts.join(", ")
+        );
+    }
+
+    Ok(())
+}
+
+/// Decide whether to follow a redirect to `url` after `hops` earlier requests
+///
+/// Every hop must stay within `max_redirects` and pass the host allowlist,
+/// so a redirect can't lead a download to a host the first URL couldn't.
+fn check_redirect(url: &str, hops: usize, config: &RomLoaderConfig) -> Result<()> {
+    if hops > config.max_redirects {
+        anyhow::bail!("Too many redirects (max: {})", config.max_redirects);
+    }
+    check_allowed_host(url, config).with_context(|| format!("Redirect to {} rejected", url))
+}
+
+/// Build the redirect policy applying [`check_redirect`] to every hop
+fn redirect_policy(config: &RomLoaderConfig) -> reqwest::redirect::Policy {
+    let config = config.clone();
+    reqwest::redirect::Policy::custom(move |attempt| {
+        match check_redirect(attempt.url().as_str(), attempt.previous().len(), &config) {
+            Ok(()) => attempt.follow(),
+            Err(e) => attempt.error(format!("{:#}", e)),
+        }
+    })
+}
+
+/// Read a stream in chunks, failing as soon as it exceeds `max_size` bytes
+fn read_limited<R: Read>(
+    mut reader: R,
+    max_size: usize,
+    total: Option<u64>,
+    on_progress: &mut dyn FnMut(usize, Option<u64>),
+) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    let mut chunk = [0u8; DOWNLOAD_CHUNK_SIZE];
+
+    loop {
+        let read = reader
+            .read(&mut chunk)
+            .context("Failed to read response body")?;
+        if read == 0 {
+            break;
+        }
+
+        if data.len() + read > max_size {
+            anyhow::bail!(
+                "ROM too large: download exceeded {} bytes (max: {} bytes)",
+                data.len() + read,
+                max_size
+            );
+        }
+
+        data.extend_from_slice(&chunk[..read]);
+        on_progress(data.len(), total);
+    }
+
+    Ok(data)
+}
+
+/// Result of one download attempt, classified for retrying
+enum Attempt<T> {
+    /// The attempt succeeded
+    Done(T),
+    /// Connection error, timeout or server error: worth trying again
+    Transient(anyhow::Error),
+    /// Client error or bad request: retrying won't help
+    Fatal(anyhow::Error),
+}
+
+/// Run `attempt` until it succeeds, fails fatally or uses up `max_retries` retries
+///
+/// Waits `backoff`, `2 * backoff`, `4 * backoff`, ... (through `sleep`) before
+/// each retry. The final error says how many attempts were made.
+fn with_retries<T>(
+    max_retries: u32,
+    backoff: Duration,
+    sleep: &mut dyn FnMut(Duration),
+    mut attempt: impl FnMut() -> Attempt<T>,
+) -> Result<T> {
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        let error = match attempt() {
+            Attempt::Done(value) => return Ok(value),
+            Attempt::Transient(_) if attempts <= max_retries => {
+                sleep(backoff.saturating_mul(1 << (attempts - 1).min(31)));
+                continue;
+            }
+            Attempt::Transient(error) | Attempt::Fatal(error) => error,
+        };
+        let plural = if attempts == 1 { "" } else { "s" };
+        return Err(error.context(format!(
+            "Download failed after {} attempt{}",
+            attempts, plural
+        )));
+    }
+}
+
+/// Send the GET request, classifying failures as transient or fatal
+fn request_rom(
+    client: &reqwest::blocking::Client,
+    url: &str,
+) -> Attempt<reqwest::blocking::Response> {
+    let response = match client.get(url).send() {
+        Ok(response) => response,
+        Err(e) if e.is_redirect() => {
+            // The redirect policy's own message says why the hop was refused
+            let reason =
+                std::error::Error::source(&e).map_or_else(|| e.to_string(), |s| s.to_string());
+            return Attempt::Fatal(anyhow::anyhow!(reason));
+        }
+        Err(e) => {
+            let transient = e.is_connect() || e.is_timeout();
+            let error = anyhow::Error::new(e).context("Failed to send HTTP request");
+            return if transient {
+                Attempt::Transient(error)
+            } else {
+                Attempt::Fatal(error)
+            };
+        }
+    };
+
+    let status = response.status();
+    if status.is_success() {
+        return Attempt::Done(response);
+    }
+    let error = anyhow::anyhow!(
+        "HTTP request failed with status {}: {}",
+        status,
+        status.canonical_reason().unwrap_or("Unknown error")
+    );
+    if status.is_server_error() {
+        Attempt::Transient(error)
+    } else {
+        Attempt::Fatal(error)
+    }
+}
+
+/// Check whether a Content-Type header value names an HTML document
+fn is_html(content_type: &str) -> bool {
+    let media_type = content_type.split(';').next().unwrap_or_default().trim();
+    media_type.eq_ignore_ascii_case("text/html")
+        || media_type.eq_ignore_ascii_case("application/xhtml+xml")
+}
+
 /// Load ROM data from a URL
-fn load_from_url(url: &str, config: &RomLoaderConfig) -> Result<Vec<u8>> {
+fn load_from_url(
+    url: &str,
+    config: &RomLoaderConfig,
+    on_progress: &mut dyn FnMut(usize, Option<u64>),
+) -> Result<Vec<u8>> {
+    // Reject disallowed hosts before any network activity
+    check_allowed_host(url, config)?;
+
     let client = reqwest::blocking::Client::builder()
         .timeout(config.http_timeout)
         .user_agent("joe-chip8-emulator/0.2.0")
+        .redirect(redirect_policy(config))
         .build()
         .context("Failed to create HTTP client")?;
 
-    let response = client
-        .get(url)
-        .send()
-        .context("Failed to send HTTP request")?;
-
-    if !response.status().is_success() {
+    let response = with_retries(
+        config.max_retries,
+        config.retry_backoff,
+        &mut std::thread::sleep,
+        || request_rom(&client, url),
+    )?;
+
+    // A web page usually means the link points at a download page, not the ROM
+    if let Some(content_type) = response.headers().get(reqwest::header::CONTENT_TYPE)
+        && let Ok(content_type) = content_type.to_str()
+        && is_html(content_type)
+    {
         anyhow::bail!(
-            "HTTP request failed with status {}: {}",
-            response.status(),
-            response
-                .status()
-                .canonical_reason()
-                .unwrap_or("Unknown error")
+            "Expected a ROM but the server returned a web page ({})",
+            content_type
         );
     }
 
     // Check content length if provided
-    if let Some(content_length) = response.content_length() {
-        if content_length as usize > config.max_rom_size {
-            anyhow::bail!(
-                "ROM too large: {} bytes (max: {} bytes)",
-                content_length,
-                config.max_rom_size
-            );
-        }
+    let content_length = response.content_length();
+    if let Some(content_length) = content_length
+        && content_length as usize > config.max_rom_size
+    {
+        anyhow::bail!(
+            "ROM too large: {} bytes (max: {} bytes)",
+            content_length,
+            config.max_rom_size
+        );
     }
 
-    let bytes = response.bytes().context("Failed to read response body")?;
-
-    Ok(bytes.to_vec())
+    // Stream the body so a server lying about content length can't exceed the limit
+    read_limited(response, config.max_rom_size, content_length, on_progress)
 }
 
 #[cfg(test)]
@@ -180,6 +517,83 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_file_url_detection() {
+        // Triple-slash and localhost forms both name a local path
+        assert_eq!(
+            RomSource::from_string("file:///home/user/rom.ch8"),
+            RomSource::File("/home/user/rom.ch8".to_string())
+        );
+        assert_eq!(
+            RomSource::from_string("file://localhost/home/user/rom.ch8"),
+            RomSource::File("/home/user/rom.ch8".to_string())
+        );
+
+        // Percent-encoded characters are decoded
+        assert_eq!(
+            RomSource::from_string("FILE:///roms/Space%20Invaders.ch8"),
+            RomSource::File("/roms/Space Invaders.ch8".to_string())
+        );
+
+        let source = RomSource::from_string("file:///tmp/rom.ch8");
+        assert!(source.is_file());
+        assert!(!source.is_url());
+    }
+
+    #[test]
+    fn test_load_from_file_url() {
+        let path = std::env::temp_dir().join(format!("joe-test-{}.ch8", std::process::id()));
+        std::fs::write(&path, [0x00, 0xE0]).unwrap();
+
+        let data = load_rom_data(&format!("file://{}", path.display()));
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(data.unwrap(), vec![0x00, 0xE0]);
+    }
+
+    #[test]
+    fn test_split_metadata() {
+        let mut rom = vec![0x00, 0xE0, 0x12, 0x02];
+        rom.extend_from_slice(METADATA_MARKER);
+        rom.extend_from_slice(b"Title: Pong\nauthor: Someone\n");
+
+        let (program, metadata) = split_metadata(&rom);
+        assert_eq!(program, &[0x00, 0xE0, 0x12, 0x02]);
+        let metadata = metadata.unwrap();
+        assert_eq!(metadata.title(), Some("Pong"));
+        assert_eq!(metadata.author(), Some("Someone"));
+
+        // Ordinary ROMs, and marker look-alikes followed by binary, are untouched
+        let plain = [0x00, 0xE0, 0x12, 0x02];
+        assert_eq!(split_metadata(&plain), (&plain[..], None));
+        let mut binary_tail = plain.to_vec();
+        binary_tail.extend_from_slice(METADATA_MARKER);
+        binary_tail.extend_from_slice(&[0xA2, 0x0A, 0xD0, 0x15]);
+        assert_eq!(split_metadata(&binary_tail), (&binary_tail[..], None));
+        assert_eq!(split_metadata(METADATA_MARKER).1, None);
+    }
+
+    #[test]
+    fn test_load_strips_metadata_when_configured() {
+        let path = std::env::temp_dir().join(format!("joe-test-meta-{}.ch8", std::process::id()));
+        let mut rom = vec![0x00, 0xE0];
+        rom.extend_from_slice(METADATA_MARKER);
+        rom.extend_from_slice(b"title: Blank\n");
+        std::fs::write(&path, &rom).unwrap();
+
+        let source = path.to_str().unwrap();
+        let stripped = load_rom_data(source);
+        let kept = load_rom_data_with_config(
+            source,
+            &RomLoaderConfig {
+                strip_metadata: false,
+                ..Default::default()
+            },
+        );
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(stripped.unwrap(), vec![0x00, 0xE0]);
+        assert_eq!(kept.unwrap(), rom);
+    }
+
     #[test]
     fn test_rom_source_methods() {
         let url_source = RomSource::Url("https://example.com/rom.ch8".to_string());
@@ -198,6 +612,198 @@ mod tests {
         let config = RomLoaderConfig::default();
         assert_eq!(config.http_timeout, Duration::from_secs(30));
         assert_eq!(config.max_rom_size, 4096 - 512);
+        assert!(config.allowed_hosts.is_none());
+        assert_eq!(config.max_redirects, 10);
+        assert_eq!(config.max_retries, 2);
+        assert_eq!(config.retry_backoff, Duration::from_millis(500));
+    }
+
+    /// Run `with_retries` over a fixed sequence of attempt results, returning
+    /// the result, the number of attempts made and the backoff delays slept
+    fn retry_sequence(
+        max_retries: u32,
+        results: Vec<Attempt<u8>>,
+    ) -> (Result<u8>, usize, Vec<Duration>) {
+        let mut results = results.into_iter();
+        let mut attempts = 0;
+        let mut sleeps = Vec::new();
+        let result = with_retries(
+            max_retries,
+            Duration::from_millis(100),
+            &mut |delay| sleeps.push(delay),
+            || {
+                attempts += 1;
+                results.next().expect("more attempts than results")
+            },
+        );
+        (result, attempts, sleeps)
+    }
+
+    #[test]
+    fn test_retry_recovers_from_transient_errors() {
+        let (result, attempts, sleeps) = retry_sequence(
+            3,
+            vec![
+                Attempt::Transient(anyhow::anyhow!("connection refused")),
+                Attempt::Transient(anyhow::anyhow!("HTTP 503")),
+                Attempt::Done(7),
+            ],
+        );
+        assert_eq!(result.unwrap(), 7);
+        assert_eq!(attempts, 3);
+        // Exponential backoff
+        assert_eq!(
+            sleeps,
+            vec![Duration::from_millis(100), Duration::from_millis(200)]
+        );
+    }
+
+    #[test]
+    fn test_retry_gives_up_and_counts_attempts() {
+        let (result, attempts, sleeps) = retry_sequence(
+            2,
+            vec![
+                Attempt::Transient(anyhow::anyhow!("timed out")),
+                Attempt::Transient(anyhow::anyhow!("timed out")),
+                Attempt::Transient(anyhow::anyhow!("HTTP 502")),
+            ],
+        );
+        let error = format!("{:#}", result.unwrap_err());
+        assert_eq!(error, "Download failed after 3 attempts: HTTP 502");
+        assert_eq!(attempts, 3);
+        assert_eq!(sleeps.len(), 2);
+    }
+
+    #[test]
+    fn test_retry_stops_on_fatal_error() {
+        let (result, attempts, sleeps) = retry_sequence(
+            5,
+            vec![
+                Attempt::Transient(anyhow::anyhow!("HTTP 500")),
+                Attempt::Fatal(anyhow::anyhow!("HTTP 404")),
+            ],
+        );
+        let error = format!("{:#}", result.unwrap_err());
+        assert_eq!(error, "Download failed after 2 attempts: HTTP 404");
+        assert_eq!(attempts, 2);
+        assert_eq!(sleeps.len(), 1);
+    }
+
+    #[test]
+    fn test_redirect_checks_limit_and_hosts() {
+        let config = RomLoaderConfig {
+            max_redirects: 3,
+            ..Default::default()
+        };
+        assert!(check_redirect("https://example.com/rom.ch8", 3, &config).is_ok());
+        let error = check_redirect("https://example.com/rom.ch8", 4, &config).unwrap_err();
+        assert_eq!(error.to_string(), "Too many redirects (max: 3)");
+
+        let config = RomLoaderConfig {
+            allowed_hosts: Some(vec!["github.com".to_string()]),
+            ..Default::default()
+        };
+        assert!(check_redirect("https://github.com/rom.ch8", 1, &config).is_ok());
+        let error = check_redirect("https://evil.invalid/rom.ch8", 1, &config).unwrap_err();
+        assert_eq!(
+            format!("{:#}", error),
+            "Redirect to https://evil.invalid/rom.ch8 rejected: \
+             Host 'evil.invalid' is not in the allowed hosts list (github.com)"
+        );
+    }
+
+    #[test]
+    fn test_allowed_host_passes_check() {
+        let config = RomLoaderConfig {
+            allowed_hosts: Some(vec!["github.com".to_string()]),
+            ..Default::default()
+        };
+
+        assert!(check_allowed_host("https://github.com/rom.ch8", &config).is_ok());
+        assert!(check_allowed_host("https://GitHub.com:443/rom.ch8", &config).is_ok());
+    }
+
+    #[test]
+    fn test_disallowed_host_rejected_without_request() {
+        let config = RomLoaderConfig {
+            allowed_hosts: Some(vec!["github.com".to_string()]),
+            ..Default::default()
+        };
+
+        // Userinfo must not fool the host check
+        let result = load_rom_data_with_config("https://github.com@evil.invalid/rom.ch8", &config);
+        let error_msg = format!("{:#}", result.unwrap_err());
+        assert!(error_msg.contains("'evil.invalid' is not in the allowed hosts list"));
+    }
+
+    #[test]
+    fn test_html_content_type_detection() {
+        assert!(is_html("text/html"));
+        assert!(is_html("Text/HTML; charset=utf-8"));
+        assert!(is_html("application/xhtml+xml"));
+        assert!(!is_html("application/octet-stream"));
+        assert!(!is_html("text/plain"));
+    }
+
+    #[test]
+    fn test_read_limited_accumulates_chunks() {
+        let body = vec![0xAB; DOWNLOAD_CHUNK_SIZE * 2 + 10];
+        let mut progress = Vec::new();
+
+        let data = read_limited(
+            std::io::Cursor::new(body.clone()),
+            body.len(),
+            Some(body.len() as u64),
+            &mut |received, total| progress.push((received, total)),
+        )
+        .unwrap();
+
+        assert_eq!(data, body);
+        assert_eq!(
+            progress.last(),
+            Some(&(body.len(), Some(body.len() as u64)))
+        );
+        assert_eq!(progress.len(), 3);
+    }
+
+    #[test]
+    fn test_read_limited_rejects_oversized_body() {
+        let body = vec![0xAB; 100];
+
+        let result = read_limited(std::io::Cursor::new(body), 99, None, &mut |_, _| {});
+        assert!(result.unwrap_err().to_string().contains("ROM too large"));
+    }
+
+    fn gzip(data: &[u8]) -> Vec<u8> {
+        use std::io::Write;
+        let mut encoder = flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+        encoder.write_all(data).unwrap();
+        encoder.finish().unwrap()
+    }
+
+    #[test]
+    fn test_gzip_rom_decompressed() {
+        let rom: Vec<u8> = (0..=255).collect();
+        let path = std::env::temp_dir().join(format!("joe-test-{}.ch8.gz", std::process::id()));
+        std::fs::write(&path, gzip(&rom)).unwrap();
+
+        let data = load_rom_data(path.to_str().unwrap());
+        std::fs::remove_file(&path).unwrap();
+        assert_eq!(data.unwrap(), rom);
+    }
+
+    #[test]
+    fn test_gzip_size_limit_applies_after_decompression() {
+        // Zeros compress to a few bytes but decompress past the limit
+        let compressed = gzip(&[0; 200]);
+        assert!(compressed.len() < 100);
+
+        assert_eq!(decompress_gzip(&compressed, 200).unwrap().len(), 200);
+        let error = decompress_gzip(&compressed, 100).unwrap_err().to_string();
+        assert!(error.contains("decompressed size exceeds 100 bytes"));
+
+        // Truncated streams are reported, not passed through
+        assert!(decompress_gzip(&compressed[..10], 200).is_err());
     }
 
     #[test]
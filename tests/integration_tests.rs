@@ -2,7 +2,8 @@
 //!
 //! Tests real workflows and edge cases that span multiple components.
 
-use joe::{Memory, MemoryError, constants};
+use joe::{Cpu, Display, Emulator, Memory, MemoryError, MockInput, constants, decode_opcode};
+use std::time::Duration;
 
 #[test]
 fn test_complete_rom_loading_workflow() {
@@ -44,3 +45,105 @@ fn test_write_protection_with_reset() {
     // But font should be reloaded correctly
     assert_eq!(memory.get_font_sprite(0).unwrap().len(), 5);
 }
+
+/// Minimal xorshift generator so the fuzz corpus is reproducible without extra deps
+struct XorShift(u64);
+
+impl XorShift {
+    fn next(&mut self) -> u64 {
+        self.0 ^= self.0 << 13;
+        self.0 ^= self.0 >> 7;
+        self.0 ^= self.0 << 17;
+        self.0
+    }
+}
+
+/// Step a ROM for a bounded number of cycles, stopping at the first error
+fn run_bounded(rom: &[u8], cycles: usize, rng: &mut XorShift) {
+    let mut memory = Memory::new(true);
+    memory.load_rom(rom).unwrap();
+    let mut display = Display::new();
+    let mut input = MockInput::new();
+    let mut cpu = Cpu::new();
+
+    for _ in 0..cycles {
+        // Occasionally press a key so FX0A and EX9E/EXA1 see input
+        if rng.next().is_multiple_of(16) {
+            input.press_key((rng.next() % 16) as u8).unwrap();
+        }
+
+        // Malformed ROMs may fail, but must never panic
+        if cpu
+            .execute_cycle(&mut memory, &mut display, &mut input)
+            .is_err()
+        {
+            break;
+        }
+    }
+}
+
+#[test]
+fn test_random_roms_never_panic() {
+    let rom_size = constants::MEMORY_SIZE - constants::PROGRAM_START_ADDR as usize;
+
+    for seed in 1..=300u64 {
+        let mut rng = XorShift(seed.wrapping_mul(0x9E37_79B9_7F4A_7C15));
+
+        // Mostly decodable words so runs get past the first few instructions
+        let rom: Vec<u8> = (0..rom_size / 2)
+            .flat_map(|_| {
+                loop {
+                    let word = rng.next() as u16;
+                    if word != 0x00EE && decode_opcode(word).is_ok() {
+                        break word.to_be_bytes();
+                    }
+                }
+            })
+            .collect();
+
+        run_bounded(&rom, 2000, &mut rng);
+    }
+}
+
+#[test]
+fn test_edge_case_roms_never_panic() {
+    let roms: [&[u8]; 4] = [
+        // V0 = 0xFF; loop: I += V0 (would overflow a 16-bit index)
+        &[0x60, 0xFF, 0xF0, 0x1E, 0x12, 0x02],
+        // I = 0xFFF; draw 15 rows reading past the end of memory
+        &[0xAF, 0xFF, 0xD0, 0x1F],
+        // V0 = 0xFF; jump to 0xFFF + V0 (past the end of memory)
+        &[0x60, 0xFF, 0xBF, 0xFF],
+        // Grow I, then draw from wherever it ends up
+        &[0x60, 0xFF, 0xF0, 0x1E, 0xD0, 0x1F, 0x12, 0x02],
+    ];
+
+    let mut rng = XorShift(1);
+    for rom in roms {
+        run_bounded(rom, 5000, &mut rng);
+    }
+}
+
+#[test]
+fn test_delay_timer_counts_down_at_60hz() {
+    let mut emulator = Emulator::with_defaults();
+
+    // LD V0, 30; LD DT, V0; JP 0x204 (spin)
+    emulator
+        .load_rom(&[0x60, 0x1E, 0xF0, 0x15, 0x12, 0x04])
+        .unwrap();
+    emulator.step().unwrap();
+    emulator.step().unwrap();
+    assert_eq!(emulator.cpu().get_delay_timer(), 30);
+
+    // Simulate 500 cycles at 1ms each: 0.5s is 30 ticks at 60Hz
+    let cycle_time = Duration::from_millis(1);
+    for cycle in 1..=500 {
+        emulator.step().unwrap();
+        emulator.advance_timers(cycle_time);
+        if cycle == 250 {
+            assert_eq!(emulator.cpu().get_delay_timer(), 15);
+        }
+    }
+    assert_eq!(emulator.cpu().get_delay_timer(), 0);
+}
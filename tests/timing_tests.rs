@@ -0,0 +1,159 @@
+//! Timing tests for the JOE CHIP-8 emulator
+//!
+//! Drives the emulation loop with a [`MockClock`] so the 60Hz timer and
+//! render pacing contracts can be checked deterministically.
+
+use joe::audio::TIMER_TICK;
+use joe::{Clock, Emulator, EmulatorConfig, FrameLimiter, MockClock};
+use std::time::Duration;
+
+/// LD V0, 30; LD DT, V0; JP 0x204 (spin)
+const SET_DELAY_ROM: [u8; 6] = [0x60, 0x1E, 0xF0, 0x15, 0x12, 0x04];
+
+/// Run the delay timer ROM headless for `cycles`, with each cycle taking `cycle_time`
+fn run_delay_rom(cycles: usize, cycle_time: Duration) -> u8 {
+    let config = EmulatorConfig {
+        max_cycles: cycles,
+        cycle_delay_ms: 0,
+        ..Default::default()
+    };
+    let mut emulator = Emulator::new(config);
+    emulator.set_clock(Box::new(MockClock::with_auto_advance(cycle_time)));
+    emulator.load_rom(&SET_DELAY_ROM).unwrap();
+    emulator.run_headless().unwrap();
+    emulator.cpu().get_delay_timer()
+}
+
+#[test]
+fn test_timers_tick_once_per_sixtieth_of_a_second() {
+    // One tick of simulated time per cycle: the timer is set on cycle 2 and
+    // every later cycle takes exactly one tick off it
+    assert_eq!(run_delay_rom(2, TIMER_TICK), 30);
+    assert_eq!(run_delay_rom(12, TIMER_TICK), 20);
+    assert_eq!(run_delay_rom(32, TIMER_TICK), 0);
+}
+
+#[test]
+fn test_timers_independent_of_cycle_rate() {
+    // Two cycles per tick: half as many ticks over the same number of cycles
+    assert_eq!(run_delay_rom(42, TIMER_TICK / 2), 10);
+
+    // 1ms cycles: 0.25s of simulated time is 15 ticks, 0.5s drains all 30
+    assert_eq!(run_delay_rom(251, Duration::from_millis(1)), 15);
+    assert_eq!(run_delay_rom(502, Duration::from_millis(1)), 0);
+}
+
+/// Run the delay timer ROM headless in the frame model for `cycles`
+///
+/// Clock time races ahead of the frames so the loop never sleeps; the timers
+/// only tick when a frame's cycles are done.
+fn run_paced_delay_rom(cycles: usize, config: EmulatorConfig) -> u8 {
+    let mut emulator = Emulator::new(EmulatorConfig {
+        max_cycles: cycles,
+        cycle_delay_ms: 0,
+        ..config
+    });
+    emulator.set_clock(Box::new(MockClock::with_auto_advance(TIMER_TICK)));
+    emulator.load_rom(&SET_DELAY_ROM).unwrap();
+    emulator.run_headless().unwrap();
+    emulator.cpu().get_delay_timer()
+}
+
+#[test]
+fn test_frames_run_cpu_hz_over_timer_hz_cycles_per_tick() {
+    let paced = |cpu_hz, timer_hz, cycles| {
+        run_paced_delay_rom(
+            cycles,
+            EmulatorConfig {
+                cpu_hz,
+                timer_hz,
+                pace_by_cpu_hz: true,
+                ..Default::default()
+            },
+        )
+    };
+    let per_frame = |cycles_per_frame, cycles| {
+        run_paced_delay_rom(
+            cycles,
+            EmulatorConfig {
+                cycles_per_frame,
+                ..Default::default()
+            },
+        )
+    };
+
+    // 600Hz/60Hz: 10 cycles per tick. The first tick closes the frame after
+    // cycle 10, so a run that stops on cycle 10 never reaches it
+    assert_eq!(paced(600, 60, 10), 30);
+    assert_eq!(paced(600, 60, 11), 29);
+    assert_eq!(paced(600, 60, 102), 20);
+    // A fixed cycles_per_frame of cpu_hz/60 is the same schedule
+    assert_eq!(per_frame(600 / 60, 102), 20);
+
+    // Doubling cpu_hz halves the ticks over the same cycles
+    assert_eq!(paced(1200, 60, 102), 25);
+    assert_eq!(per_frame(1200 / 60, 102), 25);
+
+    // timer_hz sets the divisor: 500Hz/50Hz is again 10 cycles per tick
+    assert_eq!(paced(500, 50, 102), 20);
+
+    // Uneven rates spread the remainder: 500Hz/60Hz runs 8, 8, 9, ...
+    assert_eq!(paced(500, 60, 26), 27);
+}
+
+#[test]
+fn test_frame_limiter_respects_refresh_rate() {
+    let clock = MockClock::new();
+    let mut limiter = FrameLimiter::new(16);
+
+    // The first frame always renders, then redraws wait for the refresh interval
+    assert!(limiter.should_render(clock.now()));
+    clock.advance(Duration::from_millis(10));
+    assert!(!limiter.should_render(clock.now()));
+    clock.advance(Duration::from_millis(6));
+    assert!(limiter.should_render(clock.now()));
+
+    // Skipped frames don't push the next one back
+    clock.advance(Duration::from_millis(8));
+    assert!(!limiter.should_render(clock.now()));
+    clock.advance(Duration::from_millis(8));
+    assert!(limiter.should_render(clock.now()));
+
+    // Asking every 1ms for a second renders one frame per 16ms
+    let mut limiter = FrameLimiter::new(16);
+    let frames = (0..1000)
+        .filter(|_| {
+            clock.advance(Duration::from_millis(1));
+            limiter.should_render(clock.now())
+        })
+        .count();
+    assert_eq!(frames, 63);
+}
+
+#[test]
+fn test_frame_callback_runs_once_per_frame() {
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    let config = EmulatorConfig {
+        max_cycles: 40,
+        cycle_delay_ms: 0,
+        ..Default::default()
+    };
+    let mut emulator = Emulator::new(config);
+    // 5ms cycles: a 60Hz frame is due every 4th cycle
+    emulator.set_clock(Box::new(MockClock::with_auto_advance(
+        Duration::from_millis(5),
+    )));
+    emulator.load_rom(&SET_DELAY_ROM).unwrap();
+
+    let frames = Rc::new(Cell::new(0));
+    let counter = frames.clone();
+    emulator.on_frame(Box::new(move |display| {
+        assert_eq!(display.get_stats().pixels_on, 0);
+        counter.set(counter.get() + 1);
+    }));
+    emulator.run_headless().unwrap();
+
+    assert_eq!(frames.get(), 10);
+}
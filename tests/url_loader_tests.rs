@@ -0,0 +1,187 @@
+//! URL loader tests for the JOE CHIP-8 emulator
+//!
+//! Serves ROMs, errors and web pages from a minimal HTTP server on a local
+//! port, so the download path (retries, size limit, content type,
+//! redirects) runs end to end without touching the network.
+
+use joe::{RomLoaderConfig, load_rom_data, load_rom_data_with_config};
+use std::io::{BufRead, BufReader, Write};
+use std::net::{TcpListener, TcpStream};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// CLS; JP 0x200
+const ROM: [u8; 4] = [0x00, 0xE0, 0x12, 0x00];
+
+/// Start a server on an ephemeral port, returning its base URL
+///
+/// `/flaky.ch8` answers 503 to the first request and serves the ROM after
+/// that; `/moved.ch8` redirects to `/rom.ch8` on the same host,
+/// `/elsewhere.ch8` to `/rom.ch8` on `localhost` and `/loop.ch8` to itself.
+/// `requests` counts every request served.
+fn spawn_server(requests: Arc<AtomicUsize>) -> String {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let base_url = format!("http://{}", listener.local_addr().unwrap());
+    let flaky_hits = AtomicUsize::new(0);
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { continue };
+            requests.fetch_add(1, Ordering::SeqCst);
+            serve(stream, &flaky_hits);
+        }
+    });
+    base_url
+}
+
+/// Answer one request with `Connection: close`
+fn serve(mut stream: TcpStream, flaky_hits: &AtomicUsize) {
+    let mut reader = BufReader::new(stream.try_clone().unwrap());
+    let mut request_line = String::new();
+    reader.read_line(&mut request_line).unwrap();
+    let path = request_line.split_whitespace().nth(1).unwrap_or("/");
+    let port = stream.local_addr().unwrap().port();
+    // Skip the headers
+    let mut line = String::new();
+    while reader.read_line(&mut line).unwrap() > 2 {
+        line.clear();
+    }
+
+    let location = match path {
+        "/moved.ch8" => Some(format!("http://127.0.0.1:{}/rom.ch8", port)),
+        "/elsewhere.ch8" => Some(format!("http://localhost:{}/rom.ch8", port)),
+        "/loop.ch8" => Some("/loop.ch8".to_string()),
+        _ => None,
+    };
+    if let Some(location) = location {
+        let header = format!(
+            "HTTP/1.1 302 Found\r\nLocation: {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            location
+        );
+        let _ = stream.write_all(header.as_bytes());
+        return;
+    }
+
+    let (status, content_type, body): (&str, &str, Vec<u8>) = match path {
+        "/rom.ch8" => ("200 OK", "application/octet-stream", ROM.to_vec()),
+        "/big.ch8" => ("200 OK", "application/octet-stream", vec![0x12; 8192]),
+        "/page.ch8" => (
+            "200 OK",
+            "text/html; charset=utf-8",
+            b"<html><body>Download here</body></html>".to_vec(),
+        ),
+        "/flaky.ch8" if flaky_hits.fetch_add(1, Ordering::SeqCst) == 0 => {
+            ("503 Service Unavailable", "text/plain", b"busy".to_vec())
+        }
+        "/flaky.ch8" => ("200 OK", "application/octet-stream", ROM.to_vec()),
+        _ => ("404 Not Found", "text/plain", b"not found".to_vec()),
+    };
+    let header = format!(
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+        status,
+        content_type,
+        body.len()
+    );
+    let _ = stream.write_all(header.as_bytes());
+    let _ = stream.write_all(&body);
+}
+
+/// Loader config that retries quickly
+fn fast_retry_config() -> RomLoaderConfig {
+    RomLoaderConfig {
+        retry_backoff: Duration::from_millis(1),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_url_loader_serves_rom() {
+    let base_url = spawn_server(Arc::new(AtomicUsize::new(0)));
+    let rom = load_rom_data(&format!("{}/rom.ch8", base_url)).unwrap();
+    assert_eq!(rom, ROM);
+}
+
+#[test]
+fn test_url_loader_not_found_is_not_retried() {
+    let requests = Arc::new(AtomicUsize::new(0));
+    let base_url = spawn_server(requests.clone());
+    let err = load_rom_data_with_config(&format!("{}/missing.ch8", base_url), &fast_retry_config())
+        .unwrap_err();
+    let message = format!("{:#}", err);
+    assert!(message.contains("404"), "{}", message);
+    assert!(message.contains("after 1 attempt"), "{}", message);
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_url_loader_rejects_oversized_rom() {
+    let base_url = spawn_server(Arc::new(AtomicUsize::new(0)));
+    let err = load_rom_data(&format!("{}/big.ch8", base_url)).unwrap_err();
+    assert!(format!("{:#}", err).contains("ROM too large"), "{:#}", err);
+}
+
+#[test]
+fn test_url_loader_rejects_html_page() {
+    let base_url = spawn_server(Arc::new(AtomicUsize::new(0)));
+    let err = load_rom_data(&format!("{}/page.ch8", base_url)).unwrap_err();
+    assert!(format!("{:#}", err).contains("web page"), "{:#}", err);
+}
+
+#[test]
+fn test_url_loader_retries_server_errors() {
+    let requests = Arc::new(AtomicUsize::new(0));
+    let base_url = spawn_server(requests.clone());
+    let rom = load_rom_data_with_config(&format!("{}/flaky.ch8", base_url), &fast_retry_config())
+        .unwrap();
+    assert_eq!(rom, ROM);
+    assert_eq!(requests.load(Ordering::SeqCst), 2);
+}
+
+/// Loader config that only downloads from the test server's IP address
+fn local_only_config() -> RomLoaderConfig {
+    RomLoaderConfig {
+        allowed_hosts: Some(vec!["127.0.0.1".to_string()]),
+        ..fast_retry_config()
+    }
+}
+
+#[test]
+fn test_url_loader_follows_redirect_to_allowed_host() {
+    let base_url = spawn_server(Arc::new(AtomicUsize::new(0)));
+    let rom = load_rom_data_with_config(&format!("{}/moved.ch8", base_url), &local_only_config())
+        .unwrap();
+    assert_eq!(rom, ROM);
+}
+
+#[test]
+fn test_url_loader_rejects_redirect_to_other_host() {
+    let requests = Arc::new(AtomicUsize::new(0));
+    let base_url = spawn_server(requests.clone());
+    let err =
+        load_rom_data_with_config(&format!("{}/elsewhere.ch8", base_url), &local_only_config())
+            .unwrap_err();
+    let message = format!("{:#}", err);
+    assert!(
+        message.contains("Host 'localhost' is not in the allowed hosts list"),
+        "{}",
+        message
+    );
+    // The redirect target is never requested
+    assert_eq!(requests.load(Ordering::SeqCst), 1);
+}
+
+#[test]
+fn test_url_loader_reports_too_many_redirects() {
+    let base_url = spawn_server(Arc::new(AtomicUsize::new(0)));
+    let config = RomLoaderConfig {
+        max_redirects: 2,
+        ..fast_retry_config()
+    };
+    let err = load_rom_data_with_config(&format!("{}/loop.ch8", base_url), &config).unwrap_err();
+    let message = format!("{:#}", err);
+    assert!(
+        message.contains("Too many redirects (max: 2)"),
+        "{}",
+        message
+    );
+}